@@ -0,0 +1,183 @@
+//! Differential fuzz target for `compute_bounded_counts`: cross-checks its bookkeeping against
+//! independent re-derivations from the same primitives it's built on, instead of just asserting it
+//! doesn't panic.
+//!
+//! Not wired into a buildable `cargo fuzz` crate yet — that needs a `fuzz/Cargo.toml` declaring
+//! `libfuzzer-sys` and `arbitrary`, plus a path dependency on the root crate with `features =
+//! ["fuzz"]`, and a matching `fuzz` feature in the root crate's own manifest. Neither exists in
+//! this tree (it has no manifest at all); this file is the harness logic as it should exist once
+//! that wiring lands.
+
+#![no_main]
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::{fuzz_target, Corpus};
+use rustc_hash::FxHashSet;
+
+use infinite_chess::core::coord::Coord;
+use infinite_chess::fuzz::FuzzScenarioInput;
+use infinite_chess::scenario::{CandidateGeneration, State};
+use infinite_chess::search::bounded::compute_bounded_counts;
+use infinite_chess::search::forced_mate::forced_mate_bounded;
+use infinite_chess::search::movegen::{legal_black_moves, legal_white_moves};
+use infinite_chess::search::resources::ResourceTracker;
+use infinite_chess::search::trap::{maximal_inescapable_trap, maximal_tempo_trap};
+use infinite_chess::search::universe::try_for_each_state_in_abs_box;
+
+fuzz_target!(|data: &[u8]| -> Corpus {
+    let mut u = Unstructured::new(data);
+    let input = match FuzzScenarioInput::arbitrary(&mut u) {
+        Ok(input) => input,
+        Err(_) => return Corpus::Reject,
+    };
+
+    let scn = match input.build() {
+        Some(scn) => scn,
+        None => return Corpus::Reject,
+    };
+
+    if scn.validate().is_err() {
+        return Corpus::Reject;
+    }
+    let (bound, allow_captures) = match scn.candidates {
+        CandidateGeneration::InBox {
+            bound,
+            allow_captures,
+        } => (bound, allow_captures),
+        _ => return Corpus::Reject,
+    };
+
+    let counts = match compute_bounded_counts(&scn) {
+        Ok(c) => c,
+        Err(_) => return Corpus::Reject,
+    };
+
+    // Independently re-enumerate the same universe `compute_bounded_counts` used, applying the
+    // same legality/laws/domain filters it does, then recount black/white moves and checkmates
+    // from scratch by calling the same movegen primitives it calls internally.
+    let mut universe: FxHashSet<State> = FxHashSet::default();
+    try_for_each_state_in_abs_box(&scn.rules, bound, allow_captures, |s| {
+        if scn.rules.is_legal_position(&s.pos) && scn.laws.allow_state(&s) && scn.domain.inside(&s)
+        {
+            universe.insert(s);
+        }
+    });
+
+    let mut black_in = 0u64;
+    let mut black_escape = 0u64;
+    let mut white_in = 0u64;
+    let mut white_escape = 0u64;
+    let mut mates = 0usize;
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    for s in universe.iter() {
+        let in_check = scn.rules.is_attacked(Coord::ORIGIN, &s.pos);
+
+        let Ok(black_moves) = legal_black_moves(&scn, &scn.laws, s, &mut tracker) else {
+            return Corpus::Reject;
+        };
+        if in_check && black_moves.is_empty() {
+            mates += 1;
+        }
+        for to in &black_moves {
+            if universe.contains(to) {
+                black_in += 1;
+            } else {
+                black_escape += 1;
+            }
+        }
+
+        let Ok(white_moves) = legal_white_moves(&scn, &scn.laws, s, &mut tracker) else {
+            return Corpus::Reject;
+        };
+        for to in &white_moves {
+            if universe.contains(to) {
+                white_in += 1;
+            } else {
+                white_escape += 1;
+            }
+        }
+    }
+
+    assert_eq!(
+        universe.len(),
+        counts.universe_states,
+        "universe size mismatch for {:?}",
+        input
+    );
+    assert_eq!(
+        (black_in, black_escape),
+        (counts.black_moves_in, counts.black_moves_escape),
+        "black move accounting mismatch for {:?}",
+        input
+    );
+    assert_eq!(
+        (white_in, white_escape),
+        (counts.white_moves_in, counts.white_moves_escape),
+        "white move accounting mismatch for {:?}",
+        input
+    );
+    assert_eq!(
+        mates, counts.checkmates_in_universe,
+        "checkmate count mismatch for {:?}",
+        input
+    );
+
+    // `trap` (a maximal inescapable trap) and `tempo` (a maximal tempo trap *within* that trap)
+    // should nest: tempo can only be stronger, never larger. See the `lib.rs` doctest for the same
+    // invariant on the built-in scenarios.
+    let Ok(trap_set) = maximal_inescapable_trap(&scn) else {
+        return Corpus::Reject;
+    };
+    let Ok(tempo_set) = maximal_tempo_trap(&scn, &trap_set) else {
+        return Corpus::Reject;
+    };
+    assert!(
+        tempo_set.is_subset(&trap_set),
+        "tempo trap not contained in inescapable trap for {:?}",
+        input
+    );
+    assert_eq!(
+        trap_set.len(),
+        counts.trap,
+        "trap size mismatch for {:?}",
+        input
+    );
+    assert_eq!(
+        tempo_set.len(),
+        counts.tempo,
+        "tempo size mismatch for {:?}",
+        input
+    );
+
+    // Every winning black-to-move placement must genuinely be one White can force mate from: at
+    // minimum, it must still have a legal black move (an already-mated position can't be "won
+    // from", it's already over) and must lie inside the inescapable trap (forced mate implies
+    // White never loses control of the position).
+    let Ok(mate_region) = forced_mate_bounded(&scn, false, false) else {
+        return Corpus::Reject;
+    };
+    for s in &mate_region.winning_btm {
+        assert!(
+            trap_set.contains(s),
+            "forced-mate state outside the inescapable trap for {:?}",
+            input
+        );
+        let Ok(black_moves) = legal_black_moves(&scn, &scn.laws, s, &mut tracker) else {
+            return Corpus::Reject;
+        };
+        assert!(
+            !black_moves.is_empty(),
+            "forced-mate region contains an already-mated state for {:?}",
+            input
+        );
+    }
+    assert_eq!(
+        mate_region.winning_btm.len(),
+        counts.mate,
+        "mate region size mismatch for {:?}",
+        input
+    );
+
+    Corpus::Keep
+});
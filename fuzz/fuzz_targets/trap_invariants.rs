@@ -0,0 +1,106 @@
+//! Differential/invariant fuzz target for `maximal_inescapable_trap`: checks structural properties
+//! the solver must satisfy for any legal scenario, rather than replaying a fixed test position.
+//!
+//! Not wired into a buildable `cargo fuzz` crate yet — that needs a `fuzz/Cargo.toml` declaring
+//! `libfuzzer-sys` and `arbitrary`, plus a path dependency on the root crate with `features =
+//! ["fuzz"]`, and a matching `fuzz` feature in the root crate's own manifest. Neither exists in
+//! this tree (it has no manifest at all); this file is the harness logic as it should exist once
+//! that wiring lands.
+
+#![no_main]
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::{fuzz_target, Corpus};
+
+use infinite_chess::chess::bounds::is_in_bound;
+use infinite_chess::fuzz::{TrapFuzzInput, MAX_BOUND};
+use infinite_chess::scenario::CandidateGeneration;
+use infinite_chess::search::movegen::{legal_black_moves, legal_white_moves};
+use infinite_chess::search::resources::ResourceTracker;
+use infinite_chess::search::trap::maximal_inescapable_trap;
+
+fuzz_target!(|data: &[u8]| -> Corpus {
+    let mut u = Unstructured::new(data);
+    let input = match TrapFuzzInput::arbitrary(&mut u) {
+        Ok(input) => input,
+        Err(_) => return Corpus::Reject,
+    };
+
+    let scn = match input.build() {
+        Some(scn) => scn,
+        None => return Corpus::Reject,
+    };
+    if scn.validate().is_err() {
+        return Corpus::Reject;
+    }
+
+    let Ok(trap) = maximal_inescapable_trap(&scn) else {
+        return Corpus::Reject;
+    };
+
+    // Invariant 1: fixpoint closure. A maximal inescapable trap must already be closed under White
+    // choosing the best reply — every black member must have at least one black move whose every
+    // white reply stays in the trap.
+    let mut tracker = ResourceTracker::new(scn.limits);
+    for p in trap.iter() {
+        let Ok(black_moves) = legal_black_moves(&scn, &scn.laws, p, &mut tracker) else {
+            return Corpus::Reject;
+        };
+        let closed = black_moves.iter().any(|w| {
+            let Ok(white_moves) = legal_white_moves(&scn, &scn.laws, w, &mut tracker) else {
+                return false;
+            };
+            !white_moves.is_empty() && white_moves.iter().all(|q| trap.contains(q))
+        });
+        assert!(
+            closed,
+            "trap member with no move closed under White's replies for {:?}",
+            input
+        );
+    }
+
+    // Invariant 2: idempotence. Re-running the solver from the trap itself (via `FromStates`)
+    // must reproduce exactly the same set — a fixpoint re-fed to the fixpoint computation doesn't
+    // shrink or grow.
+    let mut rebuilt_scn = scn.clone();
+    rebuilt_scn.candidates = CandidateGeneration::FromStates {
+        states: trap.iter().cloned().collect(),
+    };
+    let Ok(rebuilt) = maximal_inescapable_trap(&rebuilt_scn) else {
+        return Corpus::Reject;
+    };
+    assert_eq!(
+        rebuilt, trap,
+        "re-solving from the trap itself did not reproduce it for {:?}",
+        input
+    );
+
+    // Invariant 3: monotonicity. Enlarging the candidate bound can only ever grow the trap:
+    // restricting the larger trap back down to the original bound must still contain everything
+    // the smaller trap found.
+    if input.bound < MAX_BOUND {
+        if let Some(larger_scn) = input.build_at(input.bound + 1) {
+            if larger_scn.validate().is_ok() {
+                if let Ok(larger_trap) = maximal_inescapable_trap(&larger_scn) {
+                    let restricted: Vec<_> = larger_trap
+                        .iter()
+                        .filter(|s| {
+                            (0..s.pos.count()).all(|i| is_in_bound(s.pos.square(i), input.bound))
+                        })
+                        .collect();
+                    for p in trap.iter() {
+                        assert!(
+                            restricted.iter().any(|&q| q == p),
+                            "trap at bound {} not contained in trap at bound {} for {:?}",
+                            input.bound,
+                            input.bound + 1,
+                            input
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Corpus::Keep
+});
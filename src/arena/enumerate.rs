@@ -1,5 +1,6 @@
 use crate::game::Game;
 use crate::pieces::PieceKind;
+use crate::region::Bitset;
 use crate::state::{canonicalize, PackedState};
 
 /// Enumerate all legal packed states inside the region for the game's material.
@@ -14,20 +15,13 @@ pub fn all_states(game: &Game) -> Vec<PackedState> {
     let layout = &game.layout;
 
     let mut whites: Vec<u16> = vec![cap; layout.total_white()];
-    let mut used: Vec<bool> = vec![false; n];
-
     let mut out: Vec<PackedState> = Vec::new();
 
     for bk_sq in 0..(n as u16) {
-        // reset occupancy
-        for u in &mut used {
-            *u = false;
-        }
-        used[bk_sq as usize] = true;
+        let mut used = Bitset::new(n);
+        used.set(bk_sq);
 
         rec_group(0, bk_sq, game, &mut whites, &mut used, &mut out);
-
-        used[bk_sq as usize] = false;
     }
 
     out
@@ -38,7 +32,7 @@ fn rec_group(
     bk_sq: u16,
     game: &Game,
     whites: &mut [u16],
-    used: &mut [bool],
+    used: &mut Bitset,
     out: &mut Vec<PackedState>,
 ) {
     let layout = &game.layout;
@@ -58,19 +52,18 @@ fn rec_group(
 
     // Compute free squares.
     let mut free: Vec<u16> = Vec::new();
-    for (sq, &is_used) in used.iter().enumerate() {
-        if is_used {
+    for sq in 0..game.region_size() {
+        if used.get(sq) {
             continue;
         }
-        let sq_u16 = sq as u16;
         if g.kind == PieceKind::King {
             let bk_c = game.region.coord_of(bk_sq);
-            let c = game.region.coord_of(sq_u16);
+            let c = game.region.coord_of(sq);
             if (c.x - bk_c.x).abs() <= 1 && (c.y - bk_c.y).abs() <= 1 {
                 continue;
             }
         }
-        free.push(sq_u16);
+        free.push(sq);
     }
 
     let min_alive = if g.kind == PieceKind::King { len } else { 0 };
@@ -84,13 +77,13 @@ fn rec_group(
             }
             for (i, &sq) in chosen.iter().enumerate() {
                 whites[start + i] = sq;
-                used[sq as usize] = true;
+                used.set(sq);
             }
 
             rec_group(g_idx + 1, bk_sq, game, whites, used, out);
 
             for &sq in chosen {
-                used[sq as usize] = false;
+                used.clear(sq);
             }
         });
     }
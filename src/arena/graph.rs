@@ -3,6 +3,8 @@ use crate::game::Game;
 use crate::pieces::Turn;
 use crate::rules::movegen::{self, Scratch, Succ};
 use crate::state::PackedState;
+use crate::zobrist;
+use rayon::prelude::*;
 use std::collections::HashMap;
 
 pub type NodeId = usize;
@@ -22,6 +24,11 @@ pub struct Arena {
     pub nodes: Vec<Node>,
     pub sink_black: NodeId,
     pub sink_white: NodeId,
+    /// Zobrist hash (see [`ZobristKeys::hash_node`](crate::zobrist::ZobristKeys::hash_node)) of
+    /// every non-sink node's `(state, turn)`, to `NodeId`s sharing that hash. Almost always a
+    /// single-element `Vec`; a collision just means [`Arena::find_node`] checks a couple of
+    /// candidates instead of one.
+    by_hash: HashMap<u64, Vec<NodeId>>,
 }
 
 impl Arena {
@@ -32,15 +39,48 @@ impl Arena {
     pub fn is_sink(&self, id: NodeId) -> bool {
         id == self.sink_black || id == self.sink_white
     }
+
+    /// Look up the node for `(state, turn)`, replacing a linear `nodes.iter().find(...)` scan with
+    /// an O(1) (amortized) hash lookup plus a full-equality check on any collision.
+    pub fn find_node(&self, state: PackedState, turn: Turn) -> Option<NodeId> {
+        let hash = self.game.zobrist.hash_node(state, turn);
+        self.by_hash.get(&hash)?.iter().copied().find(|&id| {
+            let node = &self.nodes[id];
+            node.turn == turn && node.state == Some(state)
+        })
+    }
 }
 
 pub struct ArenaBuilder {
     pub game: Game,
+    /// Worker thread count for `enumerate_all`'s parallel successor phase. `0` (the default) lets
+    /// rayon pick its own default (the number of logical CPUs); any other value runs that phase on
+    /// a dedicated pool of exactly that size, so a caller enforcing its own CPU budget can cap it.
+    pub threads: usize,
 }
 
 impl ArenaBuilder {
     pub fn new(game: Game) -> Self {
-        Self { game }
+        Self { game, threads: 0 }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Run `f` on the default rayon pool, or on a dedicated pool sized to `self.threads` when it's
+    /// nonzero.
+    fn run_parallel<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        if self.threads == 0 {
+            f()
+        } else {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .build()
+                .expect("failed to build arena enumeration thread pool")
+                .install(f)
+        }
     }
 
     /// Enumerate all legal states in the region and build the full turn-based game graph.
@@ -48,10 +88,29 @@ impl ArenaBuilder {
         let game = self.game.clone();
         let states = enumerate::all_states(&game);
 
-        let mut state_to_index: HashMap<PackedState, usize> = HashMap::with_capacity(states.len());
-        for (i, st) in states.iter().enumerate() {
-            state_to_index.insert(*st, i);
+        // Kind-keyed incremental hash of each state (see `zobrist::piece_key`), computed once per
+        // state here rather than once per edge below: `movegen::successors_hashed` derives every
+        // successor's hash from its parent's in O(1), so this is the only full recompute needed.
+        let state_hash: Vec<u64> = states
+            .iter()
+            .map(|st| game.zobrist.hash_packed_incremental(*st))
+            .collect();
+
+        // Bucketed by hash (rather than a `HashMap<PackedState, usize>` keyed on the full state)
+        // so a successor's incrementally-derived hash resolves its index without ever re-hashing
+        // the packed state itself; a same-hash bucket with more than one entry just means the
+        // equality check below examines a couple of candidates instead of one.
+        let mut state_index: HashMap<u64, Vec<usize>> = HashMap::with_capacity(states.len());
+        for (i, &h) in state_hash.iter().enumerate() {
+            state_index.entry(h).or_default().push(i);
         }
+        let find_index = |probe: PackedState, hash: u64| -> Option<usize> {
+            state_index
+                .get(&hash)?
+                .iter()
+                .copied()
+                .find(|&i| states[i] == probe)
+        };
 
         let sink_black: NodeId = 0;
         let sink_white: NodeId = 1;
@@ -86,56 +145,100 @@ impl ArenaBuilder {
             });
         }
 
-        let mut scratch = Scratch::new(game.layout.total_white());
-
-        // Fill edges.
-        for id in 2..nodes.len() {
-            let turn = nodes[id].turn;
-            let st = nodes[id].state.expect("non-sink nodes have a state");
-
-            let succs = movegen::successors(&game, turn, st, &mut scratch);
-            let mut succ_ids: Vec<NodeId> = Vec::with_capacity(succs.len());
-
-            for s in succs {
-                match s {
-                    Succ::Sink => {
-                        let sink = match turn {
-                            Turn::Black => sink_white,
-                            Turn::White => sink_black,
-                        };
-                        succ_ids.push(sink);
-                    }
-                    Succ::State(next_state) => {
-                        let next_turn = turn.other();
-                        let idx = *state_to_index
-                            .get(&next_state)
-                            .unwrap_or_else(|| panic!("successor state missing from enumeration: {next_state}"));
-                        let base = 2 + 2 * idx;
-                        let next_id = match next_turn {
-                            Turn::Black => base,
-                            Turn::White => base + 1,
-                        };
-                        succ_ids.push(next_id);
+        // Phase 1: each node's successor set depends only on `game` (immutable) and `state_index`
+        // (read-only once built above), so it's embarrassingly parallel — run it across `threads`
+        // workers, each with its own `Scratch` (a node's `movegen::successors_hashed` call mutates
+        // its scratch buffer, so sharing one across workers would race). Successor lists are
+        // sorted/deduped here too, so the graph is identical regardless of how work was scheduled.
+        let succ_lists: Vec<Vec<NodeId>> = self.run_parallel(|| {
+            (2..nodes.len())
+                .into_par_iter()
+                .map(|id| {
+                    let turn = nodes[id].turn;
+                    let st = nodes[id].state.expect("non-sink nodes have a state");
+                    let idx = (id - 2) / 2;
+                    let hash = state_hash[idx];
+
+                    let mut scratch = Scratch::new(game.layout.total_white());
+                    let succs = movegen::successors_hashed(&game, turn, st, hash, &mut scratch);
+                    let mut succ_ids: Vec<NodeId> = Vec::with_capacity(succs.len());
+
+                    for (s, next_hash) in succs {
+                        match s {
+                            Succ::Sink => {
+                                let sink = match turn {
+                                    Turn::Black => sink_white,
+                                    Turn::White => sink_black,
+                                };
+                                succ_ids.push(sink);
+                            }
+                            Succ::State(next_state) => {
+                                let next_turn = turn.other();
+                                let next_idx =
+                                    find_index(next_state, next_hash).unwrap_or_else(|| {
+                                        panic!(
+                                            "successor state missing from enumeration: {next_state}"
+                                        )
+                                    });
+                                let base = 2 + 2 * next_idx;
+                                let next_id = match next_turn {
+                                    Turn::Black => base,
+                                    Turn::White => base + 1,
+                                };
+                                succ_ids.push(next_id);
+                            }
+                        }
                     }
-                }
-            }
 
-            // Determinise the successor list.
-            succ_ids.sort_unstable();
-            succ_ids.dedup();
+                    succ_ids.sort_unstable();
+                    succ_ids.dedup();
+                    succ_ids
+                })
+                .collect()
+        });
 
-            nodes[id].succ = succ_ids.clone();
+        for (offset, succ_ids) in succ_lists.into_iter().enumerate() {
+            nodes[2 + offset].succ = succ_ids;
+        }
 
-            for s in succ_ids {
+        // Phase 2: build `pred` lists with a single sequential pass, since concurrent pushes into
+        // shared `nodes[s].pred` vectors would race.
+        for id in 2..nodes.len() {
+            for s in nodes[id].succ.clone() {
                 nodes[s].pred.push(id);
             }
         }
 
+        // One `ZobristKeys::hash_packed` unpack per state, not per node: the Black and White nodes
+        // for a state (ids `2 + 2*i` and `2 + 2*i + 1`) share the same underlying position, so the
+        // only thing that differs between their hashes is `zobrist::side_key`. A true per-move
+        // incremental update (XOR out the moved piece's old field key, XOR in the new one) isn't
+        // safe to do here in general: `state::canonicalize` can reorder *other* same-kind slots
+        // when a move changes their relative sort order, and `zobrist::field_key` is keyed by
+        // field index, so a single piece's own move doesn't pin down which keys changed for the
+        // rest of its kind group. Reusing the base hash across a state's two nodes sidesteps that
+        // ambiguity entirely while still cutting the number of full unpacks in half.
+        let mut by_hash: HashMap<u64, Vec<NodeId>> = HashMap::with_capacity(nodes.len());
+        for (i, st) in states.iter().enumerate() {
+            let base = game.zobrist.hash_packed(*st);
+            let black_id = 2 + 2 * i;
+            let white_id = black_id + 1;
+            by_hash
+                .entry(base ^ zobrist::side_key(Turn::Black))
+                .or_default()
+                .push(black_id);
+            by_hash
+                .entry(base ^ zobrist::side_key(Turn::White))
+                .or_default()
+                .push(white_id);
+        }
+
         Arena {
             game,
             nodes,
             sink_black,
             sink_white,
+            by_hash,
         }
     }
 }
@@ -0,0 +1,64 @@
+use infinite_chess::pieces::Material;
+use infinite_chess::solve::anneal::{anneal, AnnealConfig, Config, RegionShape};
+
+/// Find the cheapest material that forces mate of a lone black king inside a fixed-shape region,
+/// via simulated annealing over `(Region, Material)`. Example: "what is the smallest material
+/// that mates a lone king inside an L∞ ball of radius 2?" is `anneal_search linf 2`.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("Usage: anneal_search <linf|l1|knight> <radius> [seconds]");
+        std::process::exit(2);
+    }
+
+    let shape = match args[1].as_str() {
+        "linf" => RegionShape::LInf,
+        "l1" => RegionShape::L1,
+        "knight" => RegionShape::KnightDistance,
+        other => {
+            eprintln!("Unknown shape '{other}'; expected linf, l1 or knight.");
+            std::process::exit(2);
+        }
+    };
+
+    let radius: i16 = match args[2].parse() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Invalid radius '{}': {e}", args[2]);
+            std::process::exit(2);
+        }
+    };
+
+    let seconds: u64 = args
+        .get(3)
+        .map_or(Ok(10), |s| s.parse())
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid seconds '{}': {e}", args[3]);
+            std::process::exit(2);
+        });
+
+    let mut anneal_cfg = AnnealConfig::default();
+    anneal_cfg.time_limit = std::time::Duration::from_secs(seconds);
+
+    // Start from one queen: always a legal (if not necessarily feasible) starting point, and
+    // cheap enough that early proposals explore quickly.
+    let start = Config::new(shape, radius, Material::new().with_queens(1));
+
+    match anneal(start, &anneal_cfg) {
+        Some(best) => {
+            let m = &best.config.material;
+            println!("Best feasible configuration (cost {:.3}):", best.cost);
+            println!(
+                "  region: {:?} radius {}",
+                best.config.shape, best.config.radius
+            );
+            println!(
+                "  material: queens={} rooks={} bishops={} knights={}",
+                m.queens, m.rooks, m.bishops, m.knights
+            );
+        }
+        None => {
+            println!("No feasible configuration found within the time limit.");
+        }
+    }
+}
@@ -7,7 +7,7 @@ use infinite_chess::core::position::{Position, MAX_PIECES};
 use infinite_chess::core::square::Square;
 use infinite_chess::scenario::{
     CacheMode, CandidateGeneration, NoLaws, NoPreferences, ResourceLimits, Scenario, Side,
-    StartState, State,
+    StartState, State, TieBreak,
 };
 use infinite_chess::scenarios::BuiltinDomain;
 use infinite_chess::search::bounded::compute_bounded_counts;
@@ -109,6 +109,7 @@ fn build_scenario(
         domain: BuiltinDomain::Box { bound },
         laws: NoLaws,
         preferences: NoPreferences,
+        tie_break: TieBreak::Forwards,
         limits: ResourceLimits::default(),
         cache_mode: CacheMode::BothBounded,
         remove_stalemates: spec.remove_stalemates,
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use infinite_chess::scenario::StartState;
 use infinite_chess::scenarios;
 use infinite_chess::solution::{export_bundle, ExportOptions};
 
@@ -7,7 +8,7 @@ fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 3 {
         eprintln!(
-            "Usage: export_solution <scenario> <out_dir> [--force] [--no-tempo] [--view-bound <B>]\n\nAvailable scenarios:\n  - {}",
+            "Usage: export_solution <scenario> <out_dir> [--force] [--no-tempo] [--view-bound <B>] [--start <text>]\n\nAvailable scenarios:\n  - {}",
             scenarios::available_names().join("\n  - ")
         );
         std::process::exit(2);
@@ -17,6 +18,7 @@ fn main() {
     let out_dir = Path::new(&args[2]);
 
     let mut opts = ExportOptions::default();
+    let mut start_text: Option<String> = None;
 
     let mut i = 3;
     while i < args.len() {
@@ -44,6 +46,14 @@ fn main() {
                 opts.view_bound = Some(b);
                 i += 2;
             }
+            "--start" => {
+                let Some(v) = args.get(i + 1) else {
+                    eprintln!("--start requires a position-text argument, e.g. \"k0,0 R3,-2 btm\"");
+                    std::process::exit(2);
+                };
+                start_text = Some(v.clone());
+                i += 2;
+            }
             x => {
                 eprintln!("Unknown option: {x}");
                 std::process::exit(2);
@@ -51,7 +61,7 @@ fn main() {
         }
     }
 
-    let scn = match scenarios::by_name(scenario_name) {
+    let mut scn = match scenarios::by_name(scenario_name) {
         Ok(Some(s)) => s,
         Ok(None) => {
             eprintln!(
@@ -66,6 +76,16 @@ fn main() {
         }
     };
 
+    if let Some(text) = start_text {
+        match StartState::from_text(&text, &scn.rules.layout) {
+            Ok(start) => scn.start = start,
+            Err(e) => {
+                eprintln!("Invalid --start {text:?}: {e}");
+                std::process::exit(2);
+            }
+        }
+    }
+
     if let Err(e) = scn.validate() {
         eprintln!("Invalid scenario {scenario_name}: {e}");
         std::process::exit(2);
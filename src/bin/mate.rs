@@ -1,9 +1,10 @@
 use infinite_chess::arena::ArenaBuilder;
 use infinite_chess::coord::Coord;
 use infinite_chess::game::Game;
-use infinite_chess::pieces::Material;
+use infinite_chess::pieces::{Material, Turn};
 use infinite_chess::region::Region;
 use infinite_chess::solve::reach::{checkmate_targets, reachability_white};
+use infinite_chess::solve::retrograde::{best_black_move, best_white_move, distance_to_mate, DRAW};
 
 fn main() {
     let region = Region::linf(2);
@@ -27,18 +28,31 @@ fn main() {
         Coord::new(0, 0),
         &[Some(Coord::new(-2, -2)), Some(Coord::new(2, 2))],
     );
-    // Node id in the arena: black nodes start at 2 and alternate (black, white).
-    // We can just look it up by scanning once here (small demo).
-    let init_id = arena
-        .nodes
-        .iter()
-        .enumerate()
-        .find(|(_, n)| n.state == Some(init) && n.turn == infinite_chess::pieces::Turn::Black)
-        .map(|(id, _)| id)
-        .unwrap();
+    let init_id = arena.find_node(init, Turn::Black).unwrap();
 
     println!(
         "Example init node id = {init_id}, mate-winning? {}",
         win[init_id]
     );
+
+    // Optimal play: White always takes the fastest mating successor, Black always takes the
+    // successor that survives longest (the hardest defense), so the printed line is a genuine
+    // "mate in N" rather than just any forced win.
+    let dtm = distance_to_mate(&arena);
+    if dtm[init_id] != DRAW {
+        println!("Distance to mate from example init: {} ply", dtm[init_id]);
+
+        let mut node = init_id;
+        let mut ply = 0;
+        while dtm[node] != DRAW && dtm[node] != 0 {
+            let next = match arena.nodes[node].turn {
+                Turn::White => best_white_move(&arena, &dtm, node),
+                Turn::Black => best_black_move(&arena, &dtm, node),
+            };
+            let Some(next) = next else { break };
+            println!("  ply {ply}: node {node} -> {next} (dtm {})", dtm[next]);
+            node = next;
+            ply += 1;
+        }
+    }
 }
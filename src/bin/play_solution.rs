@@ -4,20 +4,60 @@ use std::path::Path;
 use infinite_chess::chess::piece::PieceKind;
 use infinite_chess::core::coord::Coord;
 use infinite_chess::core::square::Square;
-use infinite_chess::solution::{delta_from_dir_index, dir_index_from_key, load_bundle, ViewMode};
+use infinite_chess::scenario::{Side, StartState};
+use infinite_chess::solution::{load_bundle, MoveAlphabet, ViewMode};
 
 const MAX_ABS_DIM: i32 = 81;
 
+/// One recorded ply pair, as written by `save` and replayed by `--replay`.
+///
+/// The leading whitespace-delimited token is the black direction key (the only thing a replay
+/// actually needs to drive); the rest of the line is the human-readable notation, kept so a
+/// replayed line can be checked against what the bundle produces today.
+struct ReplayStep {
+    key: char,
+    recorded_line: String,
+}
+
+fn parse_replay_file(path: &str) -> Vec<ReplayStep> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read --replay file {path}: {e}");
+            std::process::exit(2);
+        }
+    };
+    let mut steps = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(key) = line.chars().next() else {
+            continue;
+        };
+        steps.push(ReplayStep {
+            key,
+            recorded_line: line.to_string(),
+        });
+    }
+    steps
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: play_solution <bundle_dir> [--view relative|absolute] [--bound <B>]");
+        eprintln!(
+            "Usage: play_solution <bundle_dir> [--view relative|absolute] [--bound <B>] [--position \"<string>\"] [--replay <path>]"
+        );
         std::process::exit(2);
     }
 
     let bundle_dir = Path::new(&args[1]);
     let mut view_override: Option<ViewMode> = None;
     let mut bound_override: Option<i32> = None;
+    let mut position_arg: Option<String> = None;
+    let mut replay_arg: Option<String> = None;
 
     let mut i = 2;
     while i < args.len() {
@@ -51,6 +91,22 @@ fn main() {
                 };
                 i += 2;
             }
+            "--position" => {
+                let Some(v) = args.get(i + 1) else {
+                    eprintln!("--position requires a quoted position string");
+                    std::process::exit(2);
+                };
+                position_arg = Some(v.clone());
+                i += 2;
+            }
+            "--replay" => {
+                let Some(v) = args.get(i + 1) else {
+                    eprintln!("--replay requires a path to a saved transcript");
+                    std::process::exit(2);
+                };
+                replay_arg = Some(v.clone());
+                i += 2;
+            }
             x => {
                 eprintln!("Unknown option: {x}");
                 std::process::exit(2);
@@ -82,9 +138,38 @@ fn main() {
         std::process::exit(2);
     }
 
+    if let Some(text) = &position_arg {
+        let start = match StartState::from_text(text, &sol.rules.layout) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Failed to parse --position: {e}");
+                std::process::exit(2);
+            }
+        };
+        if start.to_move != Side::Black {
+            eprintln!("--position must be black to move ('btm'); this interactive tool currently requires start.to_move == black.");
+            std::process::exit(2);
+        }
+        match sol.id_of.get(&start.state) {
+            Some(&id) => current_b_id = id as usize,
+            None => {
+                eprintln!(
+                    "--position is outside the solved region (no matching state in this bundle)."
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
     let mut display_king = sol.states[current_b_id].abs_king;
 
-    print_help();
+    let mut replay_steps: std::collections::VecDeque<ReplayStep> = replay_arg
+        .as_deref()
+        .map(|p| parse_replay_file(p).into())
+        .unwrap_or_default();
+    let mut transcript: Vec<String> = Vec::new();
+
+    print_help(&sol.move_alphabet);
 
     loop {
         let b_state = &sol.states[current_b_id];
@@ -104,6 +189,7 @@ fn main() {
 
         render(
             &sol.rules.layout,
+            &sol.move_alphabet,
             b_state,
             display_king,
             view,
@@ -118,17 +204,27 @@ fn main() {
             yesno(in_check),
             legal_dirs
                 .iter()
-                .map(|&d| dir_key(d).to_string())
+                .filter_map(|&d| sol.move_alphabet.label_at(d))
+                .map(|c| c.to_string())
                 .collect::<Vec<_>>()
                 .join("")
         );
         io::stdout().flush().ok();
 
-        let mut line = String::new();
-        if io::stdin().read_line(&mut line).is_err() {
-            break;
-        }
-        let cmd = line.trim();
+        let cmd_owned: String;
+        let replay_step: Option<ReplayStep> = replay_steps.pop_front();
+        let cmd: &str = if let Some(step) = &replay_step {
+            cmd_owned = step.key.to_string();
+            println!("(replay)> {cmd_owned}");
+            &cmd_owned
+        } else {
+            let mut line = String::new();
+            if io::stdin().read_line(&mut line).is_err() {
+                break;
+            }
+            cmd_owned = line.trim().to_string();
+            &cmd_owned
+        };
         if cmd.is_empty() {
             continue;
         }
@@ -136,7 +232,7 @@ fn main() {
         // Command mode.
         match cmd {
             "help" => {
-                print_help();
+                print_help(&sol.move_alphabet);
                 continue;
             }
             "exit" | "quit" | "q!" => break,
@@ -151,13 +247,22 @@ fn main() {
             _ => {}
         }
 
+        if let Some(path) = cmd.strip_prefix("save ") {
+            let path = path.trim();
+            match std::fs::write(path, transcript.join("\n") + "\n") {
+                Ok(()) => println!("Saved {} ply to {path}", transcript.len()),
+                Err(e) => println!("Failed to save transcript to {path}: {e}"),
+            }
+            continue;
+        }
+
         let ch = cmd.chars().find(|c| !c.is_whitespace()).unwrap_or(' ');
         if ch == 's' {
             println!("'s' is the center key; black cannot pass. Use qwe/adzxc to move, or 'help'.");
             continue;
         }
 
-        let Some(dir_idx) = dir_index_from_key(ch) else {
+        let Some(dir_idx) = sol.move_alphabet.index_of_label(ch) else {
             println!("Unknown input '{cmd}'. Type 'help' for commands.");
             continue;
         };
@@ -169,8 +274,17 @@ fn main() {
         }
 
         // Apply black move.
-        let delta = delta_from_dir_index(dir_idx);
-        println!("Black: {ch} (delta {}, {})", delta.x, delta.y);
+        let delta = sol
+            .move_alphabet
+            .delta_at(dir_idx)
+            .expect("dir_idx came from this alphabet");
+        let black_capture = !is_empty_destination(&b_state.pos, delta);
+        let black_notation = format!(
+            "Black: K{}{}",
+            if black_capture { "x" } else { "-" },
+            square_tag(delta)
+        );
+        println!("{black_notation}");
 
         display_king = display_king + delta;
         let w_id_usize = w_id as usize;
@@ -202,16 +316,38 @@ fn main() {
 
         let w_state = &sol.states[w_id_usize];
         let b_next = &sol.states[next_b_id_usize];
-        println!(
-            "{}",
-            describe_white_action(
-                &sol.rules.layout,
-                &w_state.pos,
-                &b_next.pos,
-                display_king,
-                view
-            )
+
+        let next_legal_dirs = legal_dirs(&sol.transitions[next_b_id_usize]);
+        let next_in_check = sol
+            .rules
+            .is_attacked(infinite_chess::core::coord::Coord::ORIGIN, &b_next.pos);
+        let is_mate = next_in_check && next_legal_dirs.is_empty();
+
+        let white_notation = describe_white_action(
+            &sol.rules.layout,
+            &w_state.pos,
+            &b_next.pos,
+            display_king,
+            view,
+            is_mate,
         );
+        println!("{white_notation}");
+
+        let line = format!("{ch} {black_notation} | {white_notation}");
+        if let Some(step) = &replay_step {
+            if step.recorded_line != line {
+                println!(
+                    "Replay mismatch: recorded `{}` but the bundle now produces `{line}`.",
+                    step.recorded_line
+                );
+                println!(
+                    "The saved white strategy no longer matches this bundle; stopping replay."
+                );
+                transcript.push(line);
+                break;
+            }
+        }
+        transcript.push(line);
 
         current_b_id = next_b_id_usize;
 
@@ -230,21 +366,7 @@ fn yesno(v: bool) -> &'static str {
     }
 }
 
-fn dir_key(idx: usize) -> char {
-    match idx {
-        0 => 'q',
-        1 => 'w',
-        2 => 'e',
-        3 => 'a',
-        4 => 'd',
-        5 => 'z',
-        6 => 'x',
-        7 => 'c',
-        _ => '?',
-    }
-}
-
-fn legal_dirs(next: &[u32; 8]) -> Vec<usize> {
+fn legal_dirs(next: &[u32]) -> Vec<usize> {
     let mut out = Vec::new();
     for (i, &v) in next.iter().enumerate() {
         if v != u32::MAX {
@@ -266,6 +388,7 @@ fn piece_char(kind: PieceKind) -> char {
 
 fn render(
     layout: &infinite_chess::chess::layout::PieceLayout,
+    alphabet: &MoveAlphabet,
     state: &infinite_chess::scenario::State,
     display_king: Coord,
     mode: ViewMode,
@@ -274,7 +397,7 @@ fn render(
 ) {
     let (min_x, max_x, min_y, max_y, cropped) = match mode {
         ViewMode::Relative => (-rel_bound, rel_bound, -rel_bound, rel_bound, false),
-        ViewMode::Absolute => compute_abs_window(layout, state, display_king, legal_dirs),
+        ViewMode::Absolute => compute_abs_window(layout, alphabet, state, display_king, legal_dirs),
     };
 
     let w = (max_x - min_x + 1).max(1) as usize;
@@ -315,7 +438,9 @@ fn render(
 
     // Legal move overlay: '+' only on empty destination squares.
     for &dir in legal_dirs.iter() {
-        let delta = delta_from_dir_index(dir);
+        let Some(delta) = alphabet.delta_at(dir) else {
+            continue;
+        };
         if !is_empty_destination(&state.pos, delta) {
             continue;
         }
@@ -334,7 +459,7 @@ fn render(
     }
 
     // Capture list.
-    let captures = capture_list(layout, &state.pos, display_king, mode, legal_dirs);
+    let captures = capture_list(layout, alphabet, &state.pos, display_king, mode, legal_dirs);
 
     println!();
     for row in grid {
@@ -357,6 +482,7 @@ fn render(
 
 fn compute_abs_window(
     layout: &infinite_chess::chess::layout::PieceLayout,
+    alphabet: &MoveAlphabet,
     state: &infinite_chess::scenario::State,
     display_king: Coord,
     legal_dirs: &[usize],
@@ -380,7 +506,10 @@ fn compute_abs_window(
 
     // Include legal destinations (for '+' overlay).
     for &dir in legal_dirs.iter() {
-        let abs = display_king + delta_from_dir_index(dir);
+        let Some(delta) = alphabet.delta_at(dir) else {
+            continue;
+        };
+        let abs = display_king + delta;
         min_x = min_x.min(abs.x);
         max_x = max_x.max(abs.x);
         min_y = min_y.min(abs.y);
@@ -446,6 +575,7 @@ fn is_empty_destination(pos: &infinite_chess::core::position::Position, delta: C
 
 fn capture_list(
     layout: &infinite_chess::chess::layout::PieceLayout,
+    alphabet: &MoveAlphabet,
     pos: &infinite_chess::core::position::Position,
     display_king: Coord,
     mode: ViewMode,
@@ -453,7 +583,9 @@ fn capture_list(
 ) -> Vec<String> {
     let mut out = Vec::new();
     for &dir in legal_dirs.iter() {
-        let delta = delta_from_dir_index(dir);
+        let Some(delta) = alphabet.delta_at(dir) else {
+            continue;
+        };
         let dst_sq = Square::from_coord(delta);
         for i in 0..pos.count() {
             let sq = pos.square(i);
@@ -473,53 +605,136 @@ fn capture_list(
     out
 }
 
+/// Coordinate tag used by the algebraic notation, e.g. `(3,-2)`. The board has no ranks/files
+/// (it's unbounded), so a bare coordinate pair stands in for a classical square name.
+fn square_tag(c: Coord) -> String {
+    format!("({},{})", c.x, c.y)
+}
+
 fn describe_white_action(
     layout: &infinite_chess::chess::layout::PieceLayout,
     from: &infinite_chess::core::position::Position,
     to: &infinite_chess::core::position::Position,
     display_king: Coord,
     view: ViewMode,
+    is_mate: bool,
 ) -> String {
     if from == to {
         return "White: pass".to_string();
     }
 
-    let Some((kind, rel_from, rel_to)) = diff_single_piece_move(layout, from, to) else {
+    let Some((kind, run, rel_from, rel_to)) = diff_single_piece_move(layout, from, to) else {
         return "White: (move)".to_string();
     };
 
-    if view == ViewMode::Absolute {
-        let abs_from = display_king + rel_from;
-        let abs_to = display_king + rel_to;
+    let ambiguous = is_ambiguous(layout, run, from, rel_from, rel_to);
+    let mate_tag = if is_mate { "#" } else { "" };
+
+    let body = if ambiguous {
         format!(
-            "White: {} ({}, {}) -> ({}, {}) [abs: ({}, {}) -> ({}, {})]",
+            "{}{}-{}{mate_tag}",
             piece_char(kind),
-            rel_from.x,
-            rel_from.y,
-            rel_to.x,
-            rel_to.y,
-            abs_from.x,
-            abs_from.y,
-            abs_to.x,
-            abs_to.y
+            square_tag(rel_from),
+            square_tag(rel_to)
         )
     } else {
+        format!("{}-{}{mate_tag}", piece_char(kind), square_tag(rel_to))
+    };
+
+    if view == ViewMode::Absolute {
+        let abs_from = display_king + rel_from;
+        let abs_to = display_king + rel_to;
         format!(
-            "White: {} ({}, {}) -> ({}, {})",
-            piece_char(kind),
-            rel_from.x,
-            rel_from.y,
-            rel_to.x,
-            rel_to.y
+            "White: {body} [abs: {}-{}]",
+            square_tag(abs_from),
+            square_tag(abs_to)
         )
+    } else {
+        format!("White: {body}")
+    }
+}
+
+/// True if some other piece from the same identical-run as the mover (sitting at its square in
+/// `from`) could also have legally reached `rel_to`, making `rel_from` necessary to disambiguate
+/// the move. Movement geometry mirrors the dead-tree `rules::attacks` scan: this binary doesn't
+/// have crate access to `chess::rules::Rules`'s private per-piece checks, so it re-derives the
+/// same handful of rules locally, exactly like `diff_single_piece_move` already re-derives board
+/// diffs instead of calling into `Rules`.
+fn is_ambiguous(
+    layout: &infinite_chess::chess::layout::PieceLayout,
+    run: std::ops::Range<usize>,
+    from: &infinite_chess::core::position::Position,
+    rel_from: Coord,
+    rel_to: Coord,
+) -> bool {
+    let kind = layout.kind(run.start);
+
+    let mut blockers: Vec<Coord> = Vec::new();
+    for i in 0..from.count() {
+        let sq = from.square(i);
+        if !sq.is_none() {
+            blockers.push(sq.coord());
+        }
     }
+
+    for i in run {
+        let sq = from.square(i);
+        if sq.is_none() {
+            continue;
+        }
+        let other = sq.coord();
+        if other == rel_from {
+            continue;
+        }
+        if could_piece_reach(kind, other, rel_to, &blockers) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Self-contained movement-geometry check: could a piece of `kind` sitting on `from` reach
+/// `to` on an otherwise-empty board except for `blockers` (which may include `from` and `to`
+/// themselves; both are ignored as blockers for the purposes of this check)?
+fn could_piece_reach(kind: PieceKind, from: Coord, to: Coord, blockers: &[Coord]) -> bool {
+    let dx = to.x - from.x;
+    let dy = to.y - from.y;
+    if dx == 0 && dy == 0 {
+        return false;
+    }
+
+    match kind {
+        PieceKind::King => dx.abs() <= 1 && dy.abs() <= 1,
+        PieceKind::Knight => {
+            let ax = dx.abs();
+            let ay = dy.abs();
+            (ax == 1 && ay == 2) || (ax == 2 && ay == 1)
+        }
+        PieceKind::Rook => dx == 0 || dy == 0,
+        PieceKind::Bishop => dx.abs() == dy.abs(),
+        PieceKind::Queen => dx == 0 || dy == 0 || dx.abs() == dy.abs(),
+    }
+    .then(|| ray_clear(from, to, blockers))
+    .unwrap_or(false)
+}
+
+fn ray_clear(from: Coord, to: Coord, blockers: &[Coord]) -> bool {
+    let step = Coord::new((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut cur = from + step;
+    while cur != to {
+        if blockers.iter().any(|&b| b == cur) {
+            return false;
+        }
+        cur = cur + step;
+    }
+    true
 }
 
 fn diff_single_piece_move(
     layout: &infinite_chess::chess::layout::PieceLayout,
     from: &infinite_chess::core::position::Position,
     to: &infinite_chess::core::position::Position,
-) -> Option<(PieceKind, Coord, Coord)> {
+) -> Option<(PieceKind, std::ops::Range<usize>, Coord, Coord)> {
     for run in layout.identical_runs().iter() {
         let kind = layout.kind(run.start);
 
@@ -544,7 +759,7 @@ fn diff_single_piece_move(
             continue;
         }
         if removed.len() == 1 && added.len() == 1 {
-            return Some((kind, removed[0], added[0]));
+            return Some((kind, run.clone(), removed[0], added[0]));
         }
     }
     None
@@ -563,11 +778,23 @@ fn coords_in_a_not_in_b(a: &[Coord], b: &[Coord]) -> Vec<Coord> {
     out
 }
 
-fn print_help() {
+/// The classic q/w/e/a/d/z/x/c king-step keys, in that order — every bundle this crate produces
+/// today, but not guaranteed for a bundle that declares a different [`MoveAlphabet`].
+const KING_STEP_KEYS: [char; 8] = ['q', 'w', 'e', 'a', 'd', 'z', 'x', 'c'];
+
+fn print_help(alphabet: &MoveAlphabet) {
     println!("Commands:");
-    println!("  q w e");
-    println!("  a s d    (s is center; black cannot pass)");
-    println!("  z x c");
-    println!("  help | view relative | view absolute | exit");
+    let is_king_steps = alphabet.len() == KING_STEP_KEYS.len()
+        && (0..KING_STEP_KEYS.len()).all(|i| alphabet.label_at(i) == Some(KING_STEP_KEYS[i]));
+    if is_king_steps {
+        println!("  q w e");
+        println!("  a s d    (s is center; black cannot pass)");
+        println!("  z x c");
+    } else {
+        let keys: String = alphabet.entries().map(|(k, _)| k).collect();
+        println!("  Move keys: {keys}    (no passing)");
+    }
+    println!("  help | view relative | view absolute | save <path> | exit");
+    println!("  (start with --replay <path> to re-run a saved line)");
     println!();
 }
@@ -1,19 +1,39 @@
-use infinite_chess::scenario::CandidateGeneration;
+use infinite_chess::scenario::{CandidateGeneration, StartState};
 use infinite_chess::scenarios;
 use infinite_chess::search::trap::{maximal_inescapable_trap, maximal_tempo_trap};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
+    if args.len() < 2 {
         eprintln!(
-            "Usage: trap_search <scenario>\n\nAvailable scenarios:\n  - {}",
+            "Usage: trap_search <scenario> [--start <text>]\n\nAvailable scenarios:\n  - {}",
             scenarios::available_names().join("\n  - ")
         );
         std::process::exit(2);
     }
 
     let scenario_name = &args[1];
-    let scn = match scenarios::by_name(scenario_name) {
+    let mut start_text: Option<String> = None;
+
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--start" => {
+                let Some(v) = args.get(i + 1) else {
+                    eprintln!("--start requires a position-text argument, e.g. \"k0,0 R3,-2 btm\"");
+                    std::process::exit(2);
+                };
+                start_text = Some(v.clone());
+                i += 2;
+            }
+            x => {
+                eprintln!("Unknown option: {x}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let mut scn = match scenarios::by_name(scenario_name) {
         Ok(Some(s)) => s,
         Ok(None) => {
             eprintln!(
@@ -28,6 +48,16 @@ fn main() {
         }
     };
 
+    if let Some(text) = start_text {
+        match StartState::from_text(&text, &scn.rules.layout) {
+            Ok(start) => scn.start = start,
+            Err(e) => {
+                eprintln!("Invalid --start {text:?}: {e}");
+                std::process::exit(2);
+            }
+        }
+    }
+
     if let Err(e) = scn.validate() {
         eprintln!("Invalid scenario {scenario_name}: {e}");
         std::process::exit(2);
@@ -0,0 +1,393 @@
+//! Dense bitboard acceleration for `InAbsBox`-bounded occupancy and attack queries.
+//!
+//! [`crate::chess::rules::Rules::is_attacked`] and the `Position` occupancy scans it relies on
+//! are O(pieces) per query, which dominates the mate-terminal scan in
+//! [`crate::search::forced_mate::forced_mate_bounded`] once the universe gets large. When a
+//! scenario's candidates are `CandidateGeneration::InAbsBox { bound, .. }`, every square a piece
+//! can ever occupy lies in a fixed `(2*bound+1) x (2*bound+1)` box, so occupancy becomes one bit
+//! per square and "is `target` attacked" becomes a handful of precomputed-ray bitboard scans —
+//! the same trick classical engines use for 8x8 boards, just sized to the box. This path is only
+//! valid for bounded scenarios; `CandidateGeneration::FromStates` has no finite box and must keep
+//! using the array-scanning `Rules::is_attacked`.
+
+use std::collections::HashMap;
+
+use crate::chess::piece::{PieceKind, BISHOP_DIRS, KNIGHT_STEPS, ROOK_DIRS};
+use crate::chess::rules::Rules;
+use crate::core::coord::{Coord, KING_STEPS};
+use crate::core::position::Position;
+
+/// Maximum number of relevant-occupancy squares a slider's per-square blocker table may cover
+/// before we fall back to walking the ray directly.
+///
+/// Classical 8x8 magic bitboards keep a rook's relevant occupancy to at most 12 bits (4096
+/// entries) because the board is fixed-size; a bounded box here can be much larger, so a full
+/// `2^bits`-entry table isn't buildable for every square. A proper magic multiplier would compress
+/// the index space further, but that needs an offline search for per-square magic constants, which
+/// isn't something to hand-roll without a way to validate it; capping the direct table instead
+/// keeps every precomputed table provably correct and falls back to the existing ray walk (see
+/// [`rays_hit`]) once a square's rays are too long to be worth tabulating.
+const MAX_BLOCKER_TABLE_BITS: u32 = 16;
+
+/// Blocker-indexed attack lookup for one slider (rook or bishop) from one square: a table from
+/// "which of this square's relevant squares are occupied" to "which of them are attacked", built
+/// once per `BoxAttackTables::new` and then queried in O(1) per call instead of walking each ray.
+///
+/// Square indices into the box can exceed 128 (the box can be larger than `u128` can address
+/// directly), so blockers and attacks are keyed by *local* bit position within `relevant` (a
+/// square's own ray squares, which is always small — see [`MAX_BLOCKER_TABLE_BITS`]) rather than
+/// by the box's global square index.
+#[derive(Debug, Clone)]
+struct BlockerTable {
+    /// This square's ray squares, in the same order used to assign local bit positions.
+    relevant: Vec<usize>,
+    /// Local occupied-subset bitmask -> local attacked-squares bitmask.
+    attacks: HashMap<u32, u32>,
+}
+
+impl BlockerTable {
+    /// Builds the table for one square's rays, or returns `None` if the relevant-occupancy set
+    /// has more than [`MAX_BLOCKER_TABLE_BITS`] squares (see its doc comment).
+    fn build(rays: &[Vec<usize>; 4]) -> Option<Self> {
+        let relevant: Vec<usize> = rays.iter().flatten().copied().collect();
+        if relevant.len() as u32 > MAX_BLOCKER_TABLE_BITS {
+            return None;
+        }
+
+        let local_rays: Vec<Vec<u32>> = rays
+            .iter()
+            .map(|ray| {
+                ray.iter()
+                    .map(|sq| {
+                        relevant
+                            .iter()
+                            .position(|r| r == sq)
+                            .expect("ray squares are always a subset of `relevant`") as u32
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut attacks = HashMap::with_capacity(1usize << relevant.len());
+        for local_blockers in 0u32..(1u32 << relevant.len()) {
+            let mut mask = 0u32;
+            for ray in &local_rays {
+                for &bit in ray {
+                    mask |= 1 << bit;
+                    if local_blockers & (1 << bit) != 0 {
+                        break;
+                    }
+                }
+            }
+            attacks.insert(local_blockers, mask);
+        }
+
+        Some(Self { relevant, attacks })
+    }
+
+    /// Whether `target_global` (a global box square index) is attacked, given `occ`.
+    fn is_attacked(&self, occ: &BoxBitboard, target_global: usize) -> bool {
+        let Some(target_local) = self.relevant.iter().position(|&sq| sq == target_global) else {
+            // Not even on one of this square's rays.
+            return false;
+        };
+
+        let mut local_blockers = 0u32;
+        for (i, &sq) in self.relevant.iter().enumerate() {
+            if occ.get_index(sq) {
+                local_blockers |= 1 << i;
+            }
+        }
+
+        let mask = *self
+            .attacks
+            .get(&local_blockers)
+            .expect("every local blocker subset was precomputed in build()");
+        mask & (1 << target_local) != 0
+    }
+}
+
+/// A dense bitset over every square in a `[-bound, bound] x [-bound, bound]` box, indexed by
+/// `(x + bound) * side + (y + bound)`.
+#[derive(Debug, Clone)]
+struct BoxBitboard {
+    words: Vec<u64>,
+}
+
+impl BoxBitboard {
+    fn new(square_count: usize) -> Self {
+        Self {
+            words: vec![0u64; square_count.div_ceil(64)],
+        }
+    }
+
+    #[inline]
+    fn get_index(&self, idx: usize) -> bool {
+        (self.words[idx / 64] >> (idx % 64)) & 1 != 0
+    }
+
+    #[inline]
+    fn set_index(&mut self, idx: usize, value: bool) {
+        let w = &mut self.words[idx / 64];
+        if value {
+            *w |= 1 << (idx % 64);
+        } else {
+            *w &= !(1 << (idx % 64));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = 0);
+    }
+}
+
+/// Precomputed leaper/ray masks for every square in a bounded box.
+///
+/// Build once per `bound` with [`BoxAttackTables::new`] and reuse across every state in the
+/// universe, as [`crate::search::forced_mate::forced_mate_bounded`] does — rebuilding per query
+/// would defeat the point.
+#[derive(Debug, Clone)]
+pub struct BoxAttackTables {
+    bound: i32,
+    side: usize,
+    king_targets: Vec<Vec<usize>>,
+    knight_targets: Vec<Vec<usize>>,
+    rook_rays: Vec<[Vec<usize>; 4]>,
+    bishop_rays: Vec<[Vec<usize>; 4]>,
+    rook_lookup: Vec<Option<BlockerTable>>,
+    bishop_lookup: Vec<Option<BlockerTable>>,
+    occ: BoxBitboard,
+}
+
+impl BoxAttackTables {
+    pub fn new(bound: i32) -> Self {
+        assert!(bound >= 0);
+        let side = (2 * bound + 1) as usize;
+        let n = side * side;
+
+        let idx = |c: Coord| -> Option<usize> {
+            if c.x.abs() > bound || c.y.abs() > bound {
+                return None;
+            }
+            Some(((c.x + bound) as usize) * side + (c.y + bound) as usize)
+        };
+        let coord_of = |i: usize| -> Coord {
+            Coord::new((i / side) as i32 - bound, (i % side) as i32 - bound)
+        };
+
+        let mut king_targets: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut knight_targets: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut rook_rays: Vec<[Vec<usize>; 4]> = vec![Default::default(); n];
+        let mut bishop_rays: Vec<[Vec<usize>; 4]> = vec![Default::default(); n];
+
+        for i in 0..n {
+            let c = coord_of(i);
+
+            for &d in &KING_STEPS {
+                if let Some(j) = idx(c + d) {
+                    king_targets[i].push(j);
+                }
+            }
+            for &d in &KNIGHT_STEPS {
+                if let Some(j) = idx(c + d) {
+                    knight_targets[i].push(j);
+                }
+            }
+            for (k, &d) in ROOK_DIRS.iter().enumerate() {
+                let mut ray = Vec::new();
+                let mut step = 1;
+                while let Some(j) = idx(c + d * step) {
+                    ray.push(j);
+                    step += 1;
+                }
+                rook_rays[i][k] = ray;
+            }
+            for (k, &d) in BISHOP_DIRS.iter().enumerate() {
+                let mut ray = Vec::new();
+                let mut step = 1;
+                while let Some(j) = idx(c + d * step) {
+                    ray.push(j);
+                    step += 1;
+                }
+                bishop_rays[i][k] = ray;
+            }
+        }
+
+        let rook_lookup: Vec<Option<BlockerTable>> =
+            rook_rays.iter().map(BlockerTable::build).collect();
+        let bishop_lookup: Vec<Option<BlockerTable>> =
+            bishop_rays.iter().map(BlockerTable::build).collect();
+
+        Self {
+            bound,
+            side,
+            king_targets,
+            knight_targets,
+            rook_rays,
+            bishop_rays,
+            rook_lookup,
+            bishop_lookup,
+            occ: BoxBitboard::new(n),
+        }
+    }
+
+    #[inline]
+    fn index_of(&self, c: Coord) -> Option<usize> {
+        if c.x.abs() > self.bound || c.y.abs() > self.bound {
+            return None;
+        }
+        Some(((c.x + self.bound) as usize) * self.side + (c.y + self.bound) as usize)
+    }
+
+    /// Load `pos`'s occupancy into the reusable bitboard.
+    ///
+    /// Panics if a piece lies outside the box, since that would mean the caller passed a state
+    /// from outside the `InAbsBox` universe these tables were built for.
+    fn load(&mut self, pos: &Position) {
+        self.occ.clear();
+        for &sq in pos.squares() {
+            if sq.is_none() {
+                continue;
+            }
+            let idx = self
+                .index_of(sq.coord())
+                .expect("piece lies outside the BoxAttackTables bound");
+            self.occ.set_index(idx, true);
+        }
+    }
+}
+
+/// Bitboard-accelerated replacement for [`Rules::is_attacked`].
+///
+/// Only valid while every piece in `pos` lies inside `tables`' box, which `InAbsBox` universes
+/// guarantee by construction.
+pub fn is_attacked_boxed(
+    rules: &Rules,
+    tables: &mut BoxAttackTables,
+    target: Coord,
+    pos: &Position,
+) -> bool {
+    let Some(target_idx) = tables.index_of(target) else {
+        // Nothing confined to the box can reach a target outside it.
+        return false;
+    };
+
+    tables.load(pos);
+
+    for i in 0..pos.count() {
+        let sq = pos.square(i);
+        if sq.is_none() {
+            continue;
+        }
+        let from_idx = tables
+            .index_of(sq.coord())
+            .expect("piece lies outside the BoxAttackTables bound");
+        let kind = rules.layout.kind(i);
+
+        let hits = match kind {
+            PieceKind::King => tables.king_targets[from_idx].contains(&target_idx),
+            PieceKind::Knight => tables.knight_targets[from_idx].contains(&target_idx),
+            PieceKind::Rook => rook_hits(tables, from_idx, target_idx),
+            PieceKind::Bishop => bishop_hits(tables, from_idx, target_idx),
+            PieceKind::Queen => {
+                rook_hits(tables, from_idx, target_idx) || bishop_hits(tables, from_idx, target_idx)
+            }
+        };
+        if hits {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether the rook on `from_idx` attacks `target_idx`: an O(1) [`BlockerTable`] lookup when one
+/// was built for this square, otherwise the [`rays_hit`] walk.
+fn rook_hits(tables: &BoxAttackTables, from_idx: usize, target_idx: usize) -> bool {
+    match &tables.rook_lookup[from_idx] {
+        Some(table) => table.is_attacked(&tables.occ, target_idx),
+        None => rays_hit(&tables.rook_rays[from_idx], &tables.occ, target_idx),
+    }
+}
+
+/// Bishop counterpart to [`rook_hits`].
+fn bishop_hits(tables: &BoxAttackTables, from_idx: usize, target_idx: usize) -> bool {
+    match &tables.bishop_lookup[from_idx] {
+        Some(table) => table.is_attacked(&tables.occ, target_idx),
+        None => rays_hit(&tables.bishop_rays[from_idx], &tables.occ, target_idx),
+    }
+}
+
+/// Walk each of the 4 rays outward from a square, stopping at the first occupied square; a hit
+/// only counts if that first blocker *is* the target.
+fn rays_hit(rays: &[Vec<usize>; 4], occ: &BoxBitboard, target_idx: usize) -> bool {
+    for ray in rays {
+        for &j in ray {
+            if j == target_idx {
+                return true;
+            }
+            if occ.get_index(j) {
+                break;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::layout::PieceLayout;
+    use crate::core::square::Square;
+
+    #[test]
+    fn rook_attack_matches_array_scan() {
+        let layout = PieceLayout::from_counts(false, 0, 1, 0, 0);
+        let rules = Rules::new(layout, 4);
+
+        let mut squares = [Square::NONE; crate::core::position::MAX_PIECES];
+        squares[0] = Square::from_coord(Coord::new(3, 0));
+        let pos = Position::new(1, squares);
+
+        let mut tables = BoxAttackTables::new(4);
+        assert_eq!(
+            is_attacked_boxed(&rules, &mut tables, Coord::ORIGIN, &pos),
+            rules.is_attacked(Coord::ORIGIN, &pos),
+        );
+    }
+
+    #[test]
+    fn rook_attack_blocked_by_intervening_piece() {
+        let layout = PieceLayout::from_counts(false, 0, 2, 0, 0);
+        let rules = Rules::new(layout, 4);
+
+        let mut squares = [Square::NONE; crate::core::position::MAX_PIECES];
+        squares[0] = Square::from_coord(Coord::new(3, 0));
+        squares[1] = Square::from_coord(Coord::new(1, 0));
+        let pos = Position::new(2, squares);
+
+        let mut tables = BoxAttackTables::new(4);
+        assert!(!is_attacked_boxed(&rules, &mut tables, Coord::ORIGIN, &pos));
+        assert_eq!(
+            is_attacked_boxed(&rules, &mut tables, Coord::ORIGIN, &pos),
+            rules.is_attacked(Coord::ORIGIN, &pos),
+        );
+    }
+
+    #[test]
+    fn bishop_attack_matches_array_scan_beyond_blocker_table_threshold() {
+        // At bound=6 a bishop on (2,2) has 4+4+4+8=20 relevant squares, past
+        // MAX_BLOCKER_TABLE_BITS, so this also exercises the `rays_hit` fallback path.
+        let layout = PieceLayout::from_counts(false, 0, 0, 1, 0);
+        let rules = Rules::new(layout, 6);
+
+        let mut squares = [Square::NONE; crate::core::position::MAX_PIECES];
+        squares[0] = Square::from_coord(Coord::new(2, 2));
+        let pos = Position::new(1, squares);
+
+        let mut tables = BoxAttackTables::new(6);
+        assert_eq!(
+            is_attacked_boxed(&rules, &mut tables, Coord::ORIGIN, &pos),
+            rules.is_attacked(Coord::ORIGIN, &pos),
+        );
+    }
+}
@@ -19,6 +19,14 @@ pub struct ScenarioConfig {
     /// Remove stalemates from the candidate set (recommended for trap search).
     pub remove_stalemates: bool,
 
+    /// Fold enumerated states under the 8 D4 board symmetries (see
+    /// [`crate::core::position::Position::canonicalize_d4`]), collapsing symmetric black-to-move
+    /// placements into one universe node.
+    ///
+    /// Only takes effect when `layout.is_direction_free()`; pawn layouts (or any future
+    /// direction-dependent piece) must opt out, since a reflection would change their legal moves.
+    pub symmetry_reduction: bool,
+
     pub layout: PieceLayout,
 }
 
@@ -37,10 +45,23 @@ impl ScenarioConfig {
             move_bound,
             white_can_pass,
             remove_stalemates,
+            symmetry_reduction: false,
             layout,
         }
     }
 
+    /// Enable D4 symmetry reduction. Has no effect if `layout` contains a direction-dependent
+    /// piece kind.
+    pub fn with_symmetry_reduction(mut self, enabled: bool) -> Self {
+        self.symmetry_reduction = enabled;
+        self
+    }
+
+    /// The symmetry-reduction flag actually in effect, after gating on the layout.
+    pub fn effective_symmetry_reduction(&self) -> bool {
+        self.symmetry_reduction && self.layout.is_direction_free()
+    }
+
     pub fn piece_summary(&self) -> String {
         // compact, deterministic order
         let mut counts: Vec<(PieceKind, usize)> = Vec::new();
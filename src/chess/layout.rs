@@ -1,6 +1,8 @@
+use std::fmt;
 use std::ops::Range;
 
 use crate::chess::piece::PieceKind;
+use crate::core::position::{kind_letter, letter_kind, MAX_PIECES};
 
 /// A fixed list of piece kinds ("slots") plus contiguous ranges of identical pieces.
 ///
@@ -68,8 +70,128 @@ impl PieceLayout {
     pub fn white_king_index(&self) -> Option<usize> {
         self.white_king_index
     }
+
+    /// True iff every piece kind in this layout moves/attacks the same way under board rotation
+    /// and reflection (i.e. no pawns or other direction-dependent pieces).
+    ///
+    /// `Position::canonicalize_d4` is only sound when this holds, since a direction-dependent
+    /// piece would change meaning under a reflection.
+    #[inline]
+    pub fn is_direction_free(&self) -> bool {
+        // King/Queen/Rook/Bishop/Knight all attack symmetrically under D4; this returns `false`
+        // once a directional kind (e.g. a pawn) is added to `PieceKind`.
+        self.kinds.iter().all(PieceKind::is_direction_free)
+    }
+
+    /// Serialize to a compact kind-letter string, one character per slot in `kinds()` order (e.g.
+    /// `"KQQ"` for a white king and two queens). This is the layout-describing half of a
+    /// serialized position; `Position::to_text` covers the coordinates.
+    pub fn to_text(&self) -> String {
+        self.kinds.iter().map(|&k| kind_letter(k)).collect()
+    }
+
+    /// Parse the format produced by `to_text`, rebuilding the layout directly from the kind
+    /// letters rather than a caller-supplied count breakdown.
+    ///
+    /// Letters must already be grouped by kind in `K, Q, R, B, N` order (the order `from_counts`
+    /// produces), since `Position::canonicalize` assumes contiguous same-kind runs; `"QKQ"` or
+    /// `"RQ"` are rejected.
+    pub fn from_text(text: &str) -> Result<Self, LayoutTextError> {
+        if text.is_empty() {
+            return Err(LayoutTextError::Empty);
+        }
+
+        let mut white_king = false;
+        let mut queens = 0usize;
+        let mut rooks = 0usize;
+        let mut bishops = 0usize;
+        let mut knights = 0usize;
+        let mut last_rank: Option<u8> = None;
+
+        for (i, c) in text.chars().enumerate() {
+            let kind = letter_kind(c).ok_or(LayoutTextError::UnknownKind(c))?;
+            if kind == PieceKind::King {
+                if i != 0 {
+                    return Err(LayoutTextError::KingNotFirst);
+                }
+                white_king = true;
+                continue;
+            }
+
+            let rank = kind_rank(kind);
+            if last_rank.is_some_and(|prev| prev > rank) {
+                return Err(LayoutTextError::OutOfOrder);
+            }
+            last_rank = Some(rank);
+
+            match kind {
+                PieceKind::Queen => queens += 1,
+                PieceKind::Rook => rooks += 1,
+                PieceKind::Bishop => bishops += 1,
+                PieceKind::Knight => knights += 1,
+                PieceKind::King => unreachable!("handled above"),
+            }
+        }
+
+        let layout = PieceLayout::from_counts(white_king, queens, rooks, bishops, knights);
+        if layout.piece_count() > MAX_PIECES {
+            return Err(LayoutTextError::TooManyPieces(layout.piece_count()));
+        }
+        Ok(layout)
+    }
 }
 
+/// Where a kind sorts in `from_counts`'s fixed `Q, R, B, N` grouping order (the white king, if
+/// any, always comes first and is handled separately).
+fn kind_rank(kind: PieceKind) -> u8 {
+    match kind {
+        PieceKind::King => 0,
+        PieceKind::Queen => 1,
+        PieceKind::Rook => 2,
+        PieceKind::Bishop => 3,
+        PieceKind::Knight => 4,
+    }
+}
+
+/// Errors from parsing the textual layout format (see `PieceLayout::from_text`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutTextError {
+    /// The input was empty.
+    Empty,
+    /// A letter was not one of `K Q R B N`.
+    UnknownKind(char),
+    /// A `K` appeared somewhere other than the first letter.
+    KingNotFirst,
+    /// A letter appeared before an earlier kind's run had finished (not grouped in `Q, R, B, N`
+    /// order).
+    OutOfOrder,
+    /// The layout has more pieces than `MAX_PIECES` supports.
+    TooManyPieces(usize),
+}
+
+impl fmt::Display for LayoutTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutTextError::Empty => write!(f, "empty layout text"),
+            LayoutTextError::UnknownKind(c) => write!(f, "unknown piece letter {c:?}"),
+            LayoutTextError::KingNotFirst => {
+                write!(f, "the white king letter 'K' must come first")
+            }
+            LayoutTextError::OutOfOrder => {
+                write!(f, "piece letters must be grouped in K, Q, R, B, N order")
+            }
+            LayoutTextError::TooManyPieces(n) => {
+                write!(
+                    f,
+                    "layout has {n} pieces, more than MAX_PIECES ({MAX_PIECES})"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutTextError {}
+
 fn compute_runs(kinds: &[PieceKind]) -> Vec<Range<usize>> {
     if kinds.is_empty() {
         return Vec::new();
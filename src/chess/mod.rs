@@ -18,7 +18,9 @@
 //! Sliding pieces are limited by `move_bound` (a scenario parameter). This keeps move generation
 //! finite and makes enumeration/search feasible in practice.
 
+pub mod bitboard;
 pub mod bounds;
+pub mod config;
 pub mod layout;
 pub mod piece;
 pub mod rules;
@@ -20,6 +20,14 @@ impl PieceKind {
             _ => &[],
         }
     }
+
+    /// True iff this piece kind attacks/moves identically under any D4 board rotation/reflection.
+    #[inline]
+    pub fn is_direction_free(self) -> bool {
+        // King, Queen, Rook, Bishop, and Knight all satisfy this; only directional pieces like
+        // pawns would not.
+        true
+    }
 }
 
 pub const KING_STEPS: [Coord; 8] = [
@@ -1,3 +1,9 @@
+use std::fmt;
+
+use rustc_hash::FxHashSet;
+use smallvec::SmallVec;
+
+use crate::chess::bitboard::BoxAttackTables;
 use crate::chess::layout::PieceLayout;
 use crate::chess::piece::PieceKind;
 use crate::core::coord::{Coord, KING_STEPS};
@@ -11,6 +17,65 @@ pub struct Rules {
     pub move_bound: i32,
 }
 
+/// An in-place move against a [`Position`], for [`Rules::apply`]/[`Rules::undo`].
+///
+/// Unlike the `Vec`-returning move generators, applying a `Move` does **not** canonicalize the
+/// position afterwards — canonicalization can resort an entire identical-piece run, which would
+/// touch slots beyond the ones [`Undo`] records. Callers that need a canonical copy (e.g. to dedup
+/// states in a transposition table) should clone and canonicalize after the callback runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Move the white piece at `piece_index` to relative square `to`.
+    White { piece_index: usize, to: Square },
+    /// Step the black king by `delta` (in the *current* king-relative frame). Every other slot is
+    /// shifted by `-delta` to keep the king at the origin; a piece sitting on `delta` is captured.
+    BlackKingStep { delta: Coord },
+}
+
+/// What [`Rules::apply`] changed, so [`Rules::undo`] can restore the position exactly.
+#[derive(Debug, Clone, Copy)]
+pub enum Undo {
+    White {
+        piece_index: usize,
+        old_square: Square,
+    },
+    BlackKingStep {
+        delta: Coord,
+        /// The slot (and its old square) captured by the king landing on it, if any.
+        captured: Option<(usize, Square)>,
+    },
+}
+
+/// The set of occupied squares in a [`Position`], for [`Rules::is_attacked`] and
+/// [`Rules::attackers_of`].
+///
+/// Building this once and passing it to [`Rules::is_attacked_with`] (rather than calling
+/// [`Rules::is_attacked`] per query) turns each slider's blocker test into an O(1) lookup instead
+/// of an O(n) rescan of every other piece, which matters when the same position is checked
+/// repeatedly (e.g. once per piece in [`Rules::attackers_of`]).
+#[derive(Debug, Clone)]
+pub struct Occupancy {
+    squares: FxHashSet<Coord>,
+}
+
+impl Occupancy {
+    /// Build the occupancy set for `pos`.
+    pub fn build(pos: &Position) -> Self {
+        let squares = pos
+            .squares()
+            .iter()
+            .filter(|sq| !sq.is_none())
+            .map(|sq| sq.coord())
+            .collect();
+        Self { squares }
+    }
+
+    #[inline]
+    fn contains(&self, c: Coord) -> bool {
+        self.squares.contains(&c)
+    }
+}
+
 impl Rules {
     pub fn new(layout: PieceLayout, move_bound: i32) -> Self {
         assert!(move_bound >= 1);
@@ -18,12 +83,18 @@ impl Rules {
         Self { layout, move_bound }
     }
 
-    /// True iff the position respects basic legality constraints:
+    /// True iff the position respects basic legality constraints. See
+    /// [`Rules::validate_position`] for the specific checks and why a position was rejected.
+    pub fn is_legal_position(&self, pos: &Position) -> bool {
+        self.validate_position(pos).is_ok()
+    }
+
+    /// Validate basic legality constraints, returning *why* a position is illegal instead of a
+    /// bare `bool`:
     /// - no non-captured piece is on the origin (black king square)
     /// - no two non-captured pieces share a square
     /// - the white king (if present) is not adjacent to the black king
-    pub fn is_legal_position(&self, pos: &Position) -> bool {
-        // origin & duplicates
+    pub fn validate_position(&self, pos: &Position) -> Result<(), InvalidPosition> {
         let mut seen: [Square; MAX_PIECES] = [Square::NONE; MAX_PIECES];
         let mut seen_len = 0usize;
 
@@ -32,10 +103,10 @@ impl Rules {
                 continue;
             }
             if sq.coord() == Coord::ORIGIN {
-                return false;
+                return Err(InvalidPosition::KingSquareOccupied);
             }
             if seen.iter().take(seen_len).any(|&s| s == sq) {
-                return false;
+                return Err(InvalidPosition::OverlappingPieces);
             }
             seen[seen_len] = sq;
             seen_len += 1;
@@ -44,31 +115,77 @@ impl Rules {
         if let Some(k_idx) = self.layout.white_king_index() {
             let ks = pos.square(k_idx);
             if !ks.is_none() && ks.coord().chebyshev_norm() <= 1 {
-                return false;
+                return Err(InvalidPosition::NeighbouringKings);
             }
         }
 
-        true
+        Ok(())
     }
 
     /// Does *any* white piece attack `target` in this position?
     pub fn is_attacked(&self, target: Coord, pos: &Position) -> bool {
-        // We do O(n^2) blocker checks by scanning other pieces; piece counts are tiny.
+        self.is_attacked_with(target, pos, &Occupancy::build(pos))
+    }
+
+    /// Like [`Rules::is_attacked`], but takes an already-built [`Occupancy`] instead of
+    /// constructing one. Reuse the same `occ` across every query against `pos` (e.g. from
+    /// [`Rules::attackers_of`], or a caller checking several targets against one position) instead
+    /// of rebuilding it per call.
+    pub fn is_attacked_with(&self, target: Coord, pos: &Position, occ: &Occupancy) -> bool {
         for i in 0..pos.count() {
             let sq = pos.square(i);
             if sq.is_none() {
                 continue;
             }
             let kind = self.layout.kind(i);
-            if self.piece_attacks(kind, sq.coord(), target, pos) {
+            if self.piece_attacks(kind, sq.coord(), target, occ) {
                 return true;
             }
         }
         false
     }
 
+    /// Every piece-index attacking `target`, reusing a single [`Occupancy`] across all sliders
+    /// instead of rescanning every other piece per slider the way the old `is_attacked` did.
+    ///
+    /// Returns a `SmallVec` since most positions have at most a couple of attackers on any one
+    /// square; this would pull in the `smallvec` crate if this tree had a manifest to declare it
+    /// in (see [`crate::fuzz`]'s doc comment for the same kind of gap).
+    pub fn attackers_of(&self, target: Coord, pos: &Position) -> SmallVec<[usize; 4]> {
+        let occ = Occupancy::build(pos);
+        let mut out = SmallVec::new();
+        for i in 0..pos.count() {
+            let sq = pos.square(i);
+            if sq.is_none() {
+                continue;
+            }
+            let kind = self.layout.kind(i);
+            if self.piece_attacks(kind, sq.coord(), target, &occ) {
+                out.push(i);
+            }
+        }
+        out
+    }
+
+    /// Bitboard-accelerated equivalent of [`Rules::is_attacked`], for `InAbsBox`-bounded
+    /// scenarios.
+    ///
+    /// `tables` must be a [`BoxAttackTables`] built for a bound at least as large as the one the
+    /// universe was enumerated with, since every piece in `pos` must lie inside the box; reuse
+    /// the same `tables` across every state instead of rebuilding it per query. Unbounded
+    /// `CandidateGeneration::FromStates` scenarios have no finite box and must keep using
+    /// [`Rules::is_attacked`].
+    pub fn is_attacked_boxed(
+        &self,
+        tables: &mut BoxAttackTables,
+        target: Coord,
+        pos: &Position,
+    ) -> bool {
+        crate::chess::bitboard::is_attacked_boxed(self, tables, target, pos)
+    }
+
     #[inline]
-    fn piece_attacks(&self, kind: PieceKind, from: Coord, target: Coord, pos: &Position) -> bool {
+    fn piece_attacks(&self, kind: PieceKind, from: Coord, target: Coord, occ: &Occupancy) -> bool {
         use PieceKind::*;
         match kind {
             King => {
@@ -82,13 +199,13 @@ impl Rules {
                 let ay = d.y.abs();
                 (ax == 2 && ay == 1) || (ax == 1 && ay == 2)
             }
-            Rook => self.rider_attacks(from, target, &ROOK_DIRS, pos),
-            Bishop => self.rider_attacks(from, target, &BISHOP_DIRS, pos),
-            Queen => self.rider_attacks(from, target, &QUEEN_DIRS, pos),
+            Rook => self.rider_attacks(from, target, &ROOK_DIRS, occ),
+            Bishop => self.rider_attacks(from, target, &BISHOP_DIRS, occ),
+            Queen => self.rider_attacks(from, target, &QUEEN_DIRS, occ),
         }
     }
 
-    fn rider_attacks(&self, from: Coord, target: Coord, dirs: &[Coord], pos: &Position) -> bool {
+    fn rider_attacks(&self, from: Coord, target: Coord, dirs: &[Coord], occ: &Occupancy) -> bool {
         let v = target - from;
         if v == Coord::ORIGIN {
             return false;
@@ -104,40 +221,88 @@ impl Rules {
             return false;
         }
 
-        // Blockers: if any piece lies strictly between `from` and `target` on the same ray.
-        for &other_sq in pos.squares() {
-            if other_sq.is_none() {
-                continue;
+        // Blockers: walk the single ray from `from` to `target`, testing occupancy by lookup
+        // rather than rescanning every other piece.
+        let mut cur = from + dir;
+        for _ in 1..dist {
+            if occ.contains(cur) {
+                return false;
             }
-            let other = other_sq.coord();
-            if other == from {
-                continue;
+            cur = cur + dir;
+        }
+        true
+    }
+
+    /// Apply `mv` to `pos` in place, returning an [`Undo`] that restores it exactly.
+    ///
+    /// Does not canonicalize; see [`Move`]'s doc comment.
+    pub fn apply(&self, pos: &mut Position, mv: Move) -> Undo {
+        match mv {
+            Move::White { piece_index, to } => {
+                let old_square = pos.square(piece_index);
+                pos.set_square(piece_index, to);
+                Undo::White {
+                    piece_index,
+                    old_square,
+                }
             }
-            let w = other - from;
-            if let Some(s) = scalar_along_dir_if_aligned(w, dir) {
-                if s > 0 && s < dist {
-                    return false;
+            Move::BlackKingStep { delta } => {
+                let mut captured = None;
+                for i in 0..pos.count() {
+                    let sq = pos.square(i);
+                    if sq.is_none() {
+                        continue;
+                    }
+                    if sq.coord() == delta {
+                        captured = Some((i, sq));
+                        pos.set_square(i, Square::NONE);
+                    } else {
+                        pos.set_square(i, sq.shifted_neg(delta));
+                    }
                 }
+                Undo::BlackKingStep { delta, captured }
             }
         }
-        true
     }
 
-    /// All legal black king moves (after re-centering the king at the origin).
-    pub fn black_moves(&self, pos: &Position) -> Vec<Position> {
-        self.black_moves_with_delta(pos)
-            .into_iter()
-            .map(|(_, p)| p)
-            .collect()
+    /// Undo a move previously applied by [`Rules::apply`], restoring `pos` exactly.
+    pub fn undo(&self, pos: &mut Position, undo: Undo) {
+        match undo {
+            Undo::White {
+                piece_index,
+                old_square,
+            } => {
+                pos.set_square(piece_index, old_square);
+            }
+            Undo::BlackKingStep { delta, captured } => {
+                for i in 0..pos.count() {
+                    let sq = pos.square(i);
+                    if sq.is_none() {
+                        continue;
+                    }
+                    pos.set_square(i, sq.shifted(delta));
+                }
+                if let Some((idx, old_square)) = captured {
+                    pos.set_square(idx, old_square);
+                }
+            }
+        }
     }
 
-    /// All legal black king moves, paired with the king step `delta` taken in the *current*
-    /// king-relative coordinate system.
+    /// Walk every legal black king move from `pos` in place, via [`Rules::apply`]/[`Rules::undo`],
+    /// calling `f(delta, &mut pos)` for each (where `delta` is the king step taken in the
+    /// *current* king-relative frame) without allocating a `Position` per candidate.
     ///
-    /// This is useful for scenarios that track an absolute king anchor.
-    pub fn black_moves_with_delta(&self, pos: &Position) -> Vec<(Coord, Position)> {
-        let mut out: Vec<(Coord, Position)> = Vec::new();
-
+    /// `pos` is restored to its original contents before this method returns. `f` sees the raw
+    /// (non-canonicalized) successor, mutably, so a deep search can recurse through it via further
+    /// `apply`/`undo` calls instead of cloning; clone and canonicalize it instead if you just need
+    /// a canonical key. Return `false` from `f` to stop early (e.g. on an alpha-beta cutoff) —
+    /// every other candidate is skipped and `pos` is still restored correctly.
+    pub fn for_each_black_move(
+        &self,
+        pos: &mut Position,
+        mut f: impl FnMut(Coord, &mut Position) -> bool,
+    ) {
         for &delta in &KING_STEPS {
             // The black king cannot capture the white king.
             if let Some(k_idx) = self.layout.white_king_index() {
@@ -147,48 +312,43 @@ impl Rules {
                 }
             }
 
-            let mut next = pos.clone();
+            let undo = self.apply(pos, Move::BlackKingStep { delta });
 
-            for i in 0..next.count() {
-                let sq = next.square(i);
-                if sq.is_none() {
-                    continue;
-                }
-                if sq.coord() == delta {
-                    // Capture (unless it's the white king, already checked above).
-                    next.set_square(i, Square::NONE);
-                } else {
-                    next.set_square(i, sq.shifted_neg(delta));
-                }
-            }
+            let keep_going = if self.is_legal_position(pos) && !self.is_attacked(Coord::ORIGIN, pos)
+            {
+                f(delta, pos)
+            } else {
+                true
+            };
 
-            next.canonicalize(&self.layout);
+            self.undo(pos, undo);
 
-            if !self.is_legal_position(&next) {
-                continue;
-            }
-            // Illegal if the destination square is attacked.
-            if self.is_attacked(Coord::ORIGIN, &next) {
-                continue;
+            if !keep_going {
+                break;
             }
-
-            out.push((delta, next));
         }
-
-        out
     }
 
-    /// All legal white moves from `pos`.
+    /// Walk every legal white move from `pos` in place, via [`Rules::apply`]/[`Rules::undo`],
+    /// calling `f(Some((kind, from, to)), &mut pos)` for each, or `f(None, &mut pos)` for the
+    /// `allow_pass` no-op, without allocating a `Position` per candidate.
     ///
-    /// `allow_pass` adds a "do nothing" move that keeps the position unchanged.
-    pub fn white_moves(&self, pos: &Position, allow_pass: bool) -> Vec<Position> {
-        let mut out = Vec::new();
-
-        if allow_pass {
-            out.push(pos.clone());
+    /// `pos` is restored to its original contents before this method returns. `f` sees the raw
+    /// (non-canonicalized) successor, mutably, so a deep search can recurse through it via further
+    /// `apply`/`undo` calls instead of cloning; clone and canonicalize it instead if you just need
+    /// a canonical key. Return `false` from `f` to stop early (e.g. on an alpha-beta cutoff) —
+    /// every other candidate is skipped and `pos` is still restored correctly.
+    pub fn for_each_white_move(
+        &self,
+        pos: &mut Position,
+        allow_pass: bool,
+        mut f: impl FnMut(Option<(PieceKind, Coord, Coord)>, &mut Position) -> bool,
+    ) {
+        if allow_pass && !f(None, pos) {
+            return;
         }
 
-        for i in 0..pos.count() {
+        'slots: for i in 0..pos.count() {
             let sq = pos.square(i);
             if sq.is_none() {
                 continue;
@@ -200,23 +360,15 @@ impl Rules {
                 PieceKind::King => {
                     for &d in &KING_STEPS {
                         let to = from + d;
-                        if to == Coord::ORIGIN {
+                        if to == Coord::ORIGIN || to.chebyshev_norm() <= 1 {
+                            // Can't step onto the black king, or adjacent to it.
                             continue;
                         }
-                        if to.chebyshev_norm() <= 1 {
-                            // Kings can't be adjacent.
+                        if pos.is_occupied_except(Square::from_coord(to), i) {
                             continue;
                         }
-                        let to_sq = Square::from_coord(to);
-                        if pos.is_occupied_except(to_sq, i) {
-                            continue;
-                        }
-                        let mut next = pos.clone();
-                        next.set_square(i, to_sq);
-                        next.canonicalize(&self.layout);
-                        // Other legality invariants should still hold.
-                        if self.is_legal_position(&next) {
-                            out.push(next);
+                        if !self.try_white_move(pos, i, kind, from, to, &mut f) {
+                            break 'slots;
                         }
                     }
                 }
@@ -226,42 +378,239 @@ impl Rules {
                         if to == Coord::ORIGIN {
                             continue;
                         }
-                        let to_sq = Square::from_coord(to);
-                        if pos.is_occupied_except(to_sq, i) {
+                        if pos.is_occupied_except(Square::from_coord(to), i) {
                             continue;
                         }
-                        let mut next = pos.clone();
-                        next.set_square(i, to_sq);
-                        next.canonicalize(&self.layout);
-                        if self.is_legal_position(&next) {
-                            out.push(next);
+                        if !self.try_white_move(pos, i, kind, from, to, &mut f) {
+                            break 'slots;
                         }
                     }
                 }
                 PieceKind::Rook | PieceKind::Bishop | PieceKind::Queen => {
-                    let dirs = kind.slide_dirs();
-                    for &dir in dirs {
+                    for &dir in kind.slide_dirs() {
                         for step in 1..=self.move_bound {
                             let to = from + dir * step;
                             if to == Coord::ORIGIN {
                                 // The black king blocks sliding movement.
                                 break;
                             }
-                            let to_sq = Square::from_coord(to);
-                            if pos.is_occupied_except(to_sq, i) {
+                            if pos.is_occupied_except(Square::from_coord(to), i) {
                                 break;
                             }
-                            let mut next = pos.clone();
-                            next.set_square(i, to_sq);
-                            next.canonicalize(&self.layout);
-                            if self.is_legal_position(&next) {
-                                out.push(next);
+                            if !self.try_white_move(pos, i, kind, from, to, &mut f) {
+                                break 'slots;
                             }
                         }
                     }
                 }
             }
         }
+    }
+
+    /// Apply a single white candidate move, invoke `f` if the result is legal, then undo.
+    /// Returns `f`'s result (`true` to keep enumerating, `false` to stop), or `true` if the
+    /// candidate was illegal and `f` wasn't called.
+    fn try_white_move<F: FnMut(Option<(PieceKind, Coord, Coord)>, &mut Position) -> bool>(
+        &self,
+        pos: &mut Position,
+        piece_index: usize,
+        kind: PieceKind,
+        from: Coord,
+        to: Coord,
+        f: &mut F,
+    ) -> bool {
+        let undo = self.apply(
+            pos,
+            Move::White {
+                piece_index,
+                to: Square::from_coord(to),
+            },
+        );
+        let keep_going = if self.is_legal_position(pos) {
+            f(Some((kind, from, to)), pos)
+        } else {
+            true
+        };
+        self.undo(pos, undo);
+        keep_going
+    }
+
+    /// All legal black king moves (after re-centering the king at the origin).
+    pub fn black_moves(&self, pos: &Position) -> Vec<Position> {
+        self.black_moves_with_delta(pos)
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect()
+    }
+
+    /// All legal black king moves, paired with the king step `delta` taken in the *current*
+    /// king-relative coordinate system.
+    ///
+    /// This is useful for scenarios that track an absolute king anchor. A thin,
+    /// `Vec`-materializing wrapper over [`Rules::for_each_black_move`]; see that method if you're
+    /// walking a move tree and don't need every successor collected up front.
+    pub fn black_moves_with_delta(&self, pos: &Position) -> Vec<(Coord, Position)> {
+        let mut out: Vec<(Coord, Position)> = Vec::new();
+        let mut scratch = pos.clone();
+        self.for_each_black_move(&mut scratch, |delta, next| {
+            let mut canon = next.clone();
+            canon.canonicalize(&self.layout);
+            out.push((delta, canon));
+            true
+        });
+        out
+    }
+
+    /// All legal white moves from `pos`.
+    ///
+    /// `allow_pass` adds a "do nothing" move that keeps the position unchanged.
+    pub fn white_moves(&self, pos: &Position, allow_pass: bool) -> Vec<Position> {
+        self.white_moves_with_move(pos, allow_pass)
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect()
+    }
+
+    /// All legal white moves from `pos`, paired with `Some((kind, from, to))` identifying the
+    /// moved piece in **relative** coordinates, or `None` for the `allow_pass` no-op move.
+    ///
+    /// This is useful for incrementally updating a [`crate::core::zobrist`] hash (XOR out the old
+    /// square's key, XOR in the new one) instead of rehashing the whole position after every move.
+    /// A thin, `Vec`-materializing wrapper over [`Rules::for_each_white_move`]; see that method if
+    /// you're walking a move tree and don't need every successor collected up front.
+    pub fn white_moves_with_move(
+        &self,
+        pos: &Position,
+        allow_pass: bool,
+    ) -> Vec<(Option<(PieceKind, Coord, Coord)>, Position)> {
+        let mut out = Vec::new();
+        let mut scratch = pos.clone();
+        self.for_each_white_move(&mut scratch, allow_pass, |mv, next| {
+            let mut canon = next.clone();
+            canon.canonicalize(&self.layout);
+            out.push((mv, canon));
+            true
+        });
+        out
+    }
+
+    /// Every `(delta, predecessor)` pair such that stepping the black king by `delta` from
+    /// `predecessor` legally reaches `target` (after canonicalization), for backward search (see
+    /// [`crate::search::movegen::black_move_predecessors`]).
+    ///
+    /// Built by running [`Rules::undo`]'s `BlackKingStep` transform on `target` for each of the 8
+    /// king steps and each optionally-recaptured vacant slot, then replaying [`Rules::apply`]
+    /// forward to confirm the reconstruction actually reproduces `target`: canonicalization can
+    /// reorder identical-kind pieces, so a purely geometric inverse could otherwise manufacture a
+    /// predecessor that doesn't really reach `target`.
+    pub fn black_predecessors(&self, target: &Position) -> Vec<(Coord, Position)> {
+        let mut out = Vec::new();
+
+        for &delta in &KING_STEPS {
+            for captured_idx in std::iter::once(None).chain((0..target.count()).map(Some)) {
+                if let Some(idx) = captured_idx {
+                    if !target.square(idx).is_none() {
+                        continue; // a recaptured slot must be vacant in `target`
+                    }
+                    if self.layout.white_king_index() == Some(idx) {
+                        continue; // the black king can never step onto the white king
+                    }
+                }
+
+                let mut candidate = target.clone();
+                self.undo(
+                    &mut candidate,
+                    Undo::BlackKingStep {
+                        delta,
+                        captured: captured_idx.map(|idx| (idx, Square::from_coord(delta))),
+                    },
+                );
+
+                if !self.is_legal_position(&candidate) {
+                    continue;
+                }
+                if let Some(k_idx) = self.layout.white_king_index() {
+                    let ks = candidate.square(k_idx);
+                    if !ks.is_none() && ks.coord() == delta {
+                        continue; // for_each_black_move never steps onto the white king
+                    }
+                }
+
+                let mut scratch = candidate.clone();
+                self.apply(&mut scratch, Move::BlackKingStep { delta });
+                scratch.canonicalize(&self.layout);
+
+                if scratch == *target {
+                    out.push((delta, candidate));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Every `(kind, from, to, predecessor)` tuple such that moving the piece at `to` in `target`
+    /// from `from` legally reaches `target` (after canonicalization), for backward search (see
+    /// [`crate::search::movegen::white_move_predecessors`]).
+    ///
+    /// Candidate `from` squares are generated with the same move geometry
+    /// [`Rules::for_each_white_move`] uses (a king/knight step, or any point along a
+    /// rook/bishop/queen ray up to `move_bound`), then each candidate is replayed through
+    /// [`Rules::for_each_white_move`] itself rather than re-deriving blocker rules here, so only
+    /// genuinely legal `from -> to` moves are returned.
+    pub fn white_predecessors(
+        &self,
+        target: &Position,
+    ) -> Vec<(PieceKind, Coord, Coord, Position)> {
+        let mut out = Vec::new();
+
+        for i in 0..target.count() {
+            let to_sq = target.square(i);
+            if to_sq.is_none() {
+                continue;
+            }
+            let to = to_sq.coord();
+            let kind = self.layout.kind(i);
+
+            let froms: Vec<Coord> = match kind {
+                PieceKind::King => KING_STEPS.iter().map(|&d| to - d).collect(),
+                PieceKind::Knight => KNIGHT_DELTAS.iter().map(|&d| to - d).collect(),
+                PieceKind::Rook | PieceKind::Bishop | PieceKind::Queen => kind
+                    .slide_dirs()
+                    .iter()
+                    .flat_map(|&dir| (1..=self.move_bound).map(move |step| to - dir * step))
+                    .collect(),
+            };
+
+            for from in froms {
+                if from == Coord::ORIGIN {
+                    continue;
+                }
+
+                let mut candidate = target.clone();
+                candidate.set_square(i, Square::from_coord(from));
+
+                if !self.is_legal_position(&candidate) {
+                    continue;
+                }
+
+                let mut scratch = candidate.clone();
+                let mut found = false;
+                self.for_each_white_move(&mut scratch, false, |mv, next| {
+                    if mv == Some((kind, from, to)) {
+                        let mut canon = next.clone();
+                        canon.canonicalize(&self.layout);
+                        found = canon == *target;
+                        return false;
+                    }
+                    true
+                });
+
+                if found {
+                    out.push((kind, from, to, candidate));
+                }
+            }
+        }
 
         out
     }
@@ -281,6 +630,33 @@ impl Rules {
     }
 }
 
+/// Reasons a [`Position`] fails [`Rules::validate_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPosition {
+    /// A non-captured piece sits on the origin (the black king's square).
+    KingSquareOccupied,
+    /// Two non-captured pieces occupy the same square.
+    OverlappingPieces,
+    /// The white king is adjacent to the black king.
+    NeighbouringKings,
+}
+
+impl fmt::Display for InvalidPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidPosition::KingSquareOccupied => {
+                write!(f, "a piece occupies the black king's square")
+            }
+            InvalidPosition::OverlappingPieces => write!(f, "two pieces occupy the same square"),
+            InvalidPosition::NeighbouringKings => {
+                write!(f, "the white king is adjacent to the black king")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidPosition {}
+
 const ROOK_DIRS: [Coord; 4] = [
     Coord { x: 1, y: 0 },
     Coord { x: -1, y: 0 },
@@ -334,28 +710,3 @@ fn normalized_dir_and_distance(v: Coord) -> Option<(Coord, i32)> {
 
     None
 }
-
-#[inline]
-fn scalar_along_dir_if_aligned(v: Coord, dir: Coord) -> Option<i32> {
-    if dir.x == 0 {
-        if v.x != 0 {
-            return None;
-        }
-        if dir.y == 0 {
-            return None;
-        }
-        let s = v.y / dir.y;
-        if s * dir.y == v.y {
-            Some(s)
-        } else {
-            None
-        }
-    } else {
-        let s = v.x / dir.x;
-        if s * dir.x == v.x && s * dir.y == v.y {
-            Some(s)
-        } else {
-            None
-        }
-    }
-}
@@ -6,7 +6,9 @@
 //! - [`coord`]: integer coordinates and common step sets (king moves).
 //! - [`square`]: packed coordinates in a single `i64` plus `Square::NONE` for captured pieces.
 //! - [`position`]: a fixed-capacity piece placement (`MAX_PIECES`) in king-relative coordinates.
+//! - [`zobrist`]: incremental hash keys over absolute squares, for transposition tables.
 
 pub mod coord;
 pub mod position;
 pub mod square;
+pub mod zobrist;
@@ -1,4 +1,8 @@
+use std::fmt;
+
 use crate::chess::layout::PieceLayout;
+use crate::chess::piece::PieceKind;
+use crate::core::coord::Coord;
 use crate::core::square::Square;
 
 /// Maximum number of white pieces we support (not counting the black king).
@@ -12,19 +16,86 @@ pub const MAX_PIECES: usize = 16;
 /// squares relative to that king. Captured pieces are stored as `Square::NONE`.
 ///
 /// The piece *types* are not stored here; that's provided by a `PieceLayout`.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct Position {
     squares: [Square; MAX_PIECES],
     count: u8,
+    /// Running [`Position::zobrist`] hash, kept in sync by every method that changes `squares`
+    /// (see `slot_key` below) so dedup lookups never have to rehash the full slot list.
+    hash: u64,
+}
+
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count && self.squares == other.squares
+    }
+}
+
+impl Eq for Position {}
+
+impl std::hash::Hash for Position {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+/// SplitMix64, as in [`crate::core::zobrist`] — used here to derive a per-slot key on demand
+/// rather than drawing from a precomputed table.
+#[inline]
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// The Zobrist key for slot `piece_index` holding `square_raw` (a [`Square::raw`] value).
+///
+/// Keying by slot index rather than `PieceKind` (contrast [`crate::core::zobrist::piece_key`])
+/// means this doesn't need a `PieceLayout` to compute, which is what makes it cheap to call from
+/// [`Position::set`] on every move.
+#[inline]
+fn slot_key(piece_index: usize, square_raw: i64) -> u64 {
+    let slot_seed = splitmix64(piece_index as u64);
+    splitmix64((square_raw as u64) ^ slot_seed.rotate_left(32))
 }
 
 impl Position {
     pub fn new(count: usize, squares: [Square; MAX_PIECES]) -> Self {
         debug_assert!(count <= MAX_PIECES);
-        Self {
+        let mut pos = Self {
             squares,
             count: count as u8,
+            hash: 0,
+        };
+        pos.recompute_hash();
+        pos
+    }
+
+    /// Recompute [`Position::zobrist`] from scratch over all occupied slots.
+    ///
+    /// Used after bulk rewrites of `squares` ([`Position::canonicalize`],
+    /// [`Position::canonicalize_d4`]) that reassign slots in bulk rather than toggling one at a
+    /// time; [`Position::set`] instead updates `hash` incrementally in O(1).
+    fn recompute_hash(&mut self) {
+        let mut h = 0u64;
+        for (i, sq) in self.squares().iter().enumerate() {
+            if !sq.is_none() {
+                h ^= slot_key(i, sq.raw());
+            }
         }
+        self.hash = h;
+    }
+
+    /// A Zobrist-style hash over this position's occupied slots, suitable as a cheap dedup bucket
+    /// key (e.g. for an arena enumerator's seen-state set) — only a hash collision falls back to
+    /// the full `Eq` comparison `squares` already supports.
+    ///
+    /// Kept up to date incrementally by [`Position::set`]/[`Position::set_square`], so repeated
+    /// inserts into a `HashMap<Position, _>` don't rehash the full slot list each time.
+    pub fn zobrist(&self) -> u64 {
+        self.hash
     }
 
     pub fn count(&self) -> usize {
@@ -49,6 +120,13 @@ impl Position {
     }
 
     pub fn set(&mut self, idx: usize, sq: Square) {
+        let old = self.squares_mut()[idx];
+        if !old.is_none() {
+            self.hash ^= slot_key(idx, old.raw());
+        }
+        if !sq.is_none() {
+            self.hash ^= slot_key(idx, sq.raw());
+        }
         self.squares_mut()[idx] = sq;
     }
 
@@ -60,6 +138,140 @@ impl Position {
         for run in layout.identical_runs() {
             self.squares[run.start..run.end].sort();
         }
+        self.recompute_hash();
+    }
+
+    /// Canonicalize under the 8 dihedral (D4) symmetries of the square lattice, folding the
+    /// position to the lexicographically smallest of its transformed-and-run-sorted forms.
+    ///
+    /// Because the black king sits at the origin, every rotation/reflection of the board fixing
+    /// the origin maps a legal king-relative position to an equivalent one: mate/trap predicates
+    /// are symmetry-invariant, so collapsing the 8 orbit members to one representative shrinks the
+    /// universe up to 8× without changing results. Only valid when `layout` has no
+    /// direction-dependent pieces (see [`PieceLayout::is_direction_free`]); callers must check that
+    /// themselves, since pawn-bearing layouts must opt out.
+    pub fn canonicalize_d4(&mut self, layout: &PieceLayout) {
+        let n = self.count();
+        let mut best: Option<[Square; MAX_PIECES]> = None;
+
+        for transform in D4_TRANSFORMS {
+            let mut candidate = [Square::NONE; MAX_PIECES];
+            for i in 0..n {
+                let sq = self.squares[i];
+                candidate[i] = if sq.is_none() {
+                    Square::NONE
+                } else {
+                    Square::from_coord(transform(sq.coord()))
+                };
+            }
+            let mut p = Position::new(n, candidate);
+            p.canonicalize(layout);
+
+            let is_better = match &best {
+                None => true,
+                Some(b) => p.squares[..n] < b[..n],
+            };
+            if is_better {
+                best = Some(p.clone_squares_array());
+            }
+        }
+
+        if let Some(b) = best {
+            self.squares = b;
+            self.recompute_hash();
+        }
+    }
+
+    /// Serialize to the textual board format (a FEN analogue for the infinite board):
+    /// `k<x>,<y> <PieceLetter><x>,<y> ...`, one whitespace-separated token for the black king
+    /// anchor followed by one per piece in `layout` order, all in **absolute** coordinates.
+    /// Captured pieces are written as `<PieceLetter>-`. Side-to-move is not part of this token
+    /// (see [`crate::scenario::StartState::to_text`]).
+    ///
+    /// Piece letters follow classical chess notation (`K Q R B N`); the lowercase `k` prefix on
+    /// the anchor token disambiguates the black king from a `PieceLayout`'s optional white king,
+    /// which also uses `K`.
+    pub fn to_text(&self, abs_king: Coord, layout: &PieceLayout) -> String {
+        let mut parts = vec![format!("k{},{}", abs_king.x, abs_king.y)];
+        for i in 0..self.count() {
+            let letter = kind_letter(layout.kind(i));
+            let sq = self.squares[i];
+            if sq.is_none() {
+                parts.push(format!("{letter}-"));
+            } else {
+                let abs = sq.coord() + abs_king;
+                parts.push(format!("{letter}{},{}", abs.x, abs.y));
+            }
+        }
+        parts.join(" ")
+    }
+
+    /// Parse the textual board format produced by [`Position::to_text`], validating piece kinds
+    /// and counts against `layout`. Returns the black king's absolute anchor plus a canonicalized
+    /// king-relative `Position`.
+    pub fn from_text(text: &str, layout: &PieceLayout) -> Result<(Coord, Position), PositionTextError> {
+        let mut tokens = text.split_whitespace();
+
+        let king_tok = tokens.next().ok_or(PositionTextError::Empty)?;
+        let king_rest = king_tok
+            .strip_prefix('k')
+            .ok_or_else(|| PositionTextError::MissingKing(king_tok.to_string()))?;
+        let abs_king = parse_coord(king_rest)?;
+
+        let mut squares = [Square::NONE; MAX_PIECES];
+        let mut count = 0usize;
+
+        for tok in tokens {
+            if count >= layout.piece_count() {
+                return Err(PositionTextError::WrongPieceCount {
+                    expected: layout.piece_count(),
+                    found: count + 1,
+                });
+            }
+
+            let mut chars = tok.chars();
+            let letter = chars.next().ok_or(PositionTextError::Empty)?;
+            let kind = letter_kind(letter).ok_or(PositionTextError::UnknownKind(letter))?;
+            let expected_kind = layout.kind(count);
+            if kind != expected_kind {
+                return Err(PositionTextError::KindMismatch {
+                    index: count,
+                    expected: expected_kind,
+                    found: kind,
+                });
+            }
+
+            let rest: String = chars.collect();
+            squares[count] = if rest == "-" {
+                if Some(count) == layout.white_king_index() {
+                    return Err(PositionTextError::CapturedKing);
+                }
+                Square::NONE
+            } else {
+                Square::from_coord(parse_coord(&rest)? - abs_king)
+            };
+            count += 1;
+        }
+
+        if count != layout.piece_count() {
+            return Err(PositionTextError::WrongPieceCount {
+                expected: layout.piece_count(),
+                found: count,
+            });
+        }
+
+        for i in 0..count {
+            if squares[i].is_none() {
+                continue;
+            }
+            if squares[i].coord() == Coord::ORIGIN || squares[..i].iter().any(|&s| s == squares[i]) {
+                return Err(PositionTextError::DuplicateSquare);
+            }
+        }
+
+        let mut pos = Position::new(count, squares);
+        pos.canonicalize(layout);
+        Ok((abs_king, pos))
     }
 
     pub fn is_occupied(&self, sq: Square) -> bool {
@@ -85,3 +297,223 @@ impl Position {
         self.squares
     }
 }
+
+/// Errors from parsing the textual board format (see [`Position::from_text`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionTextError {
+    /// The input (or a token within it) was empty.
+    Empty,
+    /// The first token did not start with `k` (the black king anchor).
+    MissingKing(String),
+    /// A piece letter was not one of `K Q R B N`.
+    UnknownKind(char),
+    /// A piece's kind did not match `layout` at its position.
+    KindMismatch {
+        index: usize,
+        expected: PieceKind,
+        found: PieceKind,
+    },
+    /// The number of piece tokens did not match `layout.piece_count()`.
+    WrongPieceCount { expected: usize, found: usize },
+    /// A `<x>,<y>` coordinate token failed to parse.
+    InvalidCoord(String),
+    /// No side-to-move token (`btm`/`wtm`) was found.
+    MissingSideToMove,
+    /// The side-to-move token was neither `btm` nor `wtm`.
+    InvalidSideToMove(String),
+    /// Two pieces (or a piece and the black king) occupied the same absolute square.
+    DuplicateSquare,
+    /// The layout's white king was written as captured (`K-`), but the white king can never be
+    /// captured.
+    CapturedKing,
+}
+
+impl fmt::Display for PositionTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionTextError::Empty => write!(f, "empty position text"),
+            PositionTextError::MissingKing(tok) => {
+                write!(f, "expected a king token starting with 'k', got {tok:?}")
+            }
+            PositionTextError::UnknownKind(c) => write!(f, "unknown piece letter {c:?}"),
+            PositionTextError::KindMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "piece {index}: expected {expected:?}, found {found:?}"
+            ),
+            PositionTextError::WrongPieceCount { expected, found } => write!(
+                f,
+                "expected {expected} piece tokens, found {found}"
+            ),
+            PositionTextError::InvalidCoord(tok) => write!(f, "invalid coordinate {tok:?}"),
+            PositionTextError::MissingSideToMove => {
+                write!(f, "missing side-to-move token (expected 'btm' or 'wtm')")
+            }
+            PositionTextError::InvalidSideToMove(tok) => {
+                write!(f, "invalid side-to-move token {tok:?} (expected 'btm' or 'wtm')")
+            }
+            PositionTextError::DuplicateSquare => {
+                write!(f, "two pieces occupy the same square")
+            }
+            PositionTextError::CapturedKing => {
+                write!(f, "the white king cannot be captured")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PositionTextError {}
+
+/// The classical-notation letter for `kind` (`K Q R B N`). Shared with
+/// [`crate::chess::layout::PieceLayout::to_text`], which serializes a bare list of these letters.
+pub(crate) fn kind_letter(kind: PieceKind) -> char {
+    match kind {
+        PieceKind::King => 'K',
+        PieceKind::Queen => 'Q',
+        PieceKind::Rook => 'R',
+        PieceKind::Bishop => 'B',
+        PieceKind::Knight => 'N',
+    }
+}
+
+/// The inverse of [`kind_letter`]; shared with [`crate::chess::layout::PieceLayout::from_text`].
+pub(crate) fn letter_kind(c: char) -> Option<PieceKind> {
+    match c {
+        'K' => Some(PieceKind::King),
+        'Q' => Some(PieceKind::Queen),
+        'R' => Some(PieceKind::Rook),
+        'B' => Some(PieceKind::Bishop),
+        'N' => Some(PieceKind::Knight),
+        _ => None,
+    }
+}
+
+fn parse_coord(s: &str) -> Result<Coord, PositionTextError> {
+    let (xs, ys) = s
+        .split_once(',')
+        .ok_or_else(|| PositionTextError::InvalidCoord(s.to_string()))?;
+    let x: i32 = xs
+        .parse()
+        .map_err(|_| PositionTextError::InvalidCoord(s.to_string()))?;
+    let y: i32 = ys
+        .parse()
+        .map_err(|_| PositionTextError::InvalidCoord(s.to_string()))?;
+    Ok(Coord::new(x, y))
+}
+
+/// The 8 dihedral (D4) symmetries of the square lattice fixing the origin: identity, the three
+/// nontrivial rotations, and the four reflections.
+const D4_TRANSFORMS: [fn(Coord) -> Coord; 8] = [
+    |c| Coord::new(c.x, c.y),
+    |c| Coord::new(-c.y, c.x),
+    |c| Coord::new(-c.x, -c.y),
+    |c| Coord::new(c.y, -c.x),
+    |c| Coord::new(-c.x, c.y),
+    |c| Coord::new(c.x, -c.y),
+    |c| Coord::new(c.y, c.x),
+    |c| Coord::new(-c.y, -c.x),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::layout::PieceLayout;
+
+    #[test]
+    fn d4_fold_collapses_rotated_rook() {
+        let layout = PieceLayout::from_counts(false, 0, 1, 0, 0);
+
+        let mut a = [Square::NONE; MAX_PIECES];
+        a[0] = Square::from_coord(Coord::new(3, 0));
+        let mut pos_a = Position::new(1, a);
+        pos_a.canonicalize_d4(&layout);
+
+        // Rotating the same rook by 90 degrees is a different raw position...
+        let mut b = [Square::NONE; MAX_PIECES];
+        b[0] = Square::from_coord(Coord::new(0, 3));
+        let mut pos_b = Position::new(1, b);
+        assert_ne!(pos_a.squares(), pos_b.squares());
+
+        // ...but folds to the same canonical representative.
+        pos_b.canonicalize_d4(&layout);
+        assert_eq!(pos_a.squares(), pos_b.squares());
+    }
+
+    #[test]
+    fn text_round_trips_through_absolute_coordinates() {
+        let layout = PieceLayout::from_counts(true, 0, 2, 0, 0);
+        let abs_king = Coord::new(5, -3);
+
+        let mut squares = [Square::NONE; MAX_PIECES];
+        squares[0] = Square::from_coord(Coord::new(0, -4) - abs_king); // white king, adjacent-safe
+        squares[1] = Square::from_coord(Coord::new(8, -3) - abs_king); // rook
+        squares[2] = Square::NONE; // captured rook
+        let mut pos = Position::new(3, squares);
+        pos.canonicalize(&layout);
+
+        let text = pos.to_text(abs_king, &layout);
+        let (parsed_king, parsed_pos) = Position::from_text(&text, &layout).unwrap();
+
+        assert_eq!(parsed_king, abs_king);
+        assert_eq!(parsed_pos.squares(), pos.squares());
+    }
+
+    #[test]
+    fn text_rejects_wrong_piece_count() {
+        let layout = PieceLayout::from_counts(false, 0, 1, 0, 0);
+        let err = Position::from_text("k0,0", &layout).unwrap_err();
+        assert_eq!(
+            err,
+            PositionTextError::WrongPieceCount {
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn text_rejects_duplicate_square() {
+        let layout = PieceLayout::from_counts(false, 0, 2, 0, 0);
+        let err = Position::from_text("k0,0 R3,0 R3,0", &layout).unwrap_err();
+        assert_eq!(err, PositionTextError::DuplicateSquare);
+    }
+
+    #[test]
+    fn text_rejects_captured_white_king() {
+        let layout = PieceLayout::from_counts(true, 0, 0, 0, 0);
+        let err = Position::from_text("k0,0 K-", &layout).unwrap_err();
+        assert_eq!(err, PositionTextError::CapturedKing);
+    }
+
+    #[test]
+    fn incremental_set_matches_recompute_from_scratch() {
+        let mut squares = [Square::NONE; MAX_PIECES];
+        squares[0] = Square::from_coord(Coord::new(1, 0));
+        squares[1] = Square::from_coord(Coord::new(2, 2));
+        let mut pos = Position::new(2, squares);
+
+        pos.set(0, Square::from_coord(Coord::new(-3, 4)));
+        pos.set(1, Square::NONE);
+
+        let recomputed = Position::new(2, pos.clone_squares_array());
+        assert_eq!(pos.zobrist(), recomputed.zobrist());
+    }
+
+    #[test]
+    fn zobrist_depends_on_slot_not_just_occupied_set() {
+        let mut a = [Square::NONE; MAX_PIECES];
+        a[0] = Square::from_coord(Coord::new(1, 1));
+        a[1] = Square::from_coord(Coord::new(2, 2));
+        let pos_a = Position::new(2, a);
+
+        let mut b = [Square::NONE; MAX_PIECES];
+        b[0] = Square::from_coord(Coord::new(2, 2));
+        b[1] = Square::from_coord(Coord::new(1, 1));
+        let pos_b = Position::new(2, b);
+
+        assert_ne!(pos_a.zobrist(), pos_b.zobrist());
+    }
+}
@@ -0,0 +1,112 @@
+//! Zobrist-style hashing for transposition tables.
+//!
+//! Classical Zobrist hashing precomputes one random `u64` per `(piece, square)` pair from a fixed
+//! 8x8 table and XORs them together. The board here is unbounded, so there is no finite table to
+//! precompute; instead each key is derived on demand from a fast, well-mixed hash of
+//! `(piece, x, y)`, which is equivalent in spirit (a fixed pseudo-random function of the
+//! coordinate) and just as cheap to query.
+//!
+//! Because keys are derived from **absolute** coordinates, [`crate::scenario::State::zobrist`] is
+//! well-defined regardless of how the king-relative [`crate::core::position::Position`] is
+//! internally canonicalized, and moves that don't touch a given square leave its key untouched —
+//! which is what makes the incremental `toggle_*` updates below correct.
+
+use crate::chess::piece::PieceKind;
+use crate::core::coord::Coord;
+
+/// Domain-separation salt so the black king's key never collides with a piece key, even though
+/// both are ultimately derived from a coordinate.
+const BLACK_KING_SALT: u64 = 0x9E3779B97F4A7C15;
+
+/// SplitMix64: a fast, well-mixed 64-bit hash. Used here as a stand-in for "look up a precomputed
+/// random table entry" over an unbounded coordinate space.
+#[inline]
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[inline]
+fn coord_bits(c: Coord) -> u64 {
+    ((c.x as u32 as u64) << 32) | (c.y as u32 as u64)
+}
+
+/// The Zobrist key for one white piece of `kind` sitting on absolute square `at`.
+///
+/// XOR this into a running hash to place the piece, and XOR it in again to remove it (XOR is its
+/// own inverse, so `toggle_piece` below is just this function by another name).
+#[inline]
+pub fn piece_key(kind: PieceKind, at: Coord) -> u64 {
+    let salt = kind as u64;
+    splitmix64(coord_bits(at) ^ salt.wrapping_mul(0xC2B2AE3D27D4EB4F))
+}
+
+/// The Zobrist key for the black king sitting on absolute square `at`.
+#[inline]
+pub fn black_king_key(at: Coord) -> u64 {
+    splitmix64(coord_bits(at) ^ BLACK_KING_SALT)
+}
+
+/// Toggle (add or remove) a piece of `kind` on absolute square `at` in a running hash.
+#[inline]
+pub fn toggle_piece(hash: u64, kind: PieceKind, at: Coord) -> u64 {
+    hash ^ piece_key(kind, at)
+}
+
+/// Toggle (add or remove) the black king on absolute square `at` in a running hash.
+#[inline]
+pub fn toggle_black_king(hash: u64, at: Coord) -> u64 {
+    hash ^ black_king_key(at)
+}
+
+/// Move the black king from `from` to `to` in a running hash (equivalent to toggling it off at
+/// `from` and on at `to`).
+#[inline]
+pub fn move_black_king(hash: u64, from: Coord, to: Coord) -> u64 {
+    toggle_black_king(toggle_black_king(hash, from), to)
+}
+
+/// Move a white piece of `kind` from `from` to `to` in a running hash.
+#[inline]
+pub fn move_piece(hash: u64, kind: PieceKind, from: Coord, to: Coord) -> u64 {
+    toggle_piece(toggle_piece(hash, kind, from), kind, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_is_its_own_inverse() {
+        let h0 = 0x1234_5678_9abc_def0u64;
+        let h1 = toggle_piece(h0, PieceKind::Rook, Coord::new(3, -2));
+        assert_ne!(h0, h1);
+        let h2 = toggle_piece(h1, PieceKind::Rook, Coord::new(3, -2));
+        assert_eq!(h0, h2);
+    }
+
+    #[test]
+    fn different_squares_give_different_keys() {
+        let a = piece_key(PieceKind::Queen, Coord::new(0, 0));
+        let b = piece_key(PieceKind::Queen, Coord::new(0, 1));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn piece_and_king_keys_do_not_collide() {
+        let at = Coord::new(5, 5);
+        assert_ne!(piece_key(PieceKind::King, at), black_king_key(at));
+    }
+
+    #[test]
+    fn move_piece_matches_two_toggles() {
+        let h0 = 0xdead_beef_u64;
+        let from = Coord::new(1, 1);
+        let to = Coord::new(2, 3);
+        let expected = toggle_piece(toggle_piece(h0, PieceKind::Bishop, from), PieceKind::Bishop, to);
+        assert_eq!(move_piece(h0, PieceKind::Bishop, from, to), expected);
+    }
+}
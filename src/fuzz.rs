@@ -0,0 +1,283 @@
+//! `arbitrary`-based scenario generation for differential fuzzing, gated behind the `fuzz`
+//! feature.
+//!
+//! [`FuzzScenarioInput`] draws a small, `InBox`-bounded scenario from fuzzer bytes;
+//! [`FuzzScenarioInput::build`] assembles it into a [`crate::scenarios::BuiltInScenario`] or
+//! returns `None` for inputs that can't be turned into a legal starting position, which the fuzz
+//! target (see `fuzz/fuzz_targets/bounded_counts.rs`) treats as `Corpus::Reject` rather than a
+//! finding.
+//!
+//! This module, and the fuzz target that drives it, need a `fuzz/Cargo.toml` (declaring
+//! `arbitrary` and `libfuzzer-sys`, plus a path dependency on this crate with `features =
+//! ["fuzz"]`) and a `fuzz` feature entry in this crate's own manifest to actually build under
+//! `cargo fuzz run`. Neither exists in this tree yet, since there is no manifest here at all; this
+//! is the harness logic as it should exist once that wiring lands.
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::chess::layout::PieceLayout;
+use crate::chess::rules::Rules;
+use crate::core::coord::Coord;
+use crate::core::position::{Position, MAX_PIECES};
+use crate::core::square::Square;
+use crate::scenario::{
+    CacheMode, CandidateGeneration, NoLaws, NoPreferences, ResourceLimits, Scenario, Side,
+    StartState, State, TieBreak,
+};
+use crate::scenarios::BuiltinDomain;
+
+/// Per-kind piece count cap, chosen so `white_king + 4*MAX_PER_KIND` stays well under
+/// [`MAX_PIECES`] even before we check `piece_count()` explicitly.
+const MAX_PER_KIND: u8 = 3;
+/// Also the largest bound [`TrapFuzzInput::build`] draws, so `build_at` can always be called with
+/// `bound + 1` for a monotonicity check without leaving this module's resource budget.
+pub const MAX_BOUND: i32 = 6;
+const MAX_MOVE_BOUND: i32 = 4;
+
+/// Raw, not-yet-validated scenario inputs drawn from fuzzer bytes.
+#[derive(Debug)]
+pub struct FuzzScenarioInput {
+    pub white_king: bool,
+    pub queens: u8,
+    pub rooks: u8,
+    pub bishops: u8,
+    pub knights: u8,
+    pub move_bound: i32,
+    pub bound: i32,
+    pub allow_captures: bool,
+}
+
+impl<'a> Arbitrary<'a> for FuzzScenarioInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            white_king: u.arbitrary()?,
+            queens: u.int_in_range(0..=MAX_PER_KIND)?,
+            rooks: u.int_in_range(0..=MAX_PER_KIND)?,
+            bishops: u.int_in_range(0..=MAX_PER_KIND)?,
+            knights: u.int_in_range(0..=MAX_PER_KIND)?,
+            move_bound: u.int_in_range(1..=MAX_MOVE_BOUND)?,
+            bound: u.int_in_range(1..=MAX_BOUND)?,
+            allow_captures: u.arbitrary()?,
+        })
+    }
+}
+
+impl FuzzScenarioInput {
+    /// Assemble `self` into a black-to-move, `InBox`-candidate scenario, or `None` if `self.bound`
+    /// has no room for a non-overlapping start placement, or the resulting start is illegal or
+    /// already in check (built-in scenarios all start quiet; mirror that here rather than teaching
+    /// the harness about scenarios that start in check).
+    pub fn build(&self) -> Option<Scenario<BuiltinDomain, NoLaws, NoPreferences>> {
+        let layout = PieceLayout::from_counts(
+            self.white_king,
+            self.queens as usize,
+            self.rooks as usize,
+            self.bishops as usize,
+            self.knights as usize,
+        );
+        if layout.piece_count() > MAX_PIECES {
+            return None;
+        }
+
+        let rules = Rules::new(layout.clone(), self.move_bound);
+
+        let mut squares = [Square::NONE; MAX_PIECES];
+        let mut used: Vec<Coord> = vec![Coord::ORIGIN];
+        for slot in squares.iter_mut().take(layout.piece_count()) {
+            let c = first_free_square(self.bound, &used)?;
+            used.push(c);
+            *slot = Square::from_coord(c);
+        }
+
+        let mut pos = Position::new(layout.piece_count(), squares);
+        pos.canonicalize(&layout);
+
+        if !rules.is_legal_position(&pos) {
+            return None;
+        }
+        if rules.is_attacked(Coord::ORIGIN, &pos) {
+            return None;
+        }
+
+        Some(Scenario {
+            name: "fuzz",
+            rules,
+            white_can_pass: true,
+            track_abs_king: true,
+            start: StartState {
+                to_move: Side::Black,
+                state: State::new(Coord::ORIGIN, pos),
+            },
+            candidates: CandidateGeneration::InBox {
+                bound: self.bound,
+                allow_captures: self.allow_captures,
+            },
+            domain: BuiltinDomain::AbsBox { bound: self.bound },
+            laws: NoLaws,
+            preferences: NoPreferences,
+            tie_break: TieBreak::Forwards,
+            limits: fuzz_limits(),
+            cache_mode: CacheMode::BothBounded,
+            remove_stalemates: true,
+        })
+    }
+}
+
+/// The first square in `[-bound, bound]^2` (row-major, so the search is deterministic across
+/// fuzzer runs) not already in `used`.
+fn first_free_square(bound: i32, used: &[Coord]) -> Option<Coord> {
+    for y in -bound..=bound {
+        for x in -bound..=bound {
+            let c = Coord::new(x, y);
+            if !used.contains(&c) {
+                return Some(c);
+            }
+        }
+    }
+    None
+}
+
+/// Small resource limits so a single fuzz iteration stays bounded even for the worst-case
+/// move_bound/piece-count/bound combination [`FuzzScenarioInput::arbitrary`] can draw.
+fn fuzz_limits() -> ResourceLimits {
+    ResourceLimits {
+        max_states: 200_000,
+        max_edges: 2_000_000,
+        max_cache_entries: 50_000,
+        max_cached_moves: 500_000,
+        max_runtime_steps: 5_000_000,
+        parallel_attractor: false,
+        parallel_trap: false,
+    }
+}
+
+impl<'a> Arbitrary<'a> for PieceLayout {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(PieceLayout::from_counts(
+            u.arbitrary()?,
+            u.int_in_range(0..=MAX_PER_KIND as usize)?,
+            u.int_in_range(0..=MAX_PER_KIND as usize)?,
+            u.int_in_range(0..=MAX_PER_KIND as usize)?,
+            u.int_in_range(0..=MAX_PER_KIND as usize)?,
+        ))
+    }
+}
+
+/// Draws a raw, possibly-illegal `Position`: a random occupied-slot count and, per slot, either
+/// `Square::NONE` or a square within `[-MAX_BOUND, MAX_BOUND]^2`. Unlike [`FuzzScenarioInput`]
+/// and [`TrapFuzzInput`] (which place a specific [`PieceLayout`]'s pieces on non-overlapping
+/// squares), this makes no attempt at legality — `Rules::is_legal_position` is what's supposed to
+/// reject the results that don't make sense, exactly as it does for every other `Position` this
+/// crate touches.
+impl<'a> Arbitrary<'a> for Position {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let count = u.int_in_range(0..=MAX_PIECES)?;
+        let mut squares = [Square::NONE; MAX_PIECES];
+        for slot in squares.iter_mut().take(count) {
+            if u.arbitrary()? {
+                let x = u.int_in_range(-MAX_BOUND..=MAX_BOUND)?;
+                let y = u.int_in_range(-MAX_BOUND..=MAX_BOUND)?;
+                *slot = Square::from_coord(Coord::new(x, y));
+            }
+        }
+        Ok(Position::new(count, squares))
+    }
+}
+
+impl<'a> Arbitrary<'a> for State {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let x = u.int_in_range(-MAX_BOUND..=MAX_BOUND)?;
+        let y = u.int_in_range(-MAX_BOUND..=MAX_BOUND)?;
+        Ok(State::new(Coord::new(x, y), u.arbitrary()?))
+    }
+}
+
+/// A trap-solver-specific scenario input: draws a [`PieceLayout`] directly (via its `Arbitrary`
+/// impl above) rather than [`FuzzScenarioInput`]'s raw counts, and builds an `InLinfBound`
+/// scenario shaped for [`crate::search::trap::maximal_inescapable_trap`]'s invariants (see
+/// `fuzz/fuzz_targets/trap_invariants.rs`).
+#[derive(Debug)]
+pub struct TrapFuzzInput {
+    pub layout: PieceLayout,
+    pub move_bound: i32,
+    pub bound: i32,
+    pub allow_captures: bool,
+}
+
+impl<'a> Arbitrary<'a> for TrapFuzzInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            layout: u.arbitrary()?,
+            move_bound: u.int_in_range(1..=MAX_MOVE_BOUND)?,
+            bound: u.int_in_range(1..=MAX_BOUND)?,
+            allow_captures: u.arbitrary()?,
+        })
+    }
+}
+
+impl TrapFuzzInput {
+    /// Assemble `self` into a quiet, black-to-move `InLinfBound` scenario whose candidates are
+    /// generated at `bound` (rather than `self.bound`), or `None` under the same rejection policy
+    /// as [`FuzzScenarioInput::build`] (layout too big, or no quiet legal start placement fits).
+    ///
+    /// `track_abs_king` is `false` and the domain is [`BuiltinDomain::All`): `InLinfBound`
+    /// candidates always seed `abs_king = Coord::ORIGIN`, so this is the translation-reduced
+    /// search `InLinfBound` is meant for, not [`BuiltinDomain::AbsBox`]'s absolute-anchor tracking
+    /// (that pairs with `InBox`; see `search::universe`).
+    ///
+    /// Letting the caller override `bound` independently of `self.bound` is what makes the
+    /// monotonicity check in `fuzz/fuzz_targets/trap_invariants.rs` possible: the same layout and
+    /// start placement, re-enumerated at a larger bound.
+    pub fn build_at(&self, bound: i32) -> Option<Scenario<BuiltinDomain, NoLaws, NoPreferences>> {
+        if self.layout.piece_count() > MAX_PIECES {
+            return None;
+        }
+
+        let rules = Rules::new(self.layout.clone(), self.move_bound);
+
+        let mut squares = [Square::NONE; MAX_PIECES];
+        let mut used: Vec<Coord> = vec![Coord::ORIGIN];
+        for slot in squares.iter_mut().take(self.layout.piece_count()) {
+            let c = first_free_square(bound, &used)?;
+            used.push(c);
+            *slot = Square::from_coord(c);
+        }
+
+        let mut pos = Position::new(self.layout.piece_count(), squares);
+        pos.canonicalize(&self.layout);
+
+        if !rules.is_legal_position(&pos) {
+            return None;
+        }
+        if rules.is_attacked(Coord::ORIGIN, &pos) {
+            return None;
+        }
+
+        Some(Scenario {
+            name: "fuzz_trap",
+            rules,
+            white_can_pass: true,
+            track_abs_king: false,
+            start: StartState {
+                to_move: Side::Black,
+                state: State::new(Coord::ORIGIN, pos),
+            },
+            candidates: CandidateGeneration::InLinfBound {
+                bound,
+                allow_captures: self.allow_captures,
+            },
+            domain: BuiltinDomain::All,
+            laws: NoLaws,
+            preferences: NoPreferences,
+            tie_break: TieBreak::Forwards,
+            limits: fuzz_limits(),
+            cache_mode: CacheMode::BothBounded,
+            remove_stalemates: true,
+        })
+    }
+
+    /// [`TrapFuzzInput::build_at`] at `self.bound`.
+    pub fn build(&self) -> Option<Scenario<BuiltinDomain, NoLaws, NoPreferences>> {
+        self.build_at(self.bound)
+    }
+}
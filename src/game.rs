@@ -1,7 +1,8 @@
 use crate::coord::Coord;
-use crate::pieces::{Layout, Material};
-use crate::region::Region;
+use crate::pieces::{Layout, Material, MovementRegistry};
+use crate::region::{BoundKind, MoveRays, Region, RegionAttackTables};
 use crate::state::{canonicalize, PackedState, Packer};
+use crate::zobrist::ZobristKeys;
 
 #[derive(Clone, Debug)]
 pub struct Game {
@@ -10,21 +11,57 @@ pub struct Game {
     pub packer: Packer,
     pub allow_pass: bool,
     pub move_bound: Option<u16>,
+    /// Whether `move_bound` counts the number of reachable squares along a ray or stops one short
+    /// of it; see [`BoundKind`]. Defaults to [`BoundKind::Inclusive`], matching this engine's
+    /// existing convention (`trap_tester.py`'s `step <= bound`).
+    pub bound_kind: BoundKind,
+    /// Precomputed king/knight/slider attack data for `region`, used by
+    /// `rules::attacks::is_attacked_by_white` to turn per-piece attack queries into table lookups.
+    pub attack_tables: RegionAttackTables,
+    /// Zobrist hashing for this game's packed states (see [`ZobristKeys`]), keyed off the same
+    /// `packer` above.
+    pub zobrist: ZobristKeys,
+    /// Precomputed rider destination squares for `move_bound`/`bound_kind` (see
+    /// [`rules::movegen::gen_piece_movement`](crate::rules::movegen::gen_piece_movement)), rebuilt
+    /// by [`Self::with_move_bound`]/[`Self::with_bound_kind`] whenever either changes.
+    pub move_rays: MoveRays,
+    /// Per-kind move/attack descriptors consumed by
+    /// [`rules::movegen::gen_piece_movement`](crate::rules::movegen::gen_piece_movement) and
+    /// `rules::attacks`, letting a caller swap in fairy-piece material (see
+    /// [`MovementRegistry::with_movement`]) without touching either module. Defaults to
+    /// [`MovementRegistry::classical`].
+    pub movement_registry: MovementRegistry,
 }
 
 impl Game {
     pub fn new(region: Region, material: Material) -> Self {
         let layout = Layout::from_material(&material);
         let packer = Packer::new(region.size() as u16, layout.total_white());
+        let attack_tables = RegionAttackTables::build(&region);
+        let zobrist = ZobristKeys::new(packer.clone(), layout.clone());
+        let bound_kind = BoundKind::default();
+        let move_rays = MoveRays::build(&region, None, bound_kind);
         Self {
             region,
             layout,
             packer,
             allow_pass: false,
             move_bound: None,
+            bound_kind,
+            attack_tables,
+            zobrist,
+            move_rays,
+            movement_registry: MovementRegistry::classical(),
         }
     }
 
+    /// Swap in custom per-kind move/attack descriptors (e.g. fairy-piece material); see
+    /// [`MovementRegistry`].
+    pub fn with_movement_registry(mut self, registry: MovementRegistry) -> Self {
+        self.movement_registry = registry;
+        self
+    }
+
     pub fn with_allow_pass(mut self, allow: bool) -> Self {
         self.allow_pass = allow;
         self
@@ -32,6 +69,15 @@ impl Game {
 
     pub fn with_move_bound(mut self, bound: Option<u16>) -> Self {
         self.move_bound = bound;
+        self.move_rays = MoveRays::build(&self.region, self.move_bound, self.bound_kind);
+        self
+    }
+
+    /// Switch whether `move_bound` is interpreted as inclusive or exclusive (see [`BoundKind`]),
+    /// rebuilding [`Self::move_rays`] to match.
+    pub fn with_bound_kind(mut self, bound_kind: BoundKind) -> Self {
+        self.bound_kind = bound_kind;
+        self.move_rays = MoveRays::build(&self.region, self.move_bound, self.bound_kind);
         self
     }
 
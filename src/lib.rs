@@ -37,3 +37,9 @@ pub mod core;
 pub mod scenario;
 pub mod scenarios;
 pub mod search;
+
+/// `arbitrary`-based scenario generation for the differential fuzz harness (see
+/// `fuzz/fuzz_targets/bounded_counts.rs`). Requires a `fuzz` feature entry and an `arbitrary`
+/// dependency in a manifest this tree doesn't currently have; see [`fuzz`]'s doc comment.
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
@@ -1,3 +1,6 @@
+use crate::coord::Coord;
+use crate::rules::rays::{BISHOP_DIRS, KING_DIRS, KNIGHT_OFFSETS, QUEEN_DIRS, ROOK_DIRS};
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum PieceKind {
     King,
@@ -5,6 +8,14 @@ pub enum PieceKind {
     Rook,
     Bishop,
     Knight,
+    /// Attacks the two squares diagonally forward (see [`Layout::pawn_forward`]); moves are a
+    /// single non-capturing push along the same direction. Unlike every other kind, a pawn's
+    /// attack pattern isn't symmetric under reflection, which is why it needs a direction at all.
+    /// On reaching [`Layout::promotion_rank`], a pawn's slot is vacated in favor of a reserve slot
+    /// of the chosen kind (see [`Material::promotion_kinds`]) rather than changing its own kind in
+    /// place — every other module (`canonicalize`, `rules::attacks`, `zobrist`) already treats a
+    /// slot's [`PieceKind`] as fixed for the slot's lifetime, and this keeps that true.
+    Pawn,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -35,6 +46,19 @@ pub struct Material {
     pub rooks: u8,
     pub bishops: u8,
     pub knights: u8,
+    pub pawns: u8,
+    /// The unit step a pawn's forward (non-capturing) push and two diagonal attack squares are
+    /// measured from. Only meaningful when `pawns > 0`; `Coord::new(0, 1)` (pushing towards +y)
+    /// otherwise.
+    pub pawn_forward: Coord,
+    /// The `y` coordinate (measured along `pawn_forward`) a pawn promotes on reaching. `None`
+    /// means this material's pawns never promote, and just keep pushing until they run off the
+    /// region (this engine's original pawn behavior). See [`Self::with_promotion`].
+    pub promotion_rank: Option<i32>,
+    /// Kinds a pawn reaching `promotion_rank` may promote to. [`Layout::from_material`] reserves
+    /// `pawns` extra slots per kind listed here — one per pawn, so every pawn can promote to that
+    /// kind independently of how the others promote — alongside that kind's ordinary material.
+    pub promotion_kinds: Vec<PieceKind>,
 }
 
 impl Material {
@@ -45,6 +69,10 @@ impl Material {
             rooks: 0,
             bishops: 0,
             knights: 0,
+            pawns: 0,
+            pawn_forward: Coord::new(0, 1),
+            promotion_rank: None,
+            promotion_kinds: Vec::new(),
         }
     }
 
@@ -73,12 +101,60 @@ impl Material {
         self
     }
 
+    pub fn with_pawns(mut self, n: u8) -> Self {
+        self.pawns = n;
+        self
+    }
+
+    /// `dir` must be a unit step with `x == 0` and `y == 1` or `y == -1`; pawns in this engine
+    /// only ever push straight along one of the two board axes.
+    pub fn with_pawn_forward(mut self, dir: Coord) -> Self {
+        self.pawn_forward = dir;
+        self
+    }
+
+    /// Pawns promote on reaching `rank` (see [`Self::promotion_rank`]), to any of `kinds`.
+    pub fn with_promotion(mut self, rank: i32, kinds: Vec<PieceKind>) -> Self {
+        assert!(
+            !kinds.is_empty(),
+            "a pawn reaching the promotion rank needs at least one allowed promotion kind"
+        );
+        self.promotion_rank = Some(rank);
+        self.promotion_kinds = kinds;
+        self
+    }
+
+    /// `kind`'s base count plus, if pawns may promote to it, `pawns` reserve slots (see
+    /// [`Self::promotion_kinds`]).
+    fn slot_count(&self, kind: PieceKind) -> usize {
+        let base = match kind {
+            PieceKind::King => self.white_king as usize,
+            PieceKind::Queen => self.queens as usize,
+            PieceKind::Rook => self.rooks as usize,
+            PieceKind::Bishop => self.bishops as usize,
+            PieceKind::Knight => self.knights as usize,
+            PieceKind::Pawn => self.pawns as usize,
+        };
+        let reserve = if self.promotion_kinds.contains(&kind) {
+            self.pawns as usize
+        } else {
+            0
+        };
+        base + reserve
+    }
+
     pub fn total_white(&self) -> usize {
-        (self.white_king as usize)
-            + (self.queens as usize)
-            + (self.rooks as usize)
-            + (self.bishops as usize)
-            + (self.knights as usize)
+        [
+            PieceKind::King,
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            PieceKind::Pawn,
+        ]
+        .iter()
+        .map(|&k| self.slot_count(k))
+        .sum()
     }
 }
 
@@ -95,6 +171,12 @@ pub struct Group {
 pub struct Layout {
     pub slots: Vec<PieceKind>,
     pub groups: Vec<Group>,
+    /// See [`Material::pawn_forward`].
+    pub pawn_forward: Coord,
+    /// See [`Material::promotion_rank`].
+    pub promotion_rank: Option<i32>,
+    /// See [`Material::promotion_kinds`].
+    pub promotion_kinds: Vec<PieceKind>,
 }
 
 impl Layout {
@@ -102,26 +184,54 @@ impl Layout {
         let mut slots = Vec::with_capacity(mat.total_white());
         let mut groups = Vec::new();
 
-        let push_group = |kind: PieceKind, len: usize, slots: &mut Vec<PieceKind>, groups: &mut Vec<Group>| {
-            if len == 0 {
-                return;
-            }
-            let start = slots.len();
-            for _ in 0..len {
-                slots.push(kind);
-            }
-            groups.push(Group { kind, start, len });
-        };
+        let push_group =
+            |kind: PieceKind, len: usize, slots: &mut Vec<PieceKind>, groups: &mut Vec<Group>| {
+                if len == 0 {
+                    return;
+                }
+                let start = slots.len();
+                for _ in 0..len {
+                    slots.push(kind);
+                }
+                groups.push(Group { kind, start, len });
+            };
 
         if mat.white_king {
             push_group(PieceKind::King, 1, &mut slots, &mut groups);
         }
-        push_group(PieceKind::Queen, mat.queens as usize, &mut slots, &mut groups);
-        push_group(PieceKind::Rook, mat.rooks as usize, &mut slots, &mut groups);
-        push_group(PieceKind::Bishop, mat.bishops as usize, &mut slots, &mut groups);
-        push_group(PieceKind::Knight, mat.knights as usize, &mut slots, &mut groups);
+        push_group(
+            PieceKind::Queen,
+            mat.slot_count(PieceKind::Queen),
+            &mut slots,
+            &mut groups,
+        );
+        push_group(
+            PieceKind::Rook,
+            mat.slot_count(PieceKind::Rook),
+            &mut slots,
+            &mut groups,
+        );
+        push_group(
+            PieceKind::Bishop,
+            mat.slot_count(PieceKind::Bishop),
+            &mut slots,
+            &mut groups,
+        );
+        push_group(
+            PieceKind::Knight,
+            mat.slot_count(PieceKind::Knight),
+            &mut slots,
+            &mut groups,
+        );
+        push_group(PieceKind::Pawn, mat.pawns as usize, &mut slots, &mut groups);
 
-        Self { slots, groups }
+        Self {
+            slots,
+            groups,
+            pawn_forward: mat.pawn_forward,
+            promotion_rank: mat.promotion_rank,
+            promotion_kinds: mat.promotion_kinds.clone(),
+        }
     }
 
     pub fn total_white(&self) -> usize {
@@ -132,4 +242,219 @@ impl Layout {
     pub fn group(&self, kind: PieceKind) -> Option<&Group> {
         self.groups.iter().find(|g| g.kind == kind)
     }
+
+    /// Whether a pawn standing at `y` (its coordinate along [`Self::pawn_forward`]) has reached
+    /// the promotion rank, per [`Material::with_promotion`].
+    pub fn promotion_reached(&self, y: i32) -> bool {
+        match self.promotion_rank {
+            None => false,
+            Some(rank) => {
+                if self.pawn_forward.y > 0 {
+                    y >= rank
+                } else {
+                    y <= rank
+                }
+            }
+        }
+    }
+}
+
+/// A data-driven description of how a piece moves (and, in `rules::attacks`, attacks): a set of
+/// single-step `leaps` (king/knight-style, each tried once) plus a set of unit `rides` (rook/
+/// bishop/queen-style, walked until blocked or out of the region).
+///
+/// Combining the two lets a single descriptor express compound fairy pieces — a Chancellor
+/// (rook + knight) is `PieceMovement { leaps: KNIGHT_OFFSETS.to_vec(), rides: ROOK_DIRS.to_vec(),
+/// ride_bound: None }` — without `rules::movegen`/`rules::attacks` needing a case for it.
+///
+/// `ride_bound`, if set, caps how many squares a ride may travel (a Camel-ish bounded slider);
+/// `None` means "as far as the region allows". Move generation (`rules::movegen::gen_piece_movement`)
+/// honors this bound; `rules::attacks`' table-backed fast path does not, since `RegionAttackTables`
+/// precomputes unbounded rays (see that module's doc comment) — a piece with a custom `ride_bound`
+/// is routed through the slower scan path instead, which does not enforce the bound either: attack
+/// detection treats a bounded rider as unbounded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PieceMovement {
+    pub leaps: Vec<Coord>,
+    pub rides: Vec<Coord>,
+    pub ride_bound: Option<u16>,
+}
+
+impl PieceMovement {
+    pub fn king() -> Self {
+        Self {
+            leaps: KING_DIRS.to_vec(),
+            rides: Vec::new(),
+            ride_bound: None,
+        }
+    }
+
+    pub fn knight() -> Self {
+        Self {
+            leaps: KNIGHT_OFFSETS.to_vec(),
+            rides: Vec::new(),
+            ride_bound: None,
+        }
+    }
+
+    pub fn rook() -> Self {
+        Self {
+            leaps: Vec::new(),
+            rides: ROOK_DIRS.to_vec(),
+            ride_bound: None,
+        }
+    }
+
+    pub fn bishop() -> Self {
+        Self {
+            leaps: Vec::new(),
+            rides: BISHOP_DIRS.to_vec(),
+            ride_bound: None,
+        }
+    }
+
+    pub fn queen() -> Self {
+        Self {
+            leaps: Vec::new(),
+            rides: QUEEN_DIRS.to_vec(),
+            ride_bound: None,
+        }
+    }
+}
+
+/// Maps each non-pawn [`PieceKind`] to the [`PieceMovement`] it should generate/attack with.
+///
+/// Pawn is deliberately excluded: its push/attack split is asymmetric (see [`PieceKind::Pawn`]'s
+/// doc comment) and doesn't fit the leap/ride model, so `rules::movegen`/`rules::attacks` keep
+/// pawn's hand-written special case regardless of what registry a [`Game`](crate::game::Game) uses.
+///
+/// [`MovementRegistry::classical`] reproduces this engine's original hard-coded K/Q/R/B/N moves.
+/// [`Self::with_movement`] overrides one kind's entry to build custom (fairy-piece) material —
+/// e.g. swapping in an Amazon (queen + knight) wherever `PieceKind::Queen` appears in a
+/// [`Layout`] — without touching `rules::movegen`/`rules::attacks` at all.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovementRegistry {
+    king: PieceMovement,
+    queen: PieceMovement,
+    rook: PieceMovement,
+    bishop: PieceMovement,
+    knight: PieceMovement,
+}
+
+impl MovementRegistry {
+    pub fn classical() -> Self {
+        Self {
+            king: PieceMovement::king(),
+            queen: PieceMovement::queen(),
+            rook: PieceMovement::rook(),
+            bishop: PieceMovement::bishop(),
+            knight: PieceMovement::knight(),
+        }
+    }
+
+    /// Override `kind`'s movement descriptor. Panics for `PieceKind::Pawn`, which has no entry
+    /// (see this type's doc comment).
+    pub fn with_movement(mut self, kind: PieceKind, movement: PieceMovement) -> Self {
+        match kind {
+            PieceKind::King => self.king = movement,
+            PieceKind::Queen => self.queen = movement,
+            PieceKind::Rook => self.rook = movement,
+            PieceKind::Bishop => self.bishop = movement,
+            PieceKind::Knight => self.knight = movement,
+            PieceKind::Pawn => panic!("MovementRegistry has no entry for PieceKind::Pawn"),
+        }
+        self
+    }
+
+    /// The movement descriptor for `kind`, or `None` for `PieceKind::Pawn`.
+    pub fn get(&self, kind: PieceKind) -> Option<&PieceMovement> {
+        match kind {
+            PieceKind::King => Some(&self.king),
+            PieceKind::Queen => Some(&self.queen),
+            PieceKind::Rook => Some(&self.rook),
+            PieceKind::Bishop => Some(&self.bishop),
+            PieceKind::Knight => Some(&self.knight),
+            PieceKind::Pawn => None,
+        }
+    }
+
+    /// Whether `kind`'s entry is still [`MovementRegistry::classical`]'s built-in preset, i.e.
+    /// hasn't been overridden via [`Self::with_movement`]. `rules::attacks` uses this to decide
+    /// whether a slot can use the precomputed table fast path or needs the generic scan fallback.
+    pub fn is_classical(&self, kind: PieceKind) -> bool {
+        match self.get(kind) {
+            Some(movement) => match kind {
+                PieceKind::King => *movement == PieceMovement::king(),
+                PieceKind::Queen => *movement == PieceMovement::queen(),
+                PieceKind::Rook => *movement == PieceMovement::rook(),
+                PieceKind::Bishop => *movement == PieceMovement::bishop(),
+                PieceKind::Knight => *movement == PieceMovement::knight(),
+                PieceKind::Pawn => unreachable!(),
+            },
+            None => true,
+        }
+    }
+}
+
+impl Default for MovementRegistry {
+    fn default() -> Self {
+        Self::classical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promotion_reached_none_without_promotion_rank() {
+        let mat = Material::new().with_pawns(1);
+        let layout = Layout::from_material(&mat);
+        assert!(!layout.promotion_reached(0));
+        assert!(!layout.promotion_reached(100));
+    }
+
+    #[test]
+    fn promotion_reached_for_pawns_pushing_toward_positive_y() {
+        let mat = Material::new()
+            .with_pawns(1)
+            .with_queens(1)
+            .with_pawn_forward(Coord::new(0, 1))
+            .with_promotion(7, vec![PieceKind::Queen]);
+        let layout = Layout::from_material(&mat);
+
+        assert!(!layout.promotion_reached(6));
+        assert!(layout.promotion_reached(7));
+        assert!(layout.promotion_reached(8));
+    }
+
+    #[test]
+    fn promotion_reached_for_pawns_pushing_toward_negative_y() {
+        let mat = Material::new()
+            .with_pawns(1)
+            .with_queens(1)
+            .with_pawn_forward(Coord::new(0, -1))
+            .with_promotion(-7, vec![PieceKind::Queen]);
+        let layout = Layout::from_material(&mat);
+
+        assert!(!layout.promotion_reached(-6));
+        assert!(layout.promotion_reached(-7));
+        assert!(layout.promotion_reached(-8));
+    }
+
+    #[test]
+    fn from_material_reserves_one_slot_per_pawn_per_promotion_kind() {
+        let mat = Material::new()
+            .with_pawns(2)
+            .with_queens(1)
+            .with_rooks(1)
+            .with_promotion(7, vec![PieceKind::Queen, PieceKind::Rook]);
+        let layout = Layout::from_material(&mat);
+
+        // 1 base queen + 2 reserve, 1 base rook + 2 reserve, 2 pawns.
+        assert_eq!(layout.group(PieceKind::Queen).unwrap().len, 3);
+        assert_eq!(layout.group(PieceKind::Rook).unwrap().len, 3);
+        assert_eq!(layout.group(PieceKind::Pawn).unwrap().len, 2);
+        assert_eq!(layout.total_white(), 8);
+    }
 }
@@ -1,4 +1,5 @@
 use crate::coord::Coord;
+use crate::pieces::PieceKind;
 use std::collections::{HashMap, VecDeque};
 
 /// A finite set of coordinates on the infinite chessboard.
@@ -117,6 +118,15 @@ impl Region {
         self.sq_of(coord).is_some()
     }
 
+    /// Whether every coordinate in this region's bounding box is actually present, i.e. the
+    /// region is a solid rectangle rather than some other shape (`l1`/`knight_distance` balls, or
+    /// a box with holes). [`MagicTables::build`] only applies to dense rectangular regions — a
+    /// magic multiply addresses squares by their position in the bounding box, which only matches
+    /// up with the region's actual contents when there are no holes.
+    pub fn is_dense_box(&self) -> bool {
+        self.coords.len() == (self.width as usize) * (self.height as usize)
+    }
+
     fn from_coords(mut coords: Vec<Coord>) -> Self {
         coords.sort_by_key(|c| (c.x, c.y));
         coords.dedup();
@@ -151,3 +161,625 @@ impl Region {
         }
     }
 }
+
+/// A bitset over region square indices, one bit per [`Region::size`] entry.
+#[derive(Clone, Debug)]
+pub struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    pub fn new(num_squares: usize) -> Self {
+        let words = (num_squares + 63) / 64;
+        Self {
+            words: vec![0; words],
+        }
+    }
+
+    #[inline]
+    pub fn set(&mut self, sq: u16) {
+        let i = sq as usize;
+        self.words[i >> 6] |= 1u64 << (i & 63);
+    }
+
+    #[inline]
+    pub fn clear(&mut self, sq: u16) {
+        let i = sq as usize;
+        self.words[i >> 6] &= !(1u64 << (i & 63));
+    }
+
+    #[inline]
+    pub fn get(&self, sq: u16) -> bool {
+        let i = sq as usize;
+        (self.words[i >> 6] >> (i & 63)) & 1u64 == 1u64
+    }
+
+    #[inline]
+    pub fn or_with(&mut self, other: &Bitset) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// This bitset's first word as a raw `u64`. Meaningful only when it was built over at most 64
+    /// squares (see [`MagicTables`]) — a bitset over a larger region has occupancy in later words
+    /// this ignores.
+    #[inline]
+    fn low_word(&self) -> u64 {
+        self.words.first().copied().unwrap_or(0)
+    }
+
+    /// The inverse of [`Bitset::low_word`]: a bitset over `num_squares` (`<= 64`) squares with
+    /// `bits` as its single word.
+    #[inline]
+    fn from_low_word(num_squares: usize, bits: u64) -> Self {
+        let mut b = Bitset::new(num_squares);
+        if let Some(word) = b.words.first_mut() {
+            *word = bits;
+        }
+        b
+    }
+}
+
+/// The 8 ray directions, grouped so that `[0..4]` are the rook (orthogonal) directions and
+/// `[4..8]` are the bishop (diagonal) directions; a queen uses all 8.
+const RAY_DIRS: [Coord; 8] = [
+    Coord::new(1, 0),
+    Coord::new(-1, 0),
+    Coord::new(0, 1),
+    Coord::new(0, -1),
+    Coord::new(1, 1),
+    Coord::new(1, -1),
+    Coord::new(-1, 1),
+    Coord::new(-1, -1),
+];
+
+/// The index into [`RAY_DIRS`] of the unit step `step`, for callers (e.g.
+/// `rules::attacks::piece_attacks`) that compute a slider's direction as a `(signum dx, signum
+/// dy)` pair and need to look it up in a [`RayTable`]/[`RegionAttackTables`].
+///
+/// Panics if `step` is not one of the 8 `RAY_DIRS` unit vectors.
+pub(crate) fn ray_dir_index(step: Coord) -> usize {
+    RAY_DIRS
+        .iter()
+        .position(|&d| d == step)
+        .expect("step must be a unit ray direction")
+}
+
+/// Precomputed, per-square, per-direction ray data for a fixed [`Region`]: for each square and
+/// each of the 8 [`RAY_DIRS`], the ordered `(square_index, Coord)` pairs of in-region squares
+/// along that ray (nearest first), truncated at the region boundary.
+///
+/// This is what lets `rules::attacks::ray_clear` become a lookup-and-scan over a cached list
+/// instead of per-step coordinate arithmetic and `Region::sq_of` hashing: see
+/// [`RayTable::ray_clear`].
+#[derive(Clone, Debug)]
+pub struct RayTable {
+    rays: Vec<[Vec<(u16, Coord)>; 8]>,
+}
+
+impl RayTable {
+    pub fn build(region: &Region) -> Self {
+        let n = region.size();
+        let mut rays: Vec<[Vec<(u16, Coord)>; 8]> = Vec::with_capacity(n);
+
+        for sq in 0..n as u16 {
+            let from = region.coord_of(sq);
+            let mut dir_rays: [Vec<(u16, Coord)>; 8] = Default::default();
+            for (i, dir) in RAY_DIRS.iter().enumerate() {
+                let mut cur = Coord::new(from.x + dir.x, from.y + dir.y);
+                while let Some(dst) = region.sq_of(cur) {
+                    dir_rays[i].push((dst, cur));
+                    cur = Coord::new(cur.x + dir.x, cur.y + dir.y);
+                }
+            }
+            rays.push(dir_rays);
+        }
+
+        Self { rays }
+    }
+
+    /// True iff a slider on `from_sq`, stepping along `RAY_DIRS[dir_idx]`, reaches `target` with
+    /// no `occ`-set square strictly in between.
+    ///
+    /// `target` may lie outside the region (checking a king's escape move into the sink): once
+    /// the cached list runs out at the region boundary with no blocker seen, the ray is clear the
+    /// rest of the way too, since every occupant of `occ` lives inside the region by construction.
+    pub fn ray_clear(&self, from_sq: u16, dir_idx: usize, target: Coord, occ: &Bitset) -> bool {
+        for &(sq, coord) in &self.rays[from_sq as usize][dir_idx] {
+            if occ.get(sq) {
+                return false;
+            }
+            if coord == target {
+                return true;
+            }
+        }
+        true
+    }
+}
+
+/// Whether a `move_bound` counts the number of reachable squares along a ray (`Inclusive`, e.g.
+/// `trap_tester.py`'s `step <= bound`, which is what this engine has always used) or stops one
+/// short of that (`Exclusive`, e.g. `infinite_tablebase.py`'s `step < bound`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundKind {
+    #[default]
+    Inclusive,
+    Exclusive,
+}
+
+/// Precomputed, per-square, per-direction destination-square lists for rider move generation
+/// (`rules::movegen::gen_piece_movement`'s ride loop), replacing the old relative-offset
+/// `rules::rays::RayTable` (renamed away since this superseded it), which re-walked `Region::sq_of`
+/// one step at a time for every candidate square. Squares are truncated at `move_bound`/
+/// `bound_kind` (see [`BoundKind`]) and/or the region boundary, whichever comes first.
+///
+/// Stepping a fixed direction always changes the square index by a consistent sign: [`coord_of`]/
+/// [`sq_of`]'s indexing sorts squares by `(x, y)`, so along any of the 8 [`RAY_DIRS`] the index is
+/// monotonic (strictly increasing, decreasing, or — vertical moves only — tied and then broken by
+/// `y`) for every square on the same ray, regardless of the region's shape. For a region of at most
+/// 64 squares, that makes a ray's destination squares a monotonic run of bits in one `u64`, so
+/// [`Self::ride`] can find the first blocker with a `trailing_zeros`/`leading_zeros` instead of a
+/// square-by-square [`Bitset::get`] scan; [`Self::blocker_masks`] is `None` above 64 squares, and
+/// [`Self::ride`] falls back to scanning its precomputed square list directly.
+///
+/// [`coord_of`]: Region::coord_of
+/// [`sq_of`]: Region::sq_of
+#[derive(Clone, Debug)]
+pub struct MoveRays {
+    /// `squares[from_sq][dir_idx]`: in-region destination squares along that ray, nearest first,
+    /// truncated at `move_bound` and the region boundary.
+    squares: Vec<[Vec<u16>; 8]>,
+    /// `edge_sink[from_sq][dir_idx]`: whether the ray was cut short by the region boundary (rather
+    /// than by `move_bound`) — i.e. a rider that reaches the end of `squares[from_sq][dir_idx]`
+    /// without being blocked first has run off the region.
+    edge_sink: Vec<[bool; 8]>,
+    /// `(mask, increasing)` per `(from_sq, dir_idx)`, `Some` only when the region has at most 64
+    /// squares: `mask` has a bit set for every square in `squares[from_sq][dir_idx]`, and
+    /// `increasing` says whether those squares' indices increase (vs. decrease) moving away from
+    /// `from_sq` — i.e. which end of a blocked submask is nearest.
+    blocker_masks: Option<Vec<[(u64, bool); 8]>>,
+}
+
+impl MoveRays {
+    pub fn build(region: &Region, move_bound: Option<u16>, bound_kind: BoundKind) -> Self {
+        let n = region.size();
+        let max_steps = match move_bound {
+            Some(b) => match bound_kind {
+                BoundKind::Inclusive => b,
+                BoundKind::Exclusive => b.saturating_sub(1),
+            },
+            None => n as u16,
+        };
+
+        let mut squares: Vec<[Vec<u16>; 8]> = Vec::with_capacity(n);
+        let mut edge_sink: Vec<[bool; 8]> = Vec::with_capacity(n);
+
+        for sq in 0..n as u16 {
+            let from = region.coord_of(sq);
+            let mut sq_dirs: [Vec<u16>; 8] = Default::default();
+            let mut sink_dirs = [false; 8];
+            for (i, dir) in RAY_DIRS.iter().enumerate() {
+                let mut cur = from;
+                for _ in 0..max_steps {
+                    cur = Coord::new(cur.x + dir.x, cur.y + dir.y);
+                    match region.sq_of(cur) {
+                        Some(dst) => sq_dirs[i].push(dst),
+                        None => {
+                            sink_dirs[i] = true;
+                            break;
+                        }
+                    }
+                }
+            }
+            squares.push(sq_dirs);
+            edge_sink.push(sink_dirs);
+        }
+
+        let blocker_masks = (n <= 64).then(|| {
+            squares
+                .iter()
+                .map(|dirs| {
+                    std::array::from_fn(|i| {
+                        let list = &dirs[i];
+                        let mut mask = 0u64;
+                        for &sq in list {
+                            mask |= 1u64 << sq;
+                        }
+                        let increasing = list.len() < 2 || list[1] > list[0];
+                        (mask, increasing)
+                    })
+                })
+                .collect()
+        });
+
+        Self {
+            squares,
+            edge_sink,
+            blocker_masks,
+        }
+    }
+
+    /// Every destination square along `from_sq`/`dir` (must be one of [`RAY_DIRS`]) strictly
+    /// before the first square set in `occ`, plus whether the rider then runs off the region (a
+    /// sink) — exactly what `gen_piece_movement`'s ride loop used to compute one `Region::sq_of`
+    /// call at a time. `ride_bound` caps how far to look, mirroring
+    /// [`crate::pieces::PieceMovement::ride_bound`]; `None` for an unbounded rider.
+    ///
+    /// The blocker-mask fast path only applies to an unbounded rider (every classical Rook/Bishop/
+    /// Queen): a custom `ride_bound` can stop a rider before it would reach where that mask assumes
+    /// it's safe to look, so a bounded rider always takes the plain scan below instead.
+    pub fn ride(
+        &self,
+        from_sq: u16,
+        dir: Coord,
+        ride_bound: Option<usize>,
+        occ: &Bitset,
+    ) -> (&[u16], bool) {
+        let dir_idx = ray_dir_index(dir);
+        let list = &self.squares[from_sq as usize][dir_idx];
+        let edge_sink = self.edge_sink[from_sq as usize][dir_idx];
+
+        if ride_bound.is_none() {
+            if let Some(masks) = &self.blocker_masks {
+                let (mask, increasing) = masks[from_sq as usize][dir_idx];
+                let blocked = mask & occ.low_word();
+                if blocked != 0 {
+                    let blocker_sq = if increasing {
+                        blocked.trailing_zeros() as u16
+                    } else {
+                        (63 - blocked.leading_zeros()) as u16
+                    };
+                    let idx = list
+                        .iter()
+                        .position(|&s| s == blocker_sq)
+                        .expect("blocker square must lie on this ray");
+                    return (&list[..idx], false);
+                }
+                return (&list[..], edge_sink);
+            }
+        }
+
+        let bound = ride_bound.unwrap_or(list.len()).min(list.len());
+        for (i, &sq) in list[..bound].iter().enumerate() {
+            if occ.get(sq) {
+                return (&list[..i], false);
+            }
+        }
+        (&list[..bound], edge_sink && bound == list.len())
+    }
+}
+
+/// A fast, well-mixed 64-bit hash, used here as a fixed-seed PRNG for magic-number search: this
+/// tree has no `rand` dependency (no manifest at all) to draw candidates from, and deterministic
+/// search output is preferable anyway, since the same `Region` always rebuilds the same magics.
+/// Mirrors the `splitmix64` used for Zobrist keys elsewhere in the crate (see `core::zobrist`) —
+/// a separate fixed point, since magic search and hashing aren't otherwise related.
+#[inline]
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Every blocker relevant to a slider on `region.coord_of(sq)` along `dirs` (`0..4` for rook,
+/// `4..8` for bishop): every in-region ray square except the farthest one per direction. A
+/// blocker on the farthest square never changes the attack set — there's nothing beyond it to
+/// occlude either way — so excluding it roughly halves the number of occupancy subsets a magic
+/// has to distinguish.
+fn relevant_mask(region: &Region, sq: u16, dirs: std::ops::Range<usize>) -> u64 {
+    let mut mask = 0u64;
+    let from = region.coord_of(sq);
+    for dir in dirs {
+        let d = RAY_DIRS[dir];
+        let mut cur = Coord::new(from.x + d.x, from.y + d.y);
+        let mut prev: Option<u16> = None;
+        while let Some(dst) = region.sq_of(cur) {
+            if let Some(p) = prev {
+                mask |= 1u64 << p;
+            }
+            prev = Some(dst);
+            cur = Coord::new(cur.x + d.x, cur.y + d.y);
+        }
+    }
+    mask
+}
+
+/// The actual attack bitboard for a slider on `region.coord_of(sq)` along `dirs`, given a masked
+/// occupancy `occ` (bits set only at `relevant_mask` positions): ray-walk each direction and set
+/// every square up to and including the first occupied one.
+fn slide_attacks(region: &Region, sq: u16, dirs: std::ops::Range<usize>, occ: u64) -> u64 {
+    let mut attacks = 0u64;
+    let from = region.coord_of(sq);
+    for dir in dirs {
+        let d = RAY_DIRS[dir];
+        let mut cur = Coord::new(from.x + d.x, from.y + d.y);
+        while let Some(dst) = region.sq_of(cur) {
+            attacks |= 1u64 << dst;
+            if occ & (1u64 << dst) != 0 {
+                break;
+            }
+            cur = Coord::new(cur.x + d.x, cur.y + d.y);
+        }
+    }
+    attacks
+}
+
+/// One square's magic-bitboard attack table for a single slider direction group (rook or bishop):
+/// `mask` is the relevant occupancy ([`relevant_mask`]), `magic`/`shift` turn a masked occupancy
+/// into a dense table index, and `table[index]` is the resulting attack bitboard.
+#[derive(Clone, Debug)]
+struct MagicEntry {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    table: Vec<u64>,
+}
+
+impl MagicEntry {
+    #[inline]
+    fn attacks(&self, occ: u64) -> u64 {
+        let idx = ((occ & self.mask).wrapping_mul(self.magic) >> self.shift) as usize;
+        self.table[idx]
+    }
+}
+
+/// Search for a magic multiplier producing a collision-free index over every subset of `mask`
+/// (the classic "find by retrying random sparse candidates" approach: almost any `mask.count_ones()`
+/// and up works eventually, and sparsely-populated high bytes tend to work far sooner than a
+/// uniformly random `u64` would), then build the resulting dense attack table.
+fn build_magic(region: &Region, sq: u16, dirs: std::ops::Range<usize>) -> MagicEntry {
+    let mask = relevant_mask(region, sq, dirs.clone());
+    let bits = mask.count_ones();
+    // `64 - bits` would overflow the shift for a corner square with no relevant blockers at all
+    // (`bits == 0`, e.g. a 1-wide region): `mask` is then always 0, so any shift in range indexes
+    // the lone table slot.
+    let shift = if bits == 0 { 0 } else { 64 - bits };
+
+    // Every subset of `mask` (the standard "carry-rippler" subset enumeration), paired with the
+    // attack set a slider on this square actually has for that occupancy.
+    let mut subsets: Vec<(u64, u64)> = Vec::with_capacity(1usize << bits);
+    let mut subset = 0u64;
+    loop {
+        subsets.push((subset, slide_attacks(region, sq, dirs.clone(), subset)));
+        if subset == mask {
+            break;
+        }
+        subset = subset.wrapping_sub(mask) & mask;
+    }
+
+    let mut seed = 0x9E37_79B9_7F4A_7C15u64 ^ (sq as u64) ^ ((dirs.start as u64) << 32);
+    loop {
+        seed = splitmix64(seed);
+        let candidate =
+            splitmix64(seed) & splitmix64(seed.wrapping_add(1)) & splitmix64(seed.wrapping_add(2));
+
+        let mut table: Vec<Option<u64>> = vec![None; 1usize << bits];
+        let mut ok = true;
+        for &(occ, attacks) in &subsets {
+            let idx = (occ.wrapping_mul(candidate) >> shift) as usize;
+            match table[idx] {
+                None => table[idx] = Some(attacks),
+                Some(existing) if existing == attacks => {}
+                Some(_) => {
+                    ok = false;
+                    break;
+                }
+            }
+        }
+        if ok {
+            let table = table.into_iter().map(|v| v.unwrap_or(0)).collect();
+            return MagicEntry {
+                mask,
+                magic: candidate,
+                shift,
+                table,
+            };
+        }
+    }
+}
+
+/// Classic magic-bitboard rook/bishop attack generation (as used by engines like seer),
+/// specialized to regions small and regular enough for it to pay off: a dense rectangular `linf`
+/// box with at most 64 squares, so the region's entire occupancy fits in one `u64` word and a
+/// single multiply-and-shift replaces a ray walk. [`RegionAttackTables::build`] only constructs
+/// this when [`Region::is_dense_box`] holds and `region.size() <= 64`; otherwise
+/// `RegionAttackTables` falls back to [`RayTable`]/ray-walking for every slider, exactly as before
+/// this existed. King and Knight always use direct arithmetic masks regardless (see
+/// [`RegionAttackTables::attacks`]) — only Rook/Bishop/Queen ever consult this table.
+#[derive(Clone, Debug)]
+pub struct MagicTables {
+    rook: Vec<MagicEntry>,
+    bishop: Vec<MagicEntry>,
+}
+
+impl MagicTables {
+    /// Build rook and bishop magics for `region`, or `None` if it isn't a dense box of at most 64
+    /// squares (see this type's doc comment).
+    pub fn build(region: &Region) -> Option<Self> {
+        if region.size() > 64 || !region.is_dense_box() {
+            return None;
+        }
+        let n = region.size() as u16;
+        let rook = (0..n).map(|sq| build_magic(region, sq, 0..4)).collect();
+        let bishop = (0..n).map(|sq| build_magic(region, sq, 4..8)).collect();
+        Some(Self { rook, bishop })
+    }
+
+    #[inline]
+    fn rook_attacks(&self, from_sq: u16, occ: u64) -> u64 {
+        self.rook[from_sq as usize].attacks(occ)
+    }
+
+    #[inline]
+    fn bishop_attacks(&self, from_sq: u16, occ: u64) -> u64 {
+        self.bishop[from_sq as usize].attacks(occ)
+    }
+}
+
+/// Precomputed, magic-bitboard-style attack data for a fixed [`Region`].
+///
+/// For each region square this stores the king/knight attack masks (which don't depend on
+/// occupancy) and, for each of the 8 slider ray directions, the ordered list of region square
+/// indices along that ray until it leaves the region (nearest first). [`RegionAttackTables::attacks`]
+/// turns a slider ray walk into "set bits until the first occupied square" instead of a
+/// target-by-target scan. [`RegionAttackTables::rays`] additionally exposes the same per-direction
+/// ray data in [`RayTable`] form, for `rules::attacks::ray_clear`'s target-outside-the-region
+/// fallback. When `region` is a dense box of at most 64 squares, it also builds [`MagicTables`]
+/// and [`RegionAttackTables::attacks`] prefers those for Rook/Bishop/Queen over the `rays` walk.
+#[derive(Clone, Debug)]
+pub struct RegionAttackTables {
+    king: Vec<Bitset>,
+    knight: Vec<Bitset>,
+    rays: Vec<[Vec<u16>; 8]>,
+    ray_table: RayTable,
+    magic: Option<MagicTables>,
+}
+
+impl RegionAttackTables {
+    pub fn build(region: &Region) -> Self {
+        let n = region.size();
+        let mut king = Vec::with_capacity(n);
+        let mut knight = Vec::with_capacity(n);
+        let mut rays: Vec<[Vec<u16>; 8]> = Vec::with_capacity(n);
+
+        let king_steps: [Coord; 8] = [
+            Coord::new(-1, -1),
+            Coord::new(-1, 0),
+            Coord::new(-1, 1),
+            Coord::new(0, -1),
+            Coord::new(0, 1),
+            Coord::new(1, -1),
+            Coord::new(1, 0),
+            Coord::new(1, 1),
+        ];
+        let knight_moves: [Coord; 8] = [
+            Coord::new(1, 2),
+            Coord::new(2, 1),
+            Coord::new(-1, 2),
+            Coord::new(-2, 1),
+            Coord::new(1, -2),
+            Coord::new(2, -1),
+            Coord::new(-1, -2),
+            Coord::new(-2, -1),
+        ];
+
+        for sq in 0..n as u16 {
+            let from = region.coord_of(sq);
+
+            let mut king_mask = Bitset::new(n);
+            for step in king_steps {
+                if let Some(dst) = region.sq_of(Coord::new(from.x + step.x, from.y + step.y)) {
+                    king_mask.set(dst);
+                }
+            }
+            king.push(king_mask);
+
+            let mut knight_mask = Bitset::new(n);
+            for mv in knight_moves {
+                if let Some(dst) = region.sq_of(Coord::new(from.x + mv.x, from.y + mv.y)) {
+                    knight_mask.set(dst);
+                }
+            }
+            knight.push(knight_mask);
+
+            let mut dir_rays: [Vec<u16>; 8] = Default::default();
+            for (i, dir) in RAY_DIRS.iter().enumerate() {
+                let mut cur = Coord::new(from.x + dir.x, from.y + dir.y);
+                while let Some(dst) = region.sq_of(cur) {
+                    dir_rays[i].push(dst);
+                    cur = Coord::new(cur.x + dir.x, cur.y + dir.y);
+                }
+            }
+            rays.push(dir_rays);
+        }
+
+        let ray_table = RayTable::build(region);
+        let magic = MagicTables::build(region);
+        Self {
+            king,
+            knight,
+            rays,
+            ray_table,
+            magic,
+        }
+    }
+
+    /// The [`RayTable`] built for this region, for `rules::attacks::ray_clear`'s
+    /// target-outside-the-region fallback (see [`RayTable::ray_clear`]).
+    pub fn rays(&self) -> &RayTable {
+        &self.ray_table
+    }
+
+    /// The set of squares attacked by a piece of `kind` sitting on `from_sq`, given `occ` as
+    /// blockers for sliding pieces. Does not check whether `from_sq` itself is occupied by a
+    /// friendly piece — callers are expected to skip captured/absent slots themselves.
+    ///
+    /// `pawn_forward` is only consulted for `PieceKind::Pawn` (see [`crate::pieces::Layout`]'s
+    /// field of the same name); every other kind ignores it.
+    ///
+    /// Rook/Bishop/Queen go through [`MagicTables`] (a multiply-and-shift, no stepping at all)
+    /// whenever this region built one; otherwise they fall back to [`RegionAttackTables::slide`]'s
+    /// ray walk. King/Knight always use the precomputed direct-arithmetic masks regardless, since
+    /// neither depends on occupancy. Pawn attacks are cheap enough (at most two squares) to compute
+    /// directly rather than earning their own precomputed table.
+    pub fn attacks(
+        &self,
+        from_sq: u16,
+        kind: PieceKind,
+        occ: &Bitset,
+        region: &Region,
+        pawn_forward: Coord,
+    ) -> Bitset {
+        match (kind, &self.magic) {
+            (PieceKind::King, _) => self.king[from_sq as usize].clone(),
+            (PieceKind::Knight, _) => self.knight[from_sq as usize].clone(),
+            (PieceKind::Pawn, _) => {
+                let from = region.coord_of(from_sq);
+                let mut mask = Bitset::new(region.size());
+                for dx in [-1, 1] {
+                    let dst = Coord::new(from.x + dx, from.y + pawn_forward.y);
+                    if let Some(sq) = region.sq_of(dst) {
+                        mask.set(sq);
+                    }
+                }
+                mask
+            }
+            (PieceKind::Rook, Some(magic)) => {
+                Bitset::from_low_word(region.size(), magic.rook_attacks(from_sq, occ.low_word()))
+            }
+            (PieceKind::Bishop, Some(magic)) => {
+                Bitset::from_low_word(region.size(), magic.bishop_attacks(from_sq, occ.low_word()))
+            }
+            (PieceKind::Queen, Some(magic)) => Bitset::from_low_word(
+                region.size(),
+                magic.rook_attacks(from_sq, occ.low_word())
+                    | magic.bishop_attacks(from_sq, occ.low_word()),
+            ),
+            (PieceKind::Rook, None) => self.slide(from_sq, 0..4, occ, region),
+            (PieceKind::Bishop, None) => self.slide(from_sq, 4..8, occ, region),
+            (PieceKind::Queen, None) => self.slide(from_sq, 0..8, occ, region),
+        }
+    }
+
+    fn slide(
+        &self,
+        from_sq: u16,
+        dirs: std::ops::Range<usize>,
+        occ: &Bitset,
+        region: &Region,
+    ) -> Bitset {
+        let mut mask = Bitset::new(region.size());
+        for dir in dirs {
+            for &sq in &self.rays[from_sq as usize][dir] {
+                mask.set(sq);
+                if occ.get(sq) {
+                    break;
+                }
+            }
+        }
+        mask
+    }
+}
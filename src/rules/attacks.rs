@@ -1,57 +1,231 @@
 use crate::coord::{signum_i16, Coord};
-use crate::pieces::{Layout, PieceKind};
-use crate::region::Region;
+use crate::pieces::{Layout, MovementRegistry, PieceKind, PieceMovement};
+use crate::region::{ray_dir_index, Bitset, Region, RegionAttackTables};
 
-#[derive(Clone, Debug)]
-pub struct Occ {
-    data: Vec<u64>,
-}
+/// A region occupancy bitset. An alias of [`Bitset`]: occupancy and attack masks are the same
+/// representation, just populated for different purposes.
+pub type Occ = Bitset;
 
-impl Occ {
-    pub fn new(num_squares: usize) -> Self {
-        let words = (num_squares + 63) / 64;
-        Self { data: vec![0; words] }
+/// Every non-captured white piece's square, used as slider blockers. A pawn's own square blocks
+/// rays exactly like any other piece's — only its *attack* pattern (see `piece_attacks`) singles
+/// out the diagonals, so this needs no pawn-specific case.
+pub fn build_white_occupancy(region: &Region, whites: &[u16], captured_code: u16) -> Occ {
+    let mut occ = Occ::new(region.size());
+    for &c in whites {
+        if c != captured_code {
+            occ.set(c);
+        }
     }
+    occ
+}
 
-    #[inline]
-    pub fn set(&mut self, sq: u16) {
-        let i = sq as usize;
-        self.data[i >> 6] |= 1u64 << (i & 63);
-    }
+/// Every white piece attacking a square, from [`white_attackers`]. Most positions have at most a
+/// couple of attackers on any one square, but this is a plain `Vec` rather than something
+/// `SmallVec`-shaped: this tree has no manifest to declare that dependency in (see
+/// [`crate::fuzz`]'s doc comment in the compiled tree for the same kind of gap).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkers {
+    /// Indices into the layout's slot order (and `whites`) of every attacking piece.
+    pub slots: Vec<usize>,
+}
 
+impl Checkers {
+    /// Whether `target` is attacked at all. Equivalent to the answer [`is_attacked_by_white`]
+    /// computes directly.
     #[inline]
-    pub fn clear(&mut self, sq: u16) {
-        let i = sq as usize;
-        self.data[i >> 6] &= !(1u64 << (i & 63));
+    pub fn any(&self) -> bool {
+        !self.slots.is_empty()
     }
 
+    /// Two or more attackers: only a king move gets Black out of this, since a single block or
+    /// capture can answer at most one of them.
     #[inline]
-    pub fn get(&self, sq: u16) -> bool {
-        let i = sq as usize;
-        (self.data[i >> 6] >> (i & 63)) & 1u64 == 1u64
+    pub fn is_double_check(&self) -> bool {
+        self.slots.len() > 1
     }
 }
 
-pub fn build_white_occupancy(region: &Region, whites: &[u16], captured_code: u16) -> Occ {
-    let mut occ = Occ::new(region.size());
-    for &c in whites {
-        if c != captured_code {
-            occ.set(c);
+/// Every white piece attacking `target`, keeping each attacker's slot index instead of collapsing
+/// to a single bit the way [`is_attacked_by_white`] does. Lets callers (e.g. a `LawsLike` candidate
+/// generator) distinguish single check (the attacker can be captured, blocked, or the king can
+/// step away) from double check (only a king step helps).
+///
+/// Follows the same `target`-inside-vs-outside-`region` split as [`is_attacked_by_white`]: inside,
+/// each piece's precomputed/table-backed attack mask is tested directly; outside, this falls back
+/// to the same per-piece geometric test [`is_attacked_by_white`]'s scan path uses. Unlike that
+/// boolean query, there's no early exit here — every attacker needs to be found, not just the
+/// first one — so this isn't a replacement for the hot `is_attacked_by_white` path, just a richer
+/// answer to the same question when a caller actually needs the attacker list.
+pub fn white_attackers(
+    target: Coord,
+    region: &Region,
+    tables: &RegionAttackTables,
+    layout: &Layout,
+    whites: &[u16],
+    captured_code: u16,
+    occ_white: &Occ,
+    registry: &MovementRegistry,
+) -> Checkers {
+    let mut slots = Vec::new();
+    match region.sq_of(target) {
+        Some(target_sq) => {
+            for (i, kind) in layout.slots.iter().enumerate() {
+                let code = whites[i];
+                if code == captured_code || code == target_sq {
+                    continue;
+                }
+                let attacked = if registry.is_classical(*kind) {
+                    tables
+                        .attacks(code, *kind, occ_white, region, layout.pawn_forward)
+                        .get(target_sq)
+                } else {
+                    piece_attacks(
+                        *kind,
+                        code,
+                        region.coord_of(code),
+                        target,
+                        tables,
+                        occ_white,
+                        layout.pawn_forward,
+                        registry,
+                    )
+                };
+                if attacked {
+                    slots.push(i);
+                }
+            }
+        }
+        None => {
+            for (i, kind) in layout.slots.iter().enumerate() {
+                let code = whites[i];
+                if code == captured_code {
+                    continue;
+                }
+                let from = region.coord_of(code);
+                if from == target {
+                    continue;
+                }
+                if piece_attacks(
+                    *kind,
+                    code,
+                    from,
+                    target,
+                    tables,
+                    occ_white,
+                    layout.pawn_forward,
+                    registry,
+                ) {
+                    slots.push(i);
+                }
+            }
         }
     }
-    occ
+    Checkers { slots }
+}
+
+/// Every white slot pinned against `target`, along with the ray direction it's pinned on.
+///
+/// A pin requires a second piece on the pinned side standing between the pinning slider and its
+/// own king — but this engine only ever models White's pieces against a *lone* Black king (a
+/// [`Game`](crate::game::Game) has no Black piece besides it); there is no second Black piece for a White slider's
+/// ray to be blocked by, and no Black slider that could pin a White piece against the Black king
+/// in the first place. So there is nothing for this to find under the current game model, and it
+/// always returns an empty [`Pins`]. It's kept as a real function with [`white_attackers`]'s same
+/// shape of inputs, rather than omitted, so a `LawsLike` candidate generator has one stable call
+/// site to switch over if a second Black piece is ever modeled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pins {
+    /// `(slot_index, ray_direction)` for each pinned White piece.
+    pub pinned: Vec<(usize, Coord)>,
+}
+
+pub fn pins(
+    _target: Coord,
+    _region: &Region,
+    _tables: &RegionAttackTables,
+    _layout: &Layout,
+    _whites: &[u16],
+    _captured_code: u16,
+    _occ_white: &Occ,
+) -> Pins {
+    Pins::default()
 }
 
 /// True iff `target` is attacked by any white piece.
 ///
-/// Uses `occ_white` only as blockers for sliding pieces.
+/// Uses `occ_white` only as blockers for sliding pieces. When `target` is inside `region`, this
+/// builds the OR of every white piece's precomputed attack mask (`tables`) and tests membership —
+/// a handful of table lookups regardless of region size, replacing a per-piece target-by-target
+/// scan. A `target` outside `region` (checking a king's escape move into the sink) can't be
+/// tested against the table, since slider rays in `tables` stop at the region boundary, so that
+/// case falls back to the direct scan.
+///
+/// Answers the same question as `white_attackers(...).any()`, kept as its own boolean computation
+/// so the hot attack-only query path (called once per square per candidate move) doesn't build the
+/// attacker `Vec` it never needs. It still allocates one region-sized [`Bitset`] per call.
 pub fn is_attacked_by_white(
     target: Coord,
     region: &Region,
+    tables: &RegionAttackTables,
     layout: &Layout,
     whites: &[u16],
     captured_code: u16,
     occ_white: &Occ,
+    registry: &MovementRegistry,
+) -> bool {
+    match region.sq_of(target) {
+        Some(target_sq) => {
+            let mut attacked = Bitset::new(region.size());
+            for (i, kind) in layout.slots.iter().enumerate() {
+                let code = whites[i];
+                if code == captured_code || code == target_sq {
+                    continue;
+                }
+                if registry.is_classical(*kind) {
+                    attacked.or_with(&tables.attacks(
+                        code,
+                        *kind,
+                        occ_white,
+                        region,
+                        layout.pawn_forward,
+                    ));
+                } else if piece_attacks(
+                    *kind,
+                    code,
+                    region.coord_of(code),
+                    target,
+                    tables,
+                    occ_white,
+                    layout.pawn_forward,
+                    registry,
+                ) {
+                    attacked.set(target_sq);
+                }
+            }
+            attacked.get(target_sq)
+        }
+        None => is_attacked_by_white_scan(
+            target,
+            region,
+            tables,
+            layout,
+            whites,
+            captured_code,
+            occ_white,
+            registry,
+        ),
+    }
+}
+
+fn is_attacked_by_white_scan(
+    target: Coord,
+    region: &Region,
+    tables: &RegionAttackTables,
+    layout: &Layout,
+    whites: &[u16],
+    captured_code: u16,
+    occ_white: &Occ,
+    registry: &MovementRegistry,
 ) -> bool {
     for (i, kind) in layout.slots.iter().enumerate() {
         let code = whites[i];
@@ -62,64 +236,109 @@ pub fn is_attacked_by_white(
         if from == target {
             continue;
         }
-        if piece_attacks(*kind, from, target, region, occ_white) {
+        if piece_attacks(
+            *kind,
+            code,
+            from,
+            target,
+            tables,
+            occ_white,
+            layout.pawn_forward,
+            registry,
+        ) {
             return true;
         }
     }
     false
 }
 
-fn piece_attacks(kind: PieceKind, from: Coord, target: Coord, region: &Region, occ_white: &Occ) -> bool {
+/// Whether the piece of `kind` on `from_sq`/`from` attacks `target`. King and Pawn keep their
+/// hand-written geometric tests (a king step radius; a pawn's asymmetric diagonal-only attack, see
+/// [`PieceKind::Pawn`]); every other kind defers to its [`PieceMovement`] descriptor in `registry`
+/// (see [`movement_attacks`]), so a fairy piece registered there is detected the same way a
+/// built-in one is.
+#[allow(clippy::too_many_arguments)]
+fn piece_attacks(
+    kind: PieceKind,
+    from_sq: u16,
+    from: Coord,
+    target: Coord,
+    tables: &RegionAttackTables,
+    occ_white: &Occ,
+    pawn_forward: Coord,
+    registry: &MovementRegistry,
+) -> bool {
     let dx = target.x - from.x;
     let dy = target.y - from.y;
 
     match kind {
-        PieceKind::King => {
-            dx.abs() <= 1 && dy.abs() <= 1 && !(dx == 0 && dy == 0)
-        }
-        PieceKind::Knight => {
-            let ax = dx.abs();
-            let ay = dy.abs();
-            (ax == 1 && ay == 2) || (ax == 2 && ay == 1)
-        }
-        PieceKind::Rook => {
-            if dx != 0 && dy != 0 {
-                return false;
-            }
-            let step = Coord::new(signum_i16(dx), signum_i16(dy));
-            ray_clear(from, target, step, region, occ_white)
-        }
-        PieceKind::Bishop => {
-            if dx.abs() != dy.abs() {
-                return false;
-            }
-            let step = Coord::new(signum_i16(dx), signum_i16(dy));
-            ray_clear(from, target, step, region, occ_white)
-        }
-        PieceKind::Queen => {
-            if dx == 0 || dy == 0 || dx.abs() == dy.abs() {
-                let step = Coord::new(signum_i16(dx), signum_i16(dy));
-                ray_clear(from, target, step, region, occ_white)
-            } else {
-                false
-            }
+        PieceKind::King => dx.abs() <= 1 && dy.abs() <= 1 && !(dx == 0 && dy == 0),
+        // Only the two diagonal-forward squares are attacked; the straight-ahead push square
+        // (handled as a move in `rules::movegen`, not an attack) is never a threat.
+        PieceKind::Pawn => dy == pawn_forward.y && dx.abs() == 1,
+        other => {
+            let movement = registry
+                .get(other)
+                .unwrap_or_else(|| panic!("no movement descriptor for {other:?}"));
+            movement_attacks(movement, dx, dy, from_sq, target, tables, occ_white)
         }
     }
 }
 
-fn ray_clear(from: Coord, target: Coord, step: Coord, region: &Region, occ_white: &Occ) -> bool {
-    if step.x == 0 && step.y == 0 {
-        return false;
+/// Whether a piece moving per `movement` and sitting a `(dx, dy)` step away from `target` attacks
+/// it: any exact leap offset match, or alignment with a ride direction (see [`rides_aligned`])
+/// followed by an unblocked [`ray_clear`] walk.
+///
+/// Does not enforce `movement.ride_bound`: [`RegionAttackTables`]' ray data (which [`ray_clear`]
+/// consults) is precomputed out to the region edge regardless of any per-piece bound (see
+/// [`PieceMovement`]'s doc comment), so a custom-bounded rider is treated as unbounded here. This
+/// matches the existing table-backed fast path in [`RegionAttackTables::attacks`], which has never
+/// modeled a bound either.
+fn movement_attacks(
+    movement: &PieceMovement,
+    dx: i16,
+    dy: i16,
+    from_sq: u16,
+    target: Coord,
+    tables: &RegionAttackTables,
+    occ_white: &Occ,
+) -> bool {
+    if movement.leaps.iter().any(|l| l.x == dx && l.y == dy) {
+        return true;
     }
+    movement.rides.iter().any(|&dir| {
+        rides_aligned(dx, dy, dir) && ray_clear(from_sq, target, dir, tables, occ_white)
+    })
+}
 
-    let mut cur = from + step;
-    while cur != target {
-        if let Some(sq) = region.sq_of(cur) {
-            if occ_white.get(sq) {
-                return false;
-            }
-        }
-        cur += step;
+/// Whether `(dx, dy)` lies exactly along the unit ride direction `dir` (one of the 8
+/// [`crate::rules::rays::KING_DIRS`]): same sign on every nonzero axis, and — for a diagonal
+/// `dir` — equal magnitude on both axes.
+fn rides_aligned(dx: i16, dy: i16, dir: Coord) -> bool {
+    if dir.x == 0 {
+        dx == 0 && signum_i16(dy) == dir.y
+    } else if dir.y == 0 {
+        dy == 0 && signum_i16(dx) == dir.x
+    } else {
+        dx.abs() == dy.abs() && signum_i16(dx) == dir.x && signum_i16(dy) == dir.y
+    }
+}
+
+/// Whether a slider on `from_sq` stepping by `step` (a unit direction, the zero vector rejected
+/// up front since callers only reach here after confirming `target` lies on a rook/bishop ray)
+/// reaches `target` unobstructed, via a [`crate::region::RayTable`] lookup instead of walking
+/// `from + step`, `from + 2*step`, ... one coordinate at a time.
+fn ray_clear(
+    from_sq: u16,
+    target: Coord,
+    step: Coord,
+    tables: &RegionAttackTables,
+    occ_white: &Occ,
+) -> bool {
+    if step.x == 0 && step.y == 0 {
+        return false;
     }
-    true
+    tables
+        .rays()
+        .ray_clear(from_sq, ray_dir_index(step), target, occ_white)
 }
@@ -11,7 +11,16 @@ pub fn is_in_check(game: &Game, bk_sq: u16, whites: &[u16]) -> bool {
     let cap = game.captured_code();
     let bk_c = region.coord_of(bk_sq);
     let occ_white = build_white_occupancy(region, whites, cap);
-    is_attacked_by_white(bk_c, region, layout, whites, cap, &occ_white)
+    is_attacked_by_white(
+        bk_c,
+        region,
+        &game.attack_tables,
+        layout,
+        whites,
+        cap,
+        &occ_white,
+        &game.movement_registry,
+    )
 }
 
 /// True if this black-to-move position is checkmate in the finite-slice game.
@@ -25,7 +34,16 @@ pub fn is_checkmate_black_to_move(game: &Game, state: PackedState, scratch: &mut
     let bk_c = game.region.coord_of(bk_sq);
     let whites = scratch.whites();
     let occ_white = build_white_occupancy(&game.region, whites, cap);
-    let in_check = is_attacked_by_white(bk_c, &game.region, &game.layout, whites, cap, &occ_white);
+    let in_check = is_attacked_by_white(
+        bk_c,
+        &game.region,
+        &game.attack_tables,
+        &game.layout,
+        whites,
+        cap,
+        &occ_white,
+        &game.movement_registry,
+    );
     if !in_check {
         return false;
     }
@@ -1,9 +1,10 @@
 use crate::coord::Coord;
 use crate::game::Game;
-use crate::pieces::{PieceKind, Turn};
+use crate::pieces::{Layout, PieceKind, PieceMovement, Turn};
 use crate::state::{canonicalize, PackedState};
 
 use super::attacks::{build_white_occupancy, is_attacked_by_white, Occ};
+use super::rays::KING_DIRS;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Succ {
@@ -37,28 +38,97 @@ pub fn successors(game: &Game, turn: Turn, state: PackedState, scratch: &mut Scr
     let bk_sq = game.packer.unpack(state, &mut scratch.whites);
 
     match turn {
-        Turn::Black => black_succ(game, bk_sq, &scratch.whites, cap),
-        Turn::White => white_succ(game, bk_sq, state, &scratch.whites, cap),
+        Turn::Black => black_succ(game, bk_sq, scratch, cap),
+        Turn::White => white_succ(game, bk_sq, state, scratch, cap),
     }
 }
 
-fn black_succ(game: &Game, bk_sq: u16, whites: &[u16], captured_code: u16) -> Vec<Succ> {
+/// An in-place move against `scratch.whites()`, for [`apply`]/[`undo`]: relocate the white piece
+/// in slot `slot_idx` to region square `to`. An ordinary piece move and `black_succ`'s capture are
+/// the same write as far as this is concerned — a capture just has `to == captured_code`, the same
+/// sentinel [`canonicalize`] already sorts to the end of its group.
+#[derive(Debug, Clone, Copy)]
+pub struct Move {
+    pub slot_idx: usize,
+    pub to: u16,
+}
+
+/// What [`apply`] changed, so [`undo`] can restore `scratch.whites()` exactly.
+///
+/// Only the touched slot's group (see [`crate::pieces::Layout::groups`]) can have reordered:
+/// `apply` writes one slot then re-canonicalizes, and every other group was already sorted coming
+/// in (sorting an already-sorted slice is a no-op), so this only needs that one group's pre-move
+/// contents, not a copy of the whole buffer.
+#[derive(Debug, Clone)]
+pub struct Undo {
+    group_start: usize,
+    group_before: Vec<u16>,
+}
+
+/// Write `mv` into `scratch.whites_mut()` and re-canonicalize, returning an [`Undo`] that steps
+/// back to the pre-move position. Replaces the `whites.to_vec()` every `black_succ`/`white_succ`/
+/// `gen_piece_movement` branch (and their `_hashed` twins) used to pay per candidate move: callers
+/// mutate the one shared `Scratch` buffer, pack the resulting canonical state, then call [`undo`]
+/// before trying the next candidate.
+pub fn apply(game: &Game, scratch: &mut Scratch, mv: Move) -> Undo {
+    let layout = &game.layout;
+    let group = layout
+        .groups
+        .iter()
+        .find(|g| mv.slot_idx >= g.start && mv.slot_idx < g.start + g.len)
+        .unwrap_or_else(|| panic!("slot {} belongs to no group", mv.slot_idx));
+    let group_before = scratch.whites[group.start..group.start + group.len].to_vec();
+
+    scratch.whites[mv.slot_idx] = mv.to;
+    canonicalize(&mut scratch.whites, layout);
+
+    Undo {
+        group_start: group.start,
+        group_before,
+    }
+}
+
+/// Restore `scratch.whites()` to the position before the [`apply`] call that produced `u`.
+pub fn undo(_game: &Game, scratch: &mut Scratch, u: Undo) {
+    let end = u.group_start + u.group_before.len();
+    scratch.whites[u.group_start..end].copy_from_slice(&u.group_before);
+}
+
+/// Like [`successors`], but pairs every [`Succ::State`] with its
+/// [`ZobristKeys::hash_packed_incremental`](crate::zobrist::ZobristKeys::hash_packed_incremental)
+/// hash, derived from `hash` (which must be `game.zobrist.hash_packed_incremental(state)`) via an
+/// O(1) update per move instead of a full recompute. [`Succ::Sink`] entries pair with `0`, which
+/// callers must not interpret as a real hash — a sink has no packed state to hash.
+///
+/// This must canonicalize (via [`state::canonicalize`]) before returning each successor, exactly
+/// like [`successors`] — the kind-keyed hash is invariant under that reordering (see
+/// [`crate::zobrist::piece_key`]), so unlike a field-keyed hash it agrees with the packed state
+/// either way, but the state itself still needs canonicalizing to match the enumerated states'
+/// packed form.
+pub fn successors_hashed(
+    game: &Game,
+    turn: Turn,
+    state: PackedState,
+    hash: u64,
+    scratch: &mut Scratch,
+) -> Vec<(Succ, u64)> {
+    let cap = game.captured_code();
+    let bk_sq = game.packer.unpack(state, &mut scratch.whites);
+
+    match turn {
+        Turn::Black => black_succ_hashed(game, bk_sq, scratch, cap, hash),
+        Turn::White => white_succ_hashed(game, bk_sq, state, scratch, cap, hash),
+    }
+}
+
+fn black_succ(game: &Game, bk_sq: u16, scratch: &mut Scratch, captured_code: u16) -> Vec<Succ> {
     let region = &game.region;
     let layout = &game.layout;
 
     let bk_c = region.coord_of(bk_sq);
-    let occ_white = build_white_occupancy(region, whites, captured_code);
-
-    let steps: [Coord; 8] = [
-        Coord::new(-1, -1),
-        Coord::new(-1, 0),
-        Coord::new(-1, 1),
-        Coord::new(0, -1),
-        Coord::new(0, 1),
-        Coord::new(1, -1),
-        Coord::new(1, 0),
-        Coord::new(1, 1),
-    ];
+    let mut occ_white = build_white_occupancy(region, &scratch.whites, captured_code);
+
+    let steps: [Coord; 8] = KING_DIRS;
 
     let mut out: Vec<Succ> = Vec::new();
     let mut has_sink = false;
@@ -68,38 +138,73 @@ fn black_succ(game: &Game, bk_sq: u16, whites: &[u16], captured_code: u16) -> Ve
         if let Some(dst_sq) = region.sq_of(dst) {
             if occ_white.get(dst_sq) {
                 // capture that piece (unless it's the white king)
-                let Some(slot_idx) = find_slot_at(whites, dst_sq, captured_code) else {
+                let Some(slot_idx) = find_slot_at(&scratch.whites, dst_sq, captured_code) else {
                     continue;
                 };
                 if layout.slots[slot_idx] == PieceKind::King {
                     continue;
                 }
 
-                let mut whites2 = whites.to_vec();
-                whites2[slot_idx] = captured_code;
-                canonicalize(&mut whites2, layout);
+                // The captured piece also stops blocking rays/occupancy, so the check test below
+                // needs it cleared from `occ_white` too; restored right after, win or lose.
+                occ_white.clear(dst_sq);
+                let u = apply(
+                    game,
+                    scratch,
+                    Move {
+                        slot_idx,
+                        to: captured_code,
+                    },
+                );
+
+                let attacked = is_attacked_by_white(
+                    dst,
+                    region,
+                    &game.attack_tables,
+                    layout,
+                    &scratch.whites,
+                    captured_code,
+                    &occ_white,
+                    &game.movement_registry,
+                );
+                let st = (!attacked).then(|| game.packer.pack(dst_sq, &scratch.whites));
 
-                let mut occ_after = occ_white.clone();
-                occ_after.clear(dst_sq);
+                undo(game, scratch, u);
+                occ_white.set(dst_sq);
 
-                let attacked = is_attacked_by_white(dst, region, layout, &whites2, captured_code, &occ_after);
-                if attacked {
-                    continue;
+                if let Some(st) = st {
+                    out.push(Succ::State(st));
                 }
-                let st = game.packer.pack(dst_sq, &whites2);
-                out.push(Succ::State(st));
             } else {
                 // normal move
-                let attacked = is_attacked_by_white(dst, region, layout, whites, captured_code, &occ_white);
+                let attacked = is_attacked_by_white(
+                    dst,
+                    region,
+                    &game.attack_tables,
+                    layout,
+                    &scratch.whites,
+                    captured_code,
+                    &occ_white,
+                    &game.movement_registry,
+                );
                 if attacked {
                     continue;
                 }
-                let st = game.packer.pack(dst_sq, whites);
+                let st = game.packer.pack(dst_sq, &scratch.whites);
                 out.push(Succ::State(st));
             }
         } else {
             // outside region => escape sink (if not attacked)
-            let attacked = is_attacked_by_white(dst, region, layout, whites, captured_code, &occ_white);
+            let attacked = is_attacked_by_white(
+                dst,
+                region,
+                &game.attack_tables,
+                layout,
+                &scratch.whites,
+                captured_code,
+                &occ_white,
+                &game.movement_registry,
+            );
             if attacked {
                 continue;
             }
@@ -114,13 +219,19 @@ fn black_succ(game: &Game, bk_sq: u16, whites: &[u16], captured_code: u16) -> Ve
     out
 }
 
-fn white_succ(game: &Game, bk_sq: u16, state: PackedState, whites: &[u16], captured_code: u16) -> Vec<Succ> {
+fn white_succ(
+    game: &Game,
+    bk_sq: u16,
+    state: PackedState,
+    scratch: &mut Scratch,
+    captured_code: u16,
+) -> Vec<Succ> {
     let region = &game.region;
     let layout = &game.layout;
 
     let bk_c = region.coord_of(bk_sq);
 
-    let occ_white = build_white_occupancy(region, whites, captured_code);
+    let occ_white = build_white_occupancy(region, &scratch.whites, captured_code);
     let mut occ_all = occ_white.clone();
     occ_all.set(bk_sq);
 
@@ -132,7 +243,7 @@ fn white_succ(game: &Game, bk_sq: u16, state: PackedState, whites: &[u16], captu
     }
 
     for (slot_idx, kind) in layout.slots.iter().enumerate() {
-        let code = whites[slot_idx];
+        let code = scratch.whites[slot_idx];
         if code == captured_code {
             continue;
         }
@@ -140,16 +251,7 @@ fn white_succ(game: &Game, bk_sq: u16, state: PackedState, whites: &[u16], captu
 
         match kind {
             PieceKind::King => {
-                let steps: [Coord; 8] = [
-                    Coord::new(-1, -1),
-                    Coord::new(-1, 0),
-                    Coord::new(-1, 1),
-                    Coord::new(0, -1),
-                    Coord::new(0, 1),
-                    Coord::new(1, -1),
-                    Coord::new(1, 0),
-                    Coord::new(1, 1),
-                ];
+                let steps: [Coord; 8] = KING_DIRS;
                 for step in steps {
                     let dst = Coord::new(from.x + step.x, from.y + step.y);
                     // cannot move adjacent to black king
@@ -160,156 +262,507 @@ fn white_succ(game: &Game, bk_sq: u16, state: PackedState, whites: &[u16], captu
                         if occ_all.get(dst_sq) {
                             continue;
                         }
-                        let mut whites2 = whites.to_vec();
-                        whites2[slot_idx] = dst_sq;
-                        canonicalize(&mut whites2, layout);
-                        let st = game.packer.pack(bk_sq, &whites2);
+                        let u = apply(
+                            game,
+                            scratch,
+                            Move {
+                                slot_idx,
+                                to: dst_sq,
+                            },
+                        );
+                        let st = game.packer.pack(bk_sq, &scratch.whites);
+                        undo(game, scratch, u);
                         out.push(Succ::State(st));
                     } else {
                         has_sink = true;
                     }
                 }
             }
-            PieceKind::Knight => {
-                let moves: [Coord; 8] = [
-                    Coord::new(1, 2),
-                    Coord::new(2, 1),
-                    Coord::new(-1, 2),
-                    Coord::new(-2, 1),
-                    Coord::new(1, -2),
-                    Coord::new(2, -1),
-                    Coord::new(-1, -2),
-                    Coord::new(-2, -1),
-                ];
-                for mv in moves {
-                    let dst = Coord::new(from.x + mv.x, from.y + mv.y);
-                    if let Some(dst_sq) = region.sq_of(dst) {
-                        if occ_all.get(dst_sq) {
-                            continue;
+            PieceKind::Pawn => {
+                // A single non-capturing push along `layout.pawn_forward`, promoting into a
+                // reserve slot of the chosen kind (see `Layout::promotion_kinds`) on reaching
+                // `Layout::promotion_rank`. The diagonal squares (see
+                // `rules::attacks::piece_attacks`) are attack-only here: the only piece a pawn
+                // could ever capture diagonally is the black king, and the black king is never
+                // removed by a white move (it's mated, not captured), so a diagonal pawn "move"
+                // never has a legal target to land on.
+                //
+                // No double push or en passant: the packed `whites` slice carries no "just
+                // double-pushed" or move-count state to make either well-defined.
+                let dst = Coord::new(from.x, from.y + game.layout.pawn_forward.y);
+                if let Some(dst_sq) = region.sq_of(dst) {
+                    if !occ_all.get(dst_sq) {
+                        if layout.promotion_reached(dst.y) {
+                            for &promo_kind in &layout.promotion_kinds {
+                                let Some(reserve_idx) = find_reserve_slot(
+                                    &scratch.whites,
+                                    layout,
+                                    promo_kind,
+                                    captured_code,
+                                ) else {
+                                    continue;
+                                };
+                                let u_pawn = apply(
+                                    game,
+                                    scratch,
+                                    Move {
+                                        slot_idx,
+                                        to: captured_code,
+                                    },
+                                );
+                                let u_reserve = apply(
+                                    game,
+                                    scratch,
+                                    Move {
+                                        slot_idx: reserve_idx,
+                                        to: dst_sq,
+                                    },
+                                );
+                                let st = game.packer.pack(bk_sq, &scratch.whites);
+                                undo(game, scratch, u_reserve);
+                                undo(game, scratch, u_pawn);
+                                out.push(Succ::State(st));
+                            }
+                        } else {
+                            let u = apply(
+                                game,
+                                scratch,
+                                Move {
+                                    slot_idx,
+                                    to: dst_sq,
+                                },
+                            );
+                            let st = game.packer.pack(bk_sq, &scratch.whites);
+                            undo(game, scratch, u);
+                            out.push(Succ::State(st));
                         }
-                        let mut whites2 = whites.to_vec();
-                        whites2[slot_idx] = dst_sq;
-                        canonicalize(&mut whites2, layout);
-                        let st = game.packer.pack(bk_sq, &whites2);
-                        out.push(Succ::State(st));
-                    } else {
-                        has_sink = true;
                     }
+                } else {
+                    has_sink = true;
                 }
             }
-            PieceKind::Rook => {
-                gen_sliding(
+            other => {
+                let movement = game
+                    .movement_registry
+                    .get(*other)
+                    .unwrap_or_else(|| panic!("no movement descriptor for {other:?}"));
+                gen_piece_movement(
                     game,
                     bk_sq,
-                    whites,
-                    captured_code,
+                    scratch,
                     slot_idx,
                     &occ_all,
+                    movement,
                     &mut out,
                     &mut has_sink,
-                    &[
-                        Coord::new(1, 0),
-                        Coord::new(-1, 0),
-                        Coord::new(0, 1),
-                        Coord::new(0, -1),
-                    ],
                 );
             }
-            PieceKind::Bishop => {
-                gen_sliding(
+        }
+    }
+
+    if has_sink {
+        out.push(Succ::Sink);
+    }
+
+    out
+}
+
+/// Generates every leap/ride move of the piece in `slot_idx`, per `movement` (see
+/// [`PieceMovement`]). Replaces the old per-kind `gen_sliding` plus the inlined Knight leap loop:
+/// a leap tries one destination, a ride asks [`Game::move_rays`] for the destination squares
+/// before the first blocker (capped at `movement.ride_bound` if set).
+///
+/// King stays out of this (see its `white_succ` arm): it also forbids landing adjacent to the
+/// black king, a rule about kings specifically rather than a property of its move shape.
+pub fn gen_piece_movement(
+    game: &Game,
+    bk_sq: u16,
+    scratch: &mut Scratch,
+    slot_idx: usize,
+    occ_all: &Occ,
+    movement: &PieceMovement,
+    out: &mut Vec<Succ>,
+    has_sink: &mut bool,
+) {
+    let region = &game.region;
+
+    let from_sq = scratch.whites[slot_idx];
+    let from = region.coord_of(from_sq);
+
+    for &leap in &movement.leaps {
+        let dst = Coord::new(from.x + leap.x, from.y + leap.y);
+        if let Some(dst_sq) = region.sq_of(dst) {
+            if occ_all.get(dst_sq) {
+                continue;
+            }
+            let u = apply(
+                game,
+                scratch,
+                Move {
+                    slot_idx,
+                    to: dst_sq,
+                },
+            );
+            let st = game.packer.pack(bk_sq, &scratch.whites);
+            undo(game, scratch, u);
+            out.push(Succ::State(st));
+        } else {
+            *has_sink = true;
+        }
+    }
+
+    for dir in &movement.rides {
+        let ride_bound = movement.ride_bound.map(|b| b as usize);
+        let (squares, sink) = game.move_rays.ride(from_sq, *dir, ride_bound, occ_all);
+        for &cur_sq in squares {
+            let u = apply(
+                game,
+                scratch,
+                Move {
+                    slot_idx,
+                    to: cur_sq,
+                },
+            );
+            let st = game.packer.pack(bk_sq, &scratch.whites);
+            undo(game, scratch, u);
+            out.push(Succ::State(st));
+        }
+        if sink {
+            *has_sink = true;
+        }
+    }
+}
+
+/// Hash-threading twin of [`black_succ`]: identical move generation (including the same
+/// [`apply`]/[`undo`] in-place capture instead of a `whites.to_vec()`), but each pushed
+/// [`Succ::State`] carries its hash derived from `hash` via
+/// [`ZobristKeys::update_black_king`](crate::zobrist::ZobristKeys::update_black_king) /
+/// [`ZobristKeys::update_piece`](crate::zobrist::ZobristKeys::update_piece) instead of a full
+/// [`ZobristKeys::hash_packed_incremental`](crate::zobrist::ZobristKeys::hash_packed_incremental).
+fn black_succ_hashed(
+    game: &Game,
+    bk_sq: u16,
+    scratch: &mut Scratch,
+    captured_code: u16,
+    hash: u64,
+) -> Vec<(Succ, u64)> {
+    let region = &game.region;
+    let layout = &game.layout;
+
+    let bk_c = region.coord_of(bk_sq);
+    let mut occ_white = build_white_occupancy(region, &scratch.whites, captured_code);
+
+    let steps: [Coord; 8] = KING_DIRS;
+
+    let mut out: Vec<(Succ, u64)> = Vec::new();
+    let mut has_sink = false;
+
+    for step in steps {
+        let dst = Coord::new(bk_c.x + step.x, bk_c.y + step.y);
+        if let Some(dst_sq) = region.sq_of(dst) {
+            if occ_white.get(dst_sq) {
+                let Some(slot_idx) = find_slot_at(&scratch.whites, dst_sq, captured_code) else {
+                    continue;
+                };
+                if layout.slots[slot_idx] == PieceKind::King {
+                    continue;
+                }
+
+                // See `black_succ`'s identical capture branch: the captured piece stops blocking
+                // rays/occupancy, so the check test needs it cleared from `occ_white` too,
+                // restored right after either way.
+                occ_white.clear(dst_sq);
+                let u = apply(
                     game,
-                    bk_sq,
-                    whites,
+                    scratch,
+                    Move {
+                        slot_idx,
+                        to: captured_code,
+                    },
+                );
+
+                let attacked = is_attacked_by_white(
+                    dst,
+                    region,
+                    &game.attack_tables,
+                    layout,
+                    &scratch.whites,
                     captured_code,
-                    slot_idx,
-                    &occ_all,
-                    &mut out,
-                    &mut has_sink,
-                    &[
-                        Coord::new(1, 1),
-                        Coord::new(1, -1),
-                        Coord::new(-1, 1),
-                        Coord::new(-1, -1),
-                    ],
+                    &occ_white,
+                    &game.movement_registry,
+                );
+                let result = (!attacked).then(|| {
+                    let st = game.packer.pack(dst_sq, &scratch.whites);
+                    let h = game.zobrist.update_black_king(hash, bk_sq, dst_sq);
+                    let h = game
+                        .zobrist
+                        .update_piece(h, slot_idx, dst_sq, captured_code);
+                    (st, h)
+                });
+
+                undo(game, scratch, u);
+                occ_white.set(dst_sq);
+
+                if let Some((st, h)) = result {
+                    out.push((Succ::State(st), h));
+                }
+            } else {
+                let attacked = is_attacked_by_white(
+                    dst,
+                    region,
+                    &game.attack_tables,
+                    layout,
+                    &scratch.whites,
+                    captured_code,
+                    &occ_white,
+                    &game.movement_registry,
                 );
+                if attacked {
+                    continue;
+                }
+                let st = game.packer.pack(dst_sq, &scratch.whites);
+                let h = game.zobrist.update_black_king(hash, bk_sq, dst_sq);
+                out.push((Succ::State(st), h));
+            }
+        } else {
+            let attacked = is_attacked_by_white(
+                dst,
+                region,
+                &game.attack_tables,
+                layout,
+                &scratch.whites,
+                captured_code,
+                &occ_white,
+                &game.movement_registry,
+            );
+            if attacked {
+                continue;
+            }
+            has_sink = true;
+        }
+    }
+
+    if has_sink {
+        out.push((Succ::Sink, 0));
+    }
+
+    out
+}
+
+/// Hash-threading twin of [`white_succ`]; see [`black_succ_hashed`].
+fn white_succ_hashed(
+    game: &Game,
+    bk_sq: u16,
+    state: PackedState,
+    scratch: &mut Scratch,
+    captured_code: u16,
+    hash: u64,
+) -> Vec<(Succ, u64)> {
+    let region = &game.region;
+    let layout = &game.layout;
+
+    let bk_c = region.coord_of(bk_sq);
+
+    let occ_white = build_white_occupancy(region, &scratch.whites, captured_code);
+    let mut occ_all = occ_white.clone();
+    occ_all.set(bk_sq);
+
+    let mut out: Vec<(Succ, u64)> = Vec::new();
+    let mut has_sink = false;
+
+    if game.allow_pass {
+        out.push((Succ::State(state), hash));
+    }
+
+    for (slot_idx, kind) in layout.slots.iter().enumerate() {
+        let code = scratch.whites[slot_idx];
+        if code == captured_code {
+            continue;
+        }
+        let from = region.coord_of(code);
+
+        match kind {
+            PieceKind::King => {
+                let steps: [Coord; 8] = KING_DIRS;
+                for step in steps {
+                    let dst = Coord::new(from.x + step.x, from.y + step.y);
+                    if (dst.x - bk_c.x).abs() <= 1 && (dst.y - bk_c.y).abs() <= 1 {
+                        continue;
+                    }
+                    if let Some(dst_sq) = region.sq_of(dst) {
+                        if occ_all.get(dst_sq) {
+                            continue;
+                        }
+                        let u = apply(
+                            game,
+                            scratch,
+                            Move {
+                                slot_idx,
+                                to: dst_sq,
+                            },
+                        );
+                        let st = game.packer.pack(bk_sq, &scratch.whites);
+                        let h = game.zobrist.update_piece(hash, slot_idx, code, dst_sq);
+                        undo(game, scratch, u);
+                        out.push((Succ::State(st), h));
+                    } else {
+                        has_sink = true;
+                    }
+                }
             }
-            PieceKind::Queen => {
-                gen_sliding(
+            PieceKind::Pawn => {
+                let dst = Coord::new(from.x, from.y + game.layout.pawn_forward.y);
+                if let Some(dst_sq) = region.sq_of(dst) {
+                    if !occ_all.get(dst_sq) {
+                        if layout.promotion_reached(dst.y) {
+                            for &promo_kind in &layout.promotion_kinds {
+                                let Some(reserve_idx) = find_reserve_slot(
+                                    &scratch.whites,
+                                    layout,
+                                    promo_kind,
+                                    captured_code,
+                                ) else {
+                                    continue;
+                                };
+                                let u_pawn = apply(
+                                    game,
+                                    scratch,
+                                    Move {
+                                        slot_idx,
+                                        to: captured_code,
+                                    },
+                                );
+                                let u_reserve = apply(
+                                    game,
+                                    scratch,
+                                    Move {
+                                        slot_idx: reserve_idx,
+                                        to: dst_sq,
+                                    },
+                                );
+                                let st = game.packer.pack(bk_sq, &scratch.whites);
+                                let h =
+                                    game.zobrist
+                                        .update_piece(hash, slot_idx, code, captured_code);
+                                let h = game.zobrist.update_piece(
+                                    h,
+                                    reserve_idx,
+                                    captured_code,
+                                    dst_sq,
+                                );
+                                undo(game, scratch, u_reserve);
+                                undo(game, scratch, u_pawn);
+                                out.push((Succ::State(st), h));
+                            }
+                        } else {
+                            let u = apply(
+                                game,
+                                scratch,
+                                Move {
+                                    slot_idx,
+                                    to: dst_sq,
+                                },
+                            );
+                            let st = game.packer.pack(bk_sq, &scratch.whites);
+                            let h = game.zobrist.update_piece(hash, slot_idx, code, dst_sq);
+                            undo(game, scratch, u);
+                            out.push((Succ::State(st), h));
+                        }
+                    }
+                } else {
+                    has_sink = true;
+                }
+            }
+            other => {
+                let movement = game
+                    .movement_registry
+                    .get(*other)
+                    .unwrap_or_else(|| panic!("no movement descriptor for {other:?}"));
+                gen_piece_movement_hashed(
                     game,
                     bk_sq,
-                    whites,
-                    captured_code,
+                    scratch,
                     slot_idx,
                     &occ_all,
+                    movement,
+                    hash,
                     &mut out,
                     &mut has_sink,
-                    &[
-                        Coord::new(1, 0),
-                        Coord::new(-1, 0),
-                        Coord::new(0, 1),
-                        Coord::new(0, -1),
-                        Coord::new(1, 1),
-                        Coord::new(1, -1),
-                        Coord::new(-1, 1),
-                        Coord::new(-1, -1),
-                    ],
                 );
             }
         }
     }
 
     if has_sink {
-        out.push(Succ::Sink);
+        out.push((Succ::Sink, 0));
     }
 
     out
 }
 
-fn gen_sliding(
+/// Hash-threading twin of [`gen_piece_movement`]; see [`black_succ_hashed`].
+#[allow(clippy::too_many_arguments)]
+fn gen_piece_movement_hashed(
     game: &Game,
     bk_sq: u16,
-    whites: &[u16],
-    _captured_code: u16,
+    scratch: &mut Scratch,
     slot_idx: usize,
     occ_all: &Occ,
-    out: &mut Vec<Succ>,
+    movement: &PieceMovement,
+    hash: u64,
+    out: &mut Vec<(Succ, u64)>,
     has_sink: &mut bool,
-    dirs: &[Coord],
 ) {
     let region = &game.region;
-    let layout = &game.layout;
 
-    let from_sq = whites[slot_idx];
+    let from_sq = scratch.whites[slot_idx];
     let from = region.coord_of(from_sq);
-    let bound = game.move_bound;
-
-    for dir in dirs {
-        let mut step_count: u16 = 0;
-        let mut cur = Coord::new(from.x + dir.x, from.y + dir.y);
 
-        loop {
-            if let Some(b) = bound {
-                if step_count >= b {
-                    break;
-                }
+    for &leap in &movement.leaps {
+        let dst = Coord::new(from.x + leap.x, from.y + leap.y);
+        if let Some(dst_sq) = region.sq_of(dst) {
+            if occ_all.get(dst_sq) {
+                continue;
             }
+            let u = apply(
+                game,
+                scratch,
+                Move {
+                    slot_idx,
+                    to: dst_sq,
+                },
+            );
+            let st = game.packer.pack(bk_sq, &scratch.whites);
+            let h = game.zobrist.update_piece(hash, slot_idx, from_sq, dst_sq);
+            undo(game, scratch, u);
+            out.push((Succ::State(st), h));
+        } else {
+            *has_sink = true;
+        }
+    }
 
-            if let Some(cur_sq) = region.sq_of(cur) {
-                if occ_all.get(cur_sq) {
-                    break;
-                }
-                let mut whites2 = whites.to_vec();
-                whites2[slot_idx] = cur_sq;
-                canonicalize(&mut whites2, layout);
-                let st = game.packer.pack(bk_sq, &whites2);
-                out.push(Succ::State(st));
-
-                step_count += 1;
-                cur = Coord::new(cur.x + dir.x, cur.y + dir.y);
-            } else {
-                *has_sink = true;
-                break;
-            }
+    for dir in &movement.rides {
+        let ride_bound = movement.ride_bound.map(|b| b as usize);
+        let (squares, sink) = game.move_rays.ride(from_sq, *dir, ride_bound, occ_all);
+        for &cur_sq in squares {
+            let u = apply(
+                game,
+                scratch,
+                Move {
+                    slot_idx,
+                    to: cur_sq,
+                },
+            );
+            let st = game.packer.pack(bk_sq, &scratch.whites);
+            // Every ray step is an independent single-ply move of this piece away from its
+            // original `from_sq`, not a chain through the previous step's square, so the
+            // update is always relative to the base `hash` (piece still at `from_sq`).
+            let h = game.zobrist.update_piece(hash, slot_idx, from_sq, cur_sq);
+            undo(game, scratch, u);
+            out.push((Succ::State(st), h));
+        }
+        if sink {
+            *has_sink = true;
         }
     }
 }
@@ -321,3 +774,112 @@ fn find_slot_at(whites: &[u16], sq: u16, captured_code: u16) -> Option<usize> {
         .find(|(_, &c)| c != captured_code && c == sq)
         .map(|(i, _)| i)
 }
+
+/// The first still-unused reserve slot in `kind`'s group (see `Material::promotion_kinds`), for a
+/// pawn promoting to `kind`. `None` if `kind` has no group (never a promotion target in this
+/// material) or every reserve slot of it is already occupied by an earlier promotion.
+fn find_reserve_slot(
+    whites: &[u16],
+    layout: &Layout,
+    kind: PieceKind,
+    captured_code: u16,
+) -> Option<usize> {
+    let group = layout.group(kind)?;
+    (group.start..group.start + group.len).find(|&i| whites[i] == captured_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::pieces::Material;
+    use crate::region::Region;
+
+    /// A pawn one step away from `promotion_rank`, with no other white piece nearby, so its only
+    /// non-sink successors are the `layout.promotion_kinds` promotion branch in `white_succ`.
+    /// Returns the game plus the black king and pawn-destination coords used to build expectations.
+    fn promoting_pawn_game() -> (Game, Coord, Coord) {
+        let region = Region::linf(4);
+        let material = Material::new()
+            .with_white_king(true)
+            .with_pawns(1)
+            .with_pawn_forward(Coord::new(0, 1))
+            .with_promotion(3, vec![PieceKind::Queen]);
+        let game = Game::new(region, material);
+
+        let bk = Coord::new(-4, -4);
+        let pawn_to = Coord::new(0, 3);
+
+        (game, bk, pawn_to)
+    }
+
+    #[test]
+    fn pawn_reaching_promotion_rank_promotes_to_queen() {
+        let (game, bk, pawn_to) = promoting_pawn_game();
+        let wk = Coord::new(4, 4);
+        let pawn_from = Coord::new(0, 2);
+
+        let whites = vec![Some(wk), None, Some(pawn_from)];
+        let state = game.pack_from_coords(bk, &whites);
+
+        let mut scratch = Scratch::new(game.layout.total_white());
+        let succs = successors(&game, Turn::White, state, &mut scratch);
+
+        let pawn_group = game.layout.group(PieceKind::Pawn).unwrap();
+        let queen_group = game.layout.group(PieceKind::Queen).unwrap();
+        let captured_code = game.captured_code();
+
+        let promoted = succs
+            .iter()
+            .filter_map(|s| match s {
+                Succ::State(st) => Some(*st),
+                Succ::Sink => None,
+            })
+            .find(|&st| {
+                let mut whites_out = vec![0u16; game.layout.total_white()];
+                game.packer.unpack(st, &mut whites_out);
+                let pawn_gone = whites_out[pawn_group.start..pawn_group.start + pawn_group.len]
+                    .iter()
+                    .all(|&c| c == captured_code);
+                let queen_at_dst = whites_out
+                    [queen_group.start..queen_group.start + queen_group.len]
+                    .iter()
+                    .any(|&c| game.region.coord_of(c) == pawn_to);
+                pawn_gone && queen_at_dst
+            })
+            .expect("pawn reaching its promotion rank must produce a promoted successor");
+
+        let mut whites_out = vec![0u16; game.layout.total_white()];
+        let promoted_bk = game.packer.unpack(promoted, &mut whites_out);
+        assert_eq!(game.region.coord_of(promoted_bk), bk);
+
+        // The successor must look exactly like a position with one fewer pawn and one more queen
+        // at `pawn_to`, rather than some other encoding of the same material.
+        let expected = game.pack_from_coords(bk, &[Some(wk), Some(pawn_to), None]);
+        assert_eq!(promoted, expected);
+    }
+
+    #[test]
+    fn pawn_promotion_hash_matches_full_recompute() {
+        let (game, bk, pawn_to) = promoting_pawn_game();
+        let wk = Coord::new(4, 4);
+        let pawn_from = Coord::new(0, 2);
+
+        let whites = vec![Some(wk), None, Some(pawn_from)];
+        let state = game.pack_from_coords(bk, &whites);
+        let hash = game.zobrist.hash_packed_incremental(state);
+
+        let mut scratch = Scratch::new(game.layout.total_white());
+        let succs_hashed = successors_hashed(&game, Turn::White, state, hash, &mut scratch);
+
+        let expected = game.pack_from_coords(bk, &[Some(wk), Some(pawn_to), None]);
+        let expected_hash = game.zobrist.hash_packed_incremental(expected);
+
+        let (_, promoted_hash) = succs_hashed
+            .iter()
+            .find(|(s, _)| matches!(s, Succ::State(st) if *st == expected))
+            .expect("hashed successors must include the promoted state");
+
+        assert_eq!(*promoted_hash, expected_hash);
+    }
+}
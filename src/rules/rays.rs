@@ -0,0 +1,62 @@
+//! Shared direction vectors for king/knight/rider move generation and attack detection.
+//!
+//! The actual per-square destination-square precomputation for riders lives in
+//! [`crate::region::MoveRays`] (move generation) and [`crate::region::RayTable`] (attack
+//! detection) — both index by a square's *region* square, which this module, being purely about
+//! direction vectors, has no notion of.
+
+use crate::coord::Coord;
+
+/// The 8 king-step directions, in a fixed order shared by black-king adjacency checks and white
+/// king move generation.
+pub const KING_DIRS: [Coord; 8] = [
+    Coord::new(-1, -1),
+    Coord::new(-1, 0),
+    Coord::new(-1, 1),
+    Coord::new(0, -1),
+    Coord::new(0, 1),
+    Coord::new(1, -1),
+    Coord::new(1, 0),
+    Coord::new(1, 1),
+];
+
+/// The 8 knight-leap offsets, precomputed once rather than rebuilt inside every move-generation
+/// call.
+pub const KNIGHT_OFFSETS: [Coord; 8] = [
+    Coord::new(1, 2),
+    Coord::new(2, 1),
+    Coord::new(-1, 2),
+    Coord::new(-2, 1),
+    Coord::new(1, -2),
+    Coord::new(2, -1),
+    Coord::new(-1, -2),
+    Coord::new(-2, -1),
+];
+
+/// The 4 orthogonal rook ride directions.
+pub const ROOK_DIRS: [Coord; 4] = [
+    Coord::new(1, 0),
+    Coord::new(-1, 0),
+    Coord::new(0, 1),
+    Coord::new(0, -1),
+];
+
+/// The 4 diagonal bishop ride directions.
+pub const BISHOP_DIRS: [Coord; 4] = [
+    Coord::new(1, 1),
+    Coord::new(1, -1),
+    Coord::new(-1, 1),
+    Coord::new(-1, -1),
+];
+
+/// The 8 queen ride directions: [`ROOK_DIRS`] followed by [`BISHOP_DIRS`].
+pub const QUEEN_DIRS: [Coord; 8] = [
+    Coord::new(1, 0),
+    Coord::new(-1, 0),
+    Coord::new(0, 1),
+    Coord::new(0, -1),
+    Coord::new(1, 1),
+    Coord::new(1, -1),
+    Coord::new(-1, 1),
+    Coord::new(-1, -1),
+];
@@ -10,14 +10,21 @@
 //! This separation keeps the core rules reusable and makes the semantics of “trap vs boundary”
 //! explicit: leaving the domain is *allowed*, but it may count as escape depending on objective.
 
+pub mod notation;
+
 use std::fmt;
+use std::path::Path;
+
+use rustc_hash::FxHashSet;
 
 use crate::chess::bounds::is_in_bound;
-use crate::chess::rules::Rules;
+use crate::chess::layout::{LayoutTextError, PieceLayout};
+use crate::chess::rules::{InvalidPosition, Rules};
 use crate::core::coord::Coord;
-use crate::core::position::Position;
+use crate::core::position::{Position, PositionTextError};
+use crate::core::zobrist;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Side to move in a game state.
 pub enum Side {
     Black,
@@ -44,6 +51,35 @@ impl State {
     pub fn new(abs_king: Coord, pos: Position) -> Self {
         Self { abs_king, pos }
     }
+
+    /// Serialize to the textual board format (see [`Position::to_text`]). Side-to-move is not
+    /// part of a bare `State`; see [`StartState::to_text`] for that.
+    pub fn to_text(&self, layout: &PieceLayout) -> String {
+        self.pos.to_text(self.abs_king, layout)
+    }
+
+    /// Parse the textual board format produced by [`State::to_text`].
+    pub fn from_text(text: &str, layout: &PieceLayout) -> Result<State, PositionTextError> {
+        let (abs_king, pos) = Position::from_text(text, layout)?;
+        Ok(State::new(abs_king, pos))
+    }
+
+    /// Zobrist hash over absolute squares (see [`crate::core::zobrist`]).
+    ///
+    /// Two states with different `abs_king` but the same relative `pos` hash differently, since
+    /// every piece key is derived from its *absolute* square; this is what makes the hash usable
+    /// as a transposition key for `track_abs_king=true` scenarios, where such states are distinct.
+    pub fn zobrist(&self, layout: &PieceLayout) -> u64 {
+        let mut hash = zobrist::black_king_key(self.abs_king);
+        for i in 0..self.pos.count() {
+            let sq = self.pos.square(i);
+            if sq.is_none() {
+                continue;
+            }
+            hash = zobrist::toggle_piece(hash, layout.kind(i), sq.coord() + self.abs_king);
+        }
+        hash
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,6 +89,200 @@ pub struct StartState {
     pub state: State,
 }
 
+impl StartState {
+    /// Serialize to the full textual format, e.g. `k0,0 R3,-2 R-5,7 btm`: the board (see
+    /// [`State::to_text`]) plus a trailing side-to-move token (`btm`/`wtm`).
+    pub fn to_text(&self, layout: &PieceLayout) -> String {
+        format!(
+            "{} {}",
+            self.state.to_text(layout),
+            side_to_text(self.to_move)
+        )
+    }
+
+    /// Parse the textual format produced by [`StartState::to_text`].
+    pub fn from_text(text: &str, layout: &PieceLayout) -> Result<StartState, PositionTextError> {
+        let (board, side_tok) = text
+            .rsplit_once(char::is_whitespace)
+            .ok_or(PositionTextError::MissingSideToMove)?;
+        let to_move = side_from_text(side_tok.trim())?;
+        let state = State::from_text(board, layout)?;
+        Ok(StartState { to_move, state })
+    }
+}
+
+/// Serialize a full scenario setup to a single line: the layout letters, the move bound as
+/// `mb<N>`, and the [`StartState::to_text`] board, e.g. `KQ mb7 k0,0 K2,2 Q3,-2 btm`.
+///
+/// Pairs with [`parse_scenario_text`], which rebuilds `rules` and `start` from exactly this line.
+pub fn scenario_to_text(rules: &Rules, start: &StartState) -> String {
+    format!(
+        "{} mb{} {}",
+        rules.layout.to_text(),
+        rules.move_bound,
+        start.to_text(&rules.layout)
+    )
+}
+
+/// Parse the format produced by [`scenario_to_text`], rebuilding a [`Rules`] (from the layout
+/// letters and move bound) and a [`StartState`] (from the remaining board text) in one call.
+///
+/// Also runs [`Rules::validate_position`] against the parsed start, so e.g. a white king adjacent
+/// to the black king's origin is rejected here rather than surfacing later as a confusing
+/// `Scenario::validate` failure.
+pub fn parse_scenario_text(text: &str) -> Result<(Rules, StartState), ScenarioTextError> {
+    let mut parts = text.splitn(3, char::is_whitespace);
+    let layout_tok = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or(ScenarioTextError::MissingLayout)?;
+    let move_bound_tok = parts.next().ok_or(ScenarioTextError::MissingMoveBound)?;
+    let rest = parts.next().ok_or(ScenarioTextError::MissingBoard)?;
+
+    let layout = PieceLayout::from_text(layout_tok)?;
+
+    let move_bound_digits = move_bound_tok
+        .strip_prefix("mb")
+        .ok_or_else(|| ScenarioTextError::InvalidMoveBound(move_bound_tok.to_string()))?;
+    let move_bound: i32 = move_bound_digits
+        .parse()
+        .map_err(|_| ScenarioTextError::InvalidMoveBound(move_bound_tok.to_string()))?;
+    if move_bound < 1 {
+        return Err(ScenarioTextError::InvalidMoveBound(
+            move_bound_tok.to_string(),
+        ));
+    }
+
+    let rules = Rules::new(layout, move_bound);
+    let start = StartState::from_text(rest, &rules.layout)?;
+    rules.validate_position(&start.state.pos)?;
+
+    Ok((rules, start))
+}
+
+/// Write a trap set (e.g. the output of
+/// [`maximal_inescapable_trap`](crate::search::trap::maximal_inescapable_trap) or
+/// [`tempo_trap_buchi`](crate::search::buchi::tempo_trap_buchi)) to `path`, one
+/// [`State::to_text`] line per state.
+///
+/// Lines are sorted before writing so the file is byte-identical across runs regardless of the
+/// set's iteration order, which is what makes it usable as a diffable golden test fixture.
+pub fn save_trap(
+    path: &Path,
+    trap: &FxHashSet<State>,
+    layout: &PieceLayout,
+) -> Result<(), SearchError> {
+    let mut lines: Vec<String> = trap.iter().map(|s| s.to_text(layout)).collect();
+    lines.sort();
+
+    let mut text = lines.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+
+    std::fs::write(path, text).map_err(|e| SearchError::Io {
+        stage: "trap_save",
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })
+}
+
+/// Load a trap set previously written by [`save_trap`].
+pub fn load_trap(path: &Path, layout: &PieceLayout) -> Result<FxHashSet<State>, SearchError> {
+    let text = std::fs::read_to_string(path).map_err(|e| SearchError::Io {
+        stage: "trap_load",
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+
+    let mut out: FxHashSet<State> = FxHashSet::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let state = State::from_text(line, layout).map_err(|e| SearchError::InvalidScenario {
+            reason: format!("trap_load: {e} (line: {line:?})"),
+        })?;
+        out.insert(state);
+    }
+
+    Ok(out)
+}
+
+/// Errors from [`parse_scenario_text`].
+#[derive(Debug)]
+pub enum ScenarioTextError {
+    /// The layout-letters token was missing.
+    MissingLayout,
+    /// The `mb<N>` move-bound token was missing.
+    MissingMoveBound,
+    /// The token wasn't `mb` followed by a positive integer.
+    InvalidMoveBound(String),
+    /// The board text after the move bound was missing.
+    MissingBoard,
+    /// The layout letters didn't parse; see [`LayoutTextError`].
+    Layout(LayoutTextError),
+    /// The board text didn't parse; see [`PositionTextError`].
+    Board(PositionTextError),
+    /// The parsed start position violates pure rules; see [`InvalidPosition`].
+    IllegalStart(InvalidPosition),
+}
+
+impl fmt::Display for ScenarioTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioTextError::MissingLayout => write!(f, "missing layout-letters token"),
+            ScenarioTextError::MissingMoveBound => write!(f, "missing move-bound token"),
+            ScenarioTextError::InvalidMoveBound(tok) => {
+                write!(
+                    f,
+                    "invalid move-bound token {tok:?}, expected \"mb<N>\" with N >= 1"
+                )
+            }
+            ScenarioTextError::MissingBoard => write!(f, "missing board text"),
+            ScenarioTextError::Layout(e) => write!(f, "invalid layout: {e}"),
+            ScenarioTextError::Board(e) => write!(f, "invalid board: {e}"),
+            ScenarioTextError::IllegalStart(e) => write!(f, "illegal start position: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioTextError {}
+
+impl From<LayoutTextError> for ScenarioTextError {
+    fn from(e: LayoutTextError) -> Self {
+        ScenarioTextError::Layout(e)
+    }
+}
+
+impl From<PositionTextError> for ScenarioTextError {
+    fn from(e: PositionTextError) -> Self {
+        ScenarioTextError::Board(e)
+    }
+}
+
+impl From<InvalidPosition> for ScenarioTextError {
+    fn from(e: InvalidPosition) -> Self {
+        ScenarioTextError::IllegalStart(e)
+    }
+}
+
+fn side_to_text(side: Side) -> &'static str {
+    match side {
+        Side::Black => "btm",
+        Side::White => "wtm",
+    }
+}
+
+fn side_from_text(tok: &str) -> Result<Side, PositionTextError> {
+    match tok {
+        "btm" => Ok(Side::Black),
+        "wtm" => Ok(Side::White),
+        other => Err(PositionTextError::InvalidSideToMove(other.to_string())),
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 /// Search budgets used to bound memory/time consumption.
 ///
@@ -61,12 +291,17 @@ pub struct StartState {
 /// - `max_edges`: number of generated moves/edges
 /// - cache limits: number of cached entries and total cached moves
 /// - `max_runtime_steps`: generic loop-iteration guard
+/// - `parallel_attractor`: opt-in rayon-parallel attractor rounds for large Büchi graphs
+/// - `parallel_trap`: opt-in thread-sharded rounds for
+///   [`crate::search::trap::maximal_inescapable_trap`]'s large-universe fixed point
 pub struct ResourceLimits {
     pub max_states: usize,
     pub max_edges: usize,
     pub max_cache_entries: usize,
     pub max_cached_moves: usize,
     pub max_runtime_steps: u64,
+    pub parallel_attractor: bool,
+    pub parallel_trap: bool,
 }
 
 impl Default for ResourceLimits {
@@ -77,6 +312,8 @@ impl Default for ResourceLimits {
             max_cache_entries: 250_000,
             max_cached_moves: 15_000_000,
             max_runtime_steps: 200_000_000,
+            parallel_attractor: false,
+            parallel_trap: false,
         }
     }
 }
@@ -197,11 +434,83 @@ pub trait LawsLike {
     }
 }
 
+/// How to resolve ties among candidate moves whose [`PreferencesLike::tie_break_keys`] disagree
+/// only partway through the key sequence, borrowed from forwards/backwards tie-breaking in
+/// preferential-voting counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The earliest key in the sequence that discriminates between two moves decides between
+    /// them (e.g. "shortest DTM first" if DTM is the first key).
+    Forwards,
+    /// The deepest (last) discriminating key decides, i.e. moves are compared key-by-key from the
+    /// end of the sequence backwards.
+    Backwards,
+}
+
 pub trait PreferencesLike {
-    /// Return an ordering (indices into `moves`) to be used when choosing a black move for demos.
-    fn rank_black_moves(&self, from: &State, moves: &[State]) -> Vec<usize>;
-    /// Return an ordering (indices into `moves`) to be used when choosing a white move for demos.
-    fn rank_white_moves(&self, from: &State, moves: &[State]) -> Vec<usize>;
+    /// Ordered, most-significant-key-first, secondary ranking keys for the candidate move that
+    /// ends in state `mv` from `from`. Smaller is preferred at each key. Used only to
+    /// deterministically break ties among moves that are otherwise equally good (see
+    /// [`TieBreak`]); an empty sequence (the default) means "no opinion", which keeps all moves
+    /// tied and falls back to their original relative order.
+    fn tie_break_keys(&self, from: &State, mv: &State) -> Vec<i64> {
+        let _ = (from, mv);
+        Vec::new()
+    }
+
+    /// Return an ordering (indices into `moves`) to be used when choosing a black move for demos,
+    /// resolved via [`Self::tie_break_keys`] read `tie_break`-wise.
+    fn rank_black_moves(&self, from: &State, moves: &[State], tie_break: TieBreak) -> Vec<usize> {
+        rank_by_tie_break_keys(self, from, moves, tie_break)
+    }
+    /// Return an ordering (indices into `moves`) to be used when choosing a white move for demos,
+    /// resolved via [`Self::tie_break_keys`] read `tie_break`-wise.
+    fn rank_white_moves(&self, from: &State, moves: &[State], tie_break: TieBreak) -> Vec<usize> {
+        rank_by_tie_break_keys(self, from, moves, tie_break)
+    }
+}
+
+fn rank_by_tie_break_keys<T: PreferencesLike + ?Sized>(
+    prefs: &T,
+    from: &State,
+    moves: &[State],
+    tie_break: TieBreak,
+) -> Vec<usize> {
+    let keys: Vec<Vec<i64>> = moves
+        .iter()
+        .map(|mv| prefs.tie_break_keys(from, mv))
+        .collect();
+    let mut idx: Vec<usize> = (0..moves.len()).collect();
+    idx.sort_by(|&a, &b| compare_tie_break_keys(&keys[a], &keys[b], tie_break));
+    idx
+}
+
+/// Compare two key sequences forwards (earliest discriminating key wins) or backwards (deepest
+/// discriminating key wins); a shorter sequence that is a prefix/suffix of the other is treated as
+/// smaller, and fully-equal sequences compare `Equal` (the caller's sort is stable, so original
+/// relative order is preserved for true ties).
+fn compare_tie_break_keys(a: &[i64], b: &[i64], tie_break: TieBreak) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let found = match tie_break {
+        TieBreak::Forwards => a.iter().zip(b.iter()).find_map(|(x, y)| match x.cmp(y) {
+            Ordering::Equal => None,
+            other => Some(other),
+        }),
+        TieBreak::Backwards => {
+            a.iter()
+                .rev()
+                .zip(b.iter().rev())
+                .find_map(|(x, y)| match x.cmp(y) {
+                    Ordering::Equal => None,
+                    other => Some(other),
+                })
+        }
+    };
+
+    found
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.len().cmp(&b.len()))
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -218,16 +527,10 @@ impl DomainLike for AllDomain {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+/// No preferences: every candidate ties, so `rank_*_moves` just returns moves in their original
+/// order (the default `tie_break_keys` implementation already does this).
 pub struct NoPreferences;
-impl PreferencesLike for NoPreferences {
-    fn rank_black_moves(&self, _from: &State, moves: &[State]) -> Vec<usize> {
-        (0..moves.len()).collect()
-    }
-
-    fn rank_white_moves(&self, _from: &State, moves: &[State]) -> Vec<usize> {
-        (0..moves.len()).collect()
-    }
-}
+impl PreferencesLike for NoPreferences {}
 
 #[derive(Debug, Clone)]
 /// How to build the candidate set for trap search.
@@ -238,6 +541,12 @@ pub enum CandidateGeneration {
     FromStates { states: Vec<State> },
     /// Explore states reachable from the required `start` (often much smaller than enumeration).
     ReachableFromStart { max_queue: usize },
+    /// Grow candidates backward from the checkmate positions within `bound`: a bounded backward
+    /// breadth-first search over un-moves, stopping at states that leave the domain or once
+    /// `max_queue` states are pending. Much smaller than [`CandidateGeneration::InLinfBound`] when
+    /// mates live in a thin region of a large box, since only states from which a mate is reachable
+    /// are ever materialized.
+    BackwardFromMates { bound: i32, max_queue: usize },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -249,6 +558,12 @@ pub enum CacheMode {
     BlackOnly,
     /// Cache both black and white moves (bounded by [`ResourceLimits`]).
     BothBounded,
+    /// Cache both black and white moves, keyed by [`State::zobrist`] instead of the full `State`.
+    ///
+    /// Avoids hashing/comparing the whole piece placement on every cache lookup; prefer this over
+    /// `BothBounded` once the piece count makes `State`'s derived `Hash`/`Eq` a measurable cost.
+    /// Like `BothBounded`, still bounded by [`ResourceLimits`].
+    ZobristKeyed,
 }
 
 #[derive(Debug, Clone)]
@@ -265,6 +580,8 @@ pub struct Scenario<D, L, P> {
     pub domain: D,
     pub laws: L,
     pub preferences: P,
+    /// How `preferences.rank_*_moves` resolves ties among moves with a multi-key tie-break.
+    pub tie_break: TieBreak,
     pub limits: ResourceLimits,
     pub cache_mode: CacheMode,
     pub remove_stalemates: bool,
@@ -281,9 +598,9 @@ impl<D: DomainLike, L: LawsLike, P> Scenario<D, L, P> {
             });
         }
 
-        if !self.rules.is_legal_position(&s.pos) {
+        if let Err(e) = self.rules.validate_position(&s.pos) {
             return Err(SearchError::InvalidScenario {
-                reason: "start position is not legal under pure rules".to_string(),
+                reason: format!("start position is not legal under pure rules: {e}"),
             });
         }
 
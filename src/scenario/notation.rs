@@ -0,0 +1,147 @@
+//! A self-describing, multi-state text bundle: one header line (layout, move bound, whether
+//! absolute king coordinates are tracked, and side-to-move) followed by one [`State::to_text`]
+//! line per record.
+//!
+//! Generalizes two existing ad-hoc formats:
+//! - [`super::save_trap`]/[`super::load_trap`] write many states but no header, so a caller must
+//!   already know `layout`/`move_bound`/`track_abs_king` out of band to make sense of the file.
+//! - `scenarios::nbb::parse_k_nbb_trap_file` hand-scans 8-integers-per-record hard-coded to the
+//!   B,B,N layout and absolute coordinates, with no matching writer.
+//!
+//! [`parse_states`]/[`write_states`] take an explicit `layout`/`rules` (mirroring
+//! `parse_k_nbb_trap_file`'s signature) rather than trusting the header alone: the header's layout
+//! and move-bound tokens are checked against them, so a bundle loaded against the wrong scenario
+//! fails fast instead of silently parsing garbage.
+
+use crate::chess::layout::PieceLayout;
+use crate::chess::rules::Rules;
+use crate::core::coord::Coord;
+
+use super::{side_from_text, side_to_text, SearchError, Side, State};
+
+/// Serialize `states` to the bundle format: a header line (`layout.to_text()`, `mb<N>`,
+/// `ab<0|1>` for `track_abs_king`, then `btm`/`wtm` for `side`), followed by one
+/// [`State::to_text`] line per state. States are sorted before writing so the result is
+/// byte-identical across runs regardless of `states`' original order.
+pub fn write_states(
+    states: &[State],
+    layout: &PieceLayout,
+    move_bound: i32,
+    track_abs_king: bool,
+    side: Side,
+) -> String {
+    let mut lines: Vec<String> = Vec::with_capacity(states.len() + 1);
+    lines.push(format!(
+        "{} mb{} ab{} {}",
+        layout.to_text(),
+        move_bound,
+        track_abs_king as u8,
+        side_to_text(side)
+    ));
+    let mut body: Vec<String> = states.iter().map(|s| s.to_text(layout)).collect();
+    body.sort();
+    lines.extend(body);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Parse the format produced by [`write_states`] (or hand-authored in the same shape),
+/// generalizing `scenarios::nbb::parse_k_nbb_trap_file` to any [`PieceLayout`].
+///
+/// Performs the same filtering the old parser did: each record is canonicalized (via
+/// [`State::from_text`]) and kept only if [`Rules::is_legal_position`] accepts it.
+pub fn parse_states(
+    text: &str,
+    layout: &PieceLayout,
+    rules: &Rules,
+) -> Result<Vec<State>, SearchError> {
+    let mut lines = text.lines();
+    let header = lines.next().ok_or_else(|| SearchError::InvalidScenario {
+        reason: "notation bundle: missing header line".to_string(),
+    })?;
+
+    let mut tokens = header.split_whitespace();
+    let layout_tok = tokens.next().ok_or_else(|| SearchError::InvalidScenario {
+        reason: "notation bundle: missing layout token in header".to_string(),
+    })?;
+    let header_layout =
+        PieceLayout::from_text(layout_tok).map_err(|e| SearchError::InvalidScenario {
+            reason: format!("notation bundle: invalid layout token {layout_tok:?}: {e}"),
+        })?;
+    if header_layout.to_text() != layout.to_text() {
+        return Err(SearchError::InvalidScenario {
+            reason: format!(
+                "notation bundle: header layout {:?} does not match expected {:?}",
+                header_layout.to_text(),
+                layout.to_text()
+            ),
+        });
+    }
+
+    let move_bound_tok = tokens.next().ok_or_else(|| SearchError::InvalidScenario {
+        reason: "notation bundle: missing move-bound token in header".to_string(),
+    })?;
+    let move_bound: i32 = move_bound_tok
+        .strip_prefix("mb")
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| SearchError::InvalidScenario {
+            reason: format!("notation bundle: invalid move-bound token {move_bound_tok:?}"),
+        })?;
+    if move_bound != rules.move_bound {
+        return Err(SearchError::InvalidScenario {
+            reason: format!(
+                "notation bundle: header move bound {move_bound} does not match expected {}",
+                rules.move_bound
+            ),
+        });
+    }
+
+    let abs_king_tok = tokens.next().ok_or_else(|| SearchError::InvalidScenario {
+        reason: "notation bundle: missing track-abs-king token in header".to_string(),
+    })?;
+    let track_abs_king = match abs_king_tok {
+        "ab0" => false,
+        "ab1" => true,
+        other => {
+            return Err(SearchError::InvalidScenario {
+                reason: format!("notation bundle: invalid track-abs-king token {other:?}"),
+            })
+        }
+    };
+
+    let side_tok = tokens.next().ok_or_else(|| SearchError::InvalidScenario {
+        reason: "notation bundle: missing side-to-move token in header".to_string(),
+    })?;
+    side_from_text(side_tok).map_err(|e| SearchError::InvalidScenario {
+        reason: format!("notation bundle: {e}"),
+    })?;
+
+    let mut out: Vec<State> = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let state = State::from_text(line, layout).map_err(|e| SearchError::InvalidScenario {
+            reason: format!("notation bundle: {e} (line: {line:?})"),
+        })?;
+
+        if !track_abs_king && state.abs_king != Coord::ORIGIN {
+            return Err(SearchError::InvalidScenario {
+                reason: format!(
+                    "notation bundle: header says ab0 but record has non-origin king anchor {:?}",
+                    state.abs_king
+                ),
+            });
+        }
+
+        if rules.is_legal_position(&state.pos) {
+            out.push(state);
+        }
+    }
+
+    Ok(out)
+}
@@ -19,7 +19,7 @@ use crate::core::position::{Position, MAX_PIECES};
 use crate::core::square::Square;
 use crate::scenario::{
     CacheMode, CandidateGeneration, DomainLike, NoLaws, NoPreferences, ResourceLimits, Scenario,
-    SearchError, Side, StartState, State,
+    SearchError, Side, StartState, State, TieBreak,
 };
 
 /// Built-in domains used by the built-in scenarios.
@@ -75,6 +75,8 @@ fn demo_limits() -> ResourceLimits {
         max_cache_entries: 100_000,
         max_cached_moves: 5_000_000,
         max_runtime_steps: 50_000_000,
+        parallel_attractor: false,
+        parallel_trap: false,
     }
 }
 
@@ -85,6 +87,8 @@ fn two_rooks_limits() -> ResourceLimits {
         max_cache_entries: 250_000,
         max_cached_moves: 15_000_000,
         max_runtime_steps: 2_000_000_000,
+        parallel_attractor: false,
+        parallel_trap: false,
     }
 }
 
@@ -115,6 +119,7 @@ pub fn three_rooks_bound2_mb1() -> BuiltInScenario {
         domain: BuiltinDomain::All,
         laws: NoLaws,
         preferences: NoPreferences,
+        tie_break: TieBreak::Forwards,
         limits: demo_limits(),
         cache_mode: CacheMode::BothBounded,
         remove_stalemates: true,
@@ -145,6 +150,7 @@ pub fn two_rooks_bound7() -> BuiltInScenario {
         domain: BuiltinDomain::AbsBox { bound: 7 },
         laws: NoLaws,
         preferences: NoPreferences,
+        tie_break: TieBreak::Forwards,
         limits: two_rooks_limits(),
         cache_mode: CacheMode::BothBounded,
         remove_stalemates: true,
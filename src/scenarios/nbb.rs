@@ -28,7 +28,7 @@ use crate::core::position::{Position, MAX_PIECES};
 use crate::core::square::Square;
 use crate::scenario::{
     CacheMode, CandidateGeneration, NoLaws, NoPreferences, ResourceLimits, Scenario, SearchError,
-    Side, StartState, State,
+    Side, StartState, State, TieBreak,
 };
 
 use super::BuiltinDomain;
@@ -71,6 +71,7 @@ pub fn nbb20_from_file() -> Result<Scenario<BuiltinDomain, NoLaws, NoPreferences
         domain: BuiltinDomain::All,
         laws: NoLaws,
         preferences: NoPreferences,
+        tie_break: TieBreak::Forwards,
         limits: nbb_limits(),
         cache_mode: CacheMode::BlackOnly,
         remove_stalemates: true,
@@ -94,6 +95,8 @@ fn nbb_limits() -> ResourceLimits {
         max_cache_entries: 250_000,
         max_cached_moves: 3_000_000,
         max_runtime_steps: 500_000_000,
+        parallel_attractor: false,
+        parallel_trap: false,
     }
 }
 
@@ -133,6 +136,7 @@ pub fn nbb7_generated() -> Result<Scenario<BuiltinDomain, NoLaws, NoPreferences>
         domain: BuiltinDomain::All,
         laws: NoLaws,
         preferences: NoPreferences,
+        tie_break: TieBreak::Forwards,
         limits: nbb7_limits(),
         cache_mode: CacheMode::BlackOnly,
         remove_stalemates: true,
@@ -150,6 +154,8 @@ fn nbb7_limits() -> ResourceLimits {
         max_cache_entries: 250_000,
         max_cached_moves: 3_000_000,
         max_runtime_steps: 4_000_000_000,
+        parallel_attractor: false,
+        parallel_trap: false,
     }
 }
 
@@ -37,8 +37,9 @@ pub fn compute_bounded_counts<D, L, P>(
     scn: &Scenario<D, L, P>,
 ) -> Result<BoundedCounts, SearchError>
 where
-    D: DomainLike,
-    L: LawsLike,
+    D: DomainLike + Sync,
+    L: LawsLike + Sync,
+    P: Sync,
 {
     scn.validate()?;
 
@@ -57,8 +58,14 @@ where
     let mut tracker = ResourceTracker::new(scn.limits);
 
     // Universe placements.
+    //
+    // `insert`/`contains` below hash a `State` via its derived `Hash`, which for the `pos` field
+    // forwards to `Position`'s cached `zobrist()` value instead of rehashing every occupied
+    // square — so the two membership probes per state in the move-count scan further down stay
+    // O(1) regardless of piece count. `PartialEq` still compares the full `squares`/`count`
+    // fields, so a `u64` collision can't corrupt the set.
     let mut universe: FxHashSet<State> = FxHashSet::default();
-    try_for_each_state_in_abs_box(&scn.rules.layout, bound, allow_captures, |s| {
+    try_for_each_state_in_abs_box(&scn.rules, bound, allow_captures, |s| {
         if !scn.rules.is_legal_position(&s.pos) {
             return Ok(());
         }
@@ -112,7 +119,7 @@ where
 
     // Forced mate region (bounded-universe interpretation).
     // Passing is controlled by the scenario (typically disabled for mate).
-    let mate_region = forced_mate_bounded(scn, false)?;
+    let mate_region = forced_mate_bounded(scn, false, false)?;
 
     Ok(BoundedCounts {
         universe_states: universe.len(),
@@ -7,16 +7,62 @@
 //! White chooses a reply (including optional pass), and we only keep replies that
 //! stay inside the inescapable trap.
 
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::chess::layout::PieceLayout;
 use crate::scenario::{DomainLike, LawsLike, Scenario, SearchError, State};
-use crate::search::movegen::{legal_black_moves, legal_white_moves};
+use crate::search::movegen::{legal_black_moves_hashed, legal_white_moves_hashed};
 use crate::search::resources::ResourceTracker;
 
+/// Whether `wpos` (a white-to-move state) is a "tempo node": White can pass here, leaving `wpos`
+/// itself as the next black-to-move position, and that position is inside `btm_trap`. This is the
+/// acceptance condition the Büchi tempo-trap objective is built on, and the "free pass" a tempo
+/// strategy must hit infinitely often (see [`crate::search::strategy::verify_strategy_progress`]).
+pub(crate) fn is_tempo_node<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    btm_trap: &FxHashSet<State>,
+    wpos: &State,
+) -> bool
+where
+    L: LawsLike,
+{
+    scn.white_can_pass && scn.laws.allow_pass(wpos) && btm_trap.contains(wpos)
+}
+
+/// Insert `idx` into a Zobrist-hash-bucketed index; see [`bucket_lookup`] for why a bucket of
+/// indices rather than a single slot.
+fn bucket_insert(index: &mut FxHashMap<u64, Vec<usize>>, hash: u64, idx: usize) {
+    index.entry(hash).or_default().push(idx);
+}
+
+/// Look up `probe` in a Zobrist-hash-bucketed index of `list`.
+///
+/// `hash` is `probe`'s incrementally-maintained [`State::zobrist`] (threaded through by callers
+/// via [`legal_black_moves_hashed`]/[`legal_white_moves_hashed`] rather than recomputed from
+/// scratch per candidate), which narrows the search to a small same-hash bucket; a linear scan
+/// with a full `Eq` check then resolves any collision. Same hash-bucket-plus-fallback shape as
+/// [`crate::arena::graph::Arena`]'s `by_hash` index.
+fn bucket_lookup(
+    index: &FxHashMap<u64, Vec<usize>>,
+    list: &[State],
+    hash: u64,
+    probe: &State,
+) -> Option<usize> {
+    index
+        .get(&hash)?
+        .iter()
+        .copied()
+        .find(|&i| &list[i] == probe)
+}
+
 #[derive(Debug)]
 struct BuchiGraph {
     b_list: Vec<State>,
-    b_index: FxHashMap<State, usize>,
+    b_index: FxHashMap<u64, Vec<usize>>,
     w_list: Vec<State>,
     bw_succ: Vec<Vec<usize>>,
     wb_succ: Vec<Vec<usize>>,
@@ -53,7 +99,76 @@ where
     L: LawsLike,
 {
     let g = compute_winning_region(scn, btm_trap)?;
-    Ok((extract_b_set(&g), extract_tempo_strategy(&g)?))
+    Ok((
+        extract_b_set(&g),
+        extract_tempo_strategy(&g, &scn.rules.layout)?,
+    ))
+}
+
+/// Compute the maximal tempo trap, extract a memoryless White strategy, and build a concrete
+/// forced lasso from `start` witnessing it: see [`lasso_witness`] for what the returned line
+/// demonstrates.
+pub fn tempo_trap_lasso_witness<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    btm_trap: &FxHashSet<State>,
+    start: &State,
+) -> Result<Vec<State>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let g = compute_winning_region(scn, btm_trap)?;
+    let strategy = extract_tempo_strategy(&g, &scn.rules.layout)?;
+    lasso_witness(&g, &strategy, &scn.rules.layout, start)
+}
+
+/// A node in the black/white bipartite attractor worklist (see [`build_reverse_edges`]).
+#[derive(Debug, Clone, Copy)]
+enum AttrNode {
+    Black(usize),
+    White(usize),
+}
+
+/// Reverse edges restricted to the current subgame `Z`, shared by [`attractor_white`] and
+/// [`attractor_black`]: `black_preds_of_white[wi]` lists black indices `bi` with an edge `bi ->
+/// wi` (i.e. `wi` is one of `bw_succ[bi]`'s entries); `white_preds_of_black[bi]` lists white
+/// indices `wi` with an edge `wi -> bi`. Building both once per attractor call turns "does `bi`
+/// have a successor that just joined" from an O(out-degree) rescan of every node on every pass
+/// into an O(1) worklist step per edge overall.
+fn build_reverse_edges(
+    in_z_b: &[bool],
+    in_z_w: &[bool],
+    bw_succ: &[Vec<usize>],
+    wb_succ: &[Vec<usize>],
+) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let b_len = in_z_b.len();
+    let w_len = in_z_w.len();
+
+    let mut black_preds_of_white: Vec<Vec<usize>> = vec![Vec::new(); w_len];
+    for bi in 0..b_len {
+        if !in_z_b[bi] {
+            continue;
+        }
+        for &wi in &bw_succ[bi] {
+            if in_z_w[wi] {
+                black_preds_of_white[wi].push(bi);
+            }
+        }
+    }
+
+    let mut white_preds_of_black: Vec<Vec<usize>> = vec![Vec::new(); b_len];
+    for wi in 0..w_len {
+        if !in_z_w[wi] {
+            continue;
+        }
+        for &bi in &wb_succ[wi] {
+            if in_z_b[bi] {
+                white_preds_of_black[bi].push(wi);
+            }
+        }
+    }
+
+    (black_preds_of_white, white_preds_of_black)
 }
 
 /// Attractor to the accepting set for White.
@@ -64,6 +179,15 @@ where
 ///
 /// We intentionally require black nodes to have at least one successor inside Z;
 /// otherwise the play ends and cannot satisfy an "infinitely often" objective.
+///
+/// Linear-time worklist attractor: rather than rescanning every node on every fixpoint pass, each
+/// black node keeps a counter of its not-yet-resolved Z-successors (seeded once via
+/// [`build_reverse_edges`]), and a white node joining the attractor decrements exactly the
+/// counters of its own predecessors via the reverse-edge lists built above. A black node joins
+/// the moment its counter hits zero (which, since it started at its Z-out-degree, only happens
+/// for nodes with at least one Z-successor), and a white node joins the moment any predecessor
+/// edge tells it to. Every node and edge is processed once, giving O(V+E) instead of the previous
+/// O(V·E)-per-Zielonka-iteration rescan.
 fn attractor_white(
     in_z_b: &[bool],
     in_z_w: &[bool],
@@ -74,51 +198,51 @@ fn attractor_white(
     let b_len = in_z_b.len();
     let w_len = in_z_w.len();
 
+    let (black_preds_of_white, white_preds_of_black) =
+        build_reverse_edges(in_z_b, in_z_w, bw_succ, wb_succ);
+
     let mut in_a_b: Vec<bool> = vec![false; b_len];
     let mut in_a_w: Vec<bool> = vec![false; w_len];
 
+    // Black nodes are the "all successors in A" side here: seed each with its Z-out-degree so a
+    // predecessor edge firing can decrement straight to "is this the last one".
+    let mut rem_black: Vec<u32> = vec![0; b_len];
+    for bi in 0..b_len {
+        if in_z_b[bi] {
+            rem_black[bi] = bw_succ[bi].iter().filter(|&&wi| in_z_w[wi]).count() as u32;
+        }
+    }
+
+    let mut q: VecDeque<AttrNode> = VecDeque::new();
     for wi in 0..w_len {
         if in_z_w[wi] && is_accept_w[wi] {
             in_a_w[wi] = true;
+            q.push_back(AttrNode::White(wi));
         }
     }
 
-    let mut changed = true;
-    while changed {
-        changed = false;
-
-        // White nodes: exists succ in A.
-        for wi in 0..w_len {
-            if !in_z_w[wi] || in_a_w[wi] {
-                continue;
-            }
-            let has_edge = wb_succ[wi].iter().any(|&bi| in_z_b[bi] && in_a_b[bi]);
-            if has_edge {
-                in_a_w[wi] = true;
-                changed = true;
-            }
-        }
-
-        // Black nodes: all succ in A (and succ non-empty inside Z).
-        for bi in 0..b_len {
-            if !in_z_b[bi] || in_a_b[bi] {
-                continue;
-            }
-            let mut saw_succ_in_z = false;
-            let mut all_in_a = true;
-            for &wi in bw_succ[bi].iter() {
-                if !in_z_w[wi] {
-                    continue;
-                }
-                saw_succ_in_z = true;
-                if !in_a_w[wi] {
-                    all_in_a = false;
-                    break;
+    while let Some(node) = q.pop_front() {
+        match node {
+            AttrNode::White(wi) => {
+                for &bi in &black_preds_of_white[wi] {
+                    if in_a_b[bi] || rem_black[bi] == 0 {
+                        continue;
+                    }
+                    rem_black[bi] -= 1;
+                    if rem_black[bi] == 0 {
+                        in_a_b[bi] = true;
+                        q.push_back(AttrNode::Black(bi));
+                    }
                 }
             }
-            if saw_succ_in_z && all_in_a {
-                in_a_b[bi] = true;
-                changed = true;
+            AttrNode::Black(bi) => {
+                for &wi in &white_preds_of_black[bi] {
+                    if in_a_w[wi] {
+                        continue;
+                    }
+                    in_a_w[wi] = true;
+                    q.push_back(AttrNode::White(wi));
+                }
             }
         }
     }
@@ -197,6 +321,11 @@ fn attractor_white_with_witness(
 /// Player 2 = Black.
 /// - Black nodes join the attractor if they have *some* edge into it.
 /// - White nodes join if *all* their edges (within Z) go into it.
+///
+/// Same linear-time worklist shape as [`attractor_white`], with the "exists"/"all" roles swapped:
+/// here it's white nodes that carry the remaining-Z-successors counter, and the queue seeds from
+/// both `target_b` and `target_w` directly (unlike `attractor_white`, whose accepting set only
+/// ever names white nodes).
 fn attractor_black(
     in_z_b: &[bool],
     in_z_w: &[bool],
@@ -208,62 +337,204 @@ fn attractor_black(
     let b_len = in_z_b.len();
     let w_len = in_z_w.len();
 
+    let (black_preds_of_white, white_preds_of_black) =
+        build_reverse_edges(in_z_b, in_z_w, bw_succ, wb_succ);
+
     let mut in_a_b: Vec<bool> = vec![false; b_len];
     let mut in_a_w: Vec<bool> = vec![false; w_len];
 
-    // Seed with target.
+    // White nodes are the "all successors in A" side here.
+    let mut rem_white: Vec<u32> = vec![0; w_len];
+    for wi in 0..w_len {
+        if in_z_w[wi] {
+            rem_white[wi] = wb_succ[wi].iter().filter(|&&bi| in_z_b[bi]).count() as u32;
+        }
+    }
+
+    let mut q: VecDeque<AttrNode> = VecDeque::new();
     for bi in 0..b_len {
         if in_z_b[bi] && target_b[bi] {
             in_a_b[bi] = true;
+            q.push_back(AttrNode::Black(bi));
         }
     }
     for wi in 0..w_len {
         if in_z_w[wi] && target_w[wi] {
             in_a_w[wi] = true;
+            q.push_back(AttrNode::White(wi));
         }
     }
 
-    let mut changed = true;
-    while changed {
-        changed = false;
+    while let Some(node) = q.pop_front() {
+        match node {
+            AttrNode::Black(bi) => {
+                for &wi in &white_preds_of_black[bi] {
+                    if in_a_w[wi] || rem_white[wi] == 0 {
+                        continue;
+                    }
+                    rem_white[wi] -= 1;
+                    if rem_white[wi] == 0 {
+                        in_a_w[wi] = true;
+                        q.push_back(AttrNode::White(wi));
+                    }
+                }
+            }
+            AttrNode::White(wi) => {
+                for &bi in &black_preds_of_white[wi] {
+                    if in_a_b[bi] {
+                        continue;
+                    }
+                    in_a_b[bi] = true;
+                    q.push_back(AttrNode::Black(bi));
+                }
+            }
+        }
+    }
 
-        // Black nodes: exists succ in A.
-        for bi in 0..b_len {
-            if !in_z_b[bi] || in_a_b[bi] {
-                continue;
+    (in_a_b, in_a_w)
+}
+
+/// Parallel, round-based counterpart to [`attractor_white`], opt in via
+/// [`ResourceLimits::parallel_attractor`](crate::scenario::ResourceLimits). Each round evaluates
+/// the "exists/all successors in A" predicate for every node concurrently via rayon: every node
+/// only reads the previous round's `in_a_b`/`in_a_w` snapshot and writes its own `AtomicBool`, so
+/// there are no inter-node writes within a round and the scan stays race-free. This revisits every
+/// node on every round (the same work the worklist in `attractor_white` was built to avoid), so
+/// it's worth it only when the per-round parallel scan across many cores beats a single-threaded
+/// walk of the queue — i.e. on the largest `BuiltinDomain::Box` graphs. Semantics match
+/// `attractor_white` exactly, including the "black node needs at least one Z-successor" rule.
+fn attractor_white_parallel(
+    in_z_b: &[bool],
+    in_z_w: &[bool],
+    bw_succ: &[Vec<usize>],
+    wb_succ: &[Vec<usize>],
+    is_accept_w: &[bool],
+) -> (Vec<bool>, Vec<bool>) {
+    let b_len = in_z_b.len();
+    let w_len = in_z_w.len();
+
+    let in_a_b: Vec<AtomicBool> = (0..b_len).map(|_| AtomicBool::new(false)).collect();
+    let in_a_w: Vec<AtomicBool> = (0..w_len)
+        .map(|wi| AtomicBool::new(in_z_w[wi] && is_accept_w[wi]))
+        .collect();
+
+    loop {
+        let changed = AtomicBool::new(false);
+
+        (0..w_len).into_par_iter().for_each(|wi| {
+            if !in_z_w[wi] || in_a_w[wi].load(Ordering::Relaxed) {
+                return;
             }
-            let has_edge = bw_succ[bi].iter().any(|&wi| in_z_w[wi] && in_a_w[wi]);
+            let has_edge = wb_succ[wi]
+                .iter()
+                .any(|&bi| in_z_b[bi] && in_a_b[bi].load(Ordering::Relaxed));
             if has_edge {
-                in_a_b[bi] = true;
-                changed = true;
+                in_a_w[wi].store(true, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
+            }
+        });
+
+        (0..b_len).into_par_iter().for_each(|bi| {
+            if !in_z_b[bi] || in_a_b[bi].load(Ordering::Relaxed) {
+                return;
+            }
+            let mut saw_succ_in_z = false;
+            let mut all_in_a = true;
+            for &wi in &bw_succ[bi] {
+                if !in_z_w[wi] {
+                    continue;
+                }
+                saw_succ_in_z = true;
+                if !in_a_w[wi].load(Ordering::Relaxed) {
+                    all_in_a = false;
+                    break;
+                }
             }
+            if saw_succ_in_z && all_in_a {
+                in_a_b[bi].store(true, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
+            }
+        });
+
+        if !changed.load(Ordering::Relaxed) {
+            break;
         }
+    }
 
-        // White nodes: all succ in A (and succ non-empty inside Z).
-        for wi in 0..w_len {
-            if !in_z_w[wi] || in_a_w[wi] {
-                continue;
+    (
+        in_a_b.into_iter().map(AtomicBool::into_inner).collect(),
+        in_a_w.into_iter().map(AtomicBool::into_inner).collect(),
+    )
+}
+
+/// Parallel, round-based counterpart to [`attractor_black`]; see [`attractor_white_parallel`] for
+/// the rationale and race-freedom argument, which applies identically with the "exists"/"all"
+/// roles swapped.
+fn attractor_black_parallel(
+    in_z_b: &[bool],
+    in_z_w: &[bool],
+    bw_succ: &[Vec<usize>],
+    wb_succ: &[Vec<usize>],
+    target_b: &[bool],
+    target_w: &[bool],
+) -> (Vec<bool>, Vec<bool>) {
+    let b_len = in_z_b.len();
+    let w_len = in_z_w.len();
+
+    let in_a_b: Vec<AtomicBool> = (0..b_len)
+        .map(|bi| AtomicBool::new(in_z_b[bi] && target_b[bi]))
+        .collect();
+    let in_a_w: Vec<AtomicBool> = (0..w_len)
+        .map(|wi| AtomicBool::new(in_z_w[wi] && target_w[wi]))
+        .collect();
+
+    loop {
+        let changed = AtomicBool::new(false);
+
+        (0..b_len).into_par_iter().for_each(|bi| {
+            if !in_z_b[bi] || in_a_b[bi].load(Ordering::Relaxed) {
+                return;
+            }
+            let has_edge = bw_succ[bi]
+                .iter()
+                .any(|&wi| in_z_w[wi] && in_a_w[wi].load(Ordering::Relaxed));
+            if has_edge {
+                in_a_b[bi].store(true, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
+            }
+        });
+
+        (0..w_len).into_par_iter().for_each(|wi| {
+            if !in_z_w[wi] || in_a_w[wi].load(Ordering::Relaxed) {
+                return;
             }
             let mut saw_succ_in_z = false;
             let mut all_in_a = true;
-            for &bi in wb_succ[wi].iter() {
+            for &bi in &wb_succ[wi] {
                 if !in_z_b[bi] {
                     continue;
                 }
                 saw_succ_in_z = true;
-                if !in_a_b[bi] {
+                if !in_a_b[bi].load(Ordering::Relaxed) {
                     all_in_a = false;
                     break;
                 }
             }
             if saw_succ_in_z && all_in_a {
-                in_a_w[wi] = true;
-                changed = true;
+                in_a_w[wi].store(true, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
             }
+        });
+
+        if !changed.load(Ordering::Relaxed) {
+            break;
         }
     }
 
-    (in_a_b, in_a_w)
+    (
+        in_a_b.into_iter().map(AtomicBool::into_inner).collect(),
+        in_a_w.into_iter().map(AtomicBool::into_inner).collect(),
+    )
 }
 
 fn compute_winning_region<D, L, P>(
@@ -281,28 +552,29 @@ where
     tracker.bump_states("buchi_black_nodes", b_list.len())?;
 
     let b_len = b_list.len();
-    let mut b_index: FxHashMap<State, usize> = FxHashMap::default();
+    let mut b_index: FxHashMap<u64, Vec<usize>> = FxHashMap::default();
     tracker.try_reserve_map("buchi_black_index", "b_index", &mut b_index, b_len)?;
     for (i, p) in b_list.iter().enumerate() {
-        b_index.insert(p.clone(), i);
+        bucket_insert(&mut b_index, p.zobrist(&scn.rules.layout), i);
     }
 
     // Discover white nodes and black->white edges.
     let mut w_list: Vec<State> = Vec::new();
-    let mut w_index: FxHashMap<State, usize> = FxHashMap::default();
+    let mut w_index: FxHashMap<u64, Vec<usize>> = FxHashMap::default();
     let mut bw_succ: Vec<Vec<usize>> = vec![Vec::new(); b_len];
 
     for (bi, bpos) in b_list.iter().enumerate() {
         tracker.bump_steps("buchi_build_bw", 1)?;
 
+        let bhash = bpos.zobrist(&scn.rules.layout);
         let mut succ_w: Vec<usize> = Vec::new();
-        for wpos in legal_black_moves(scn, &scn.laws, bpos, &mut tracker)? {
-            let wi = if let Some(&existing) = w_index.get(&wpos) {
+        for (wpos, whash) in legal_black_moves_hashed(scn, &scn.laws, bpos, bhash, &mut tracker)? {
+            let wi = if let Some(existing) = bucket_lookup(&w_index, &w_list, whash, &wpos) {
                 existing
             } else {
                 let idx = w_list.len();
-                w_list.push(wpos.clone());
-                w_index.insert(wpos.clone(), idx);
+                w_list.push(wpos);
+                bucket_insert(&mut w_index, whash, idx);
                 tracker.bump_states("buchi_white_nodes", 1)?;
                 idx
             };
@@ -320,9 +592,10 @@ where
     for (wi, wpos) in w_list.iter().enumerate() {
         tracker.bump_steps("buchi_build_wb", 1)?;
 
+        let whash = wpos.zobrist(&scn.rules.layout);
         let mut succ_b: Vec<usize> = Vec::new();
-        for bnext in legal_white_moves(scn, &scn.laws, wpos, &mut tracker)? {
-            if let Some(&bi) = b_index.get(&bnext) {
+        for (bnext, bhash) in legal_white_moves_hashed(scn, &scn.laws, wpos, whash, &mut tracker)? {
+            if let Some(bi) = bucket_lookup(&b_index, &b_list, bhash, &bnext) {
                 succ_b.push(bi);
             }
         }
@@ -334,7 +607,7 @@ where
     // Acceptance set F: white nodes where passing is possible, i.e. the placement itself is in btm_trap.
     let mut is_accept_w: Vec<bool> = vec![false; w_len];
     for (wi, wpos) in w_list.iter().enumerate() {
-        if scn.white_can_pass && scn.laws.allow_pass(wpos) && b_index.contains_key(wpos) {
+        if is_tempo_node(scn, btm_trap, wpos) {
             is_accept_w[wi] = true;
         }
     }
@@ -347,7 +620,11 @@ where
         tracker.bump_steps("buchi_iter", 1)?;
 
         // Y = Attr_white(F) within Z.
-        let (in_y_b, in_y_w) = attractor_white(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &is_accept_w);
+        let (in_y_b, in_y_w) = if scn.limits.parallel_attractor {
+            attractor_white_parallel(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &is_accept_w)
+        } else {
+            attractor_white(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &is_accept_w)
+        };
 
         // Target for black attractor is Z \ Y.
         let mut target_b: Vec<bool> = vec![false; b_len];
@@ -363,8 +640,11 @@ where
             }
         }
 
-        let (in_x_b, in_x_w) =
-            attractor_black(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &target_b, &target_w);
+        let (in_x_b, in_x_w) = if scn.limits.parallel_attractor {
+            attractor_black_parallel(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &target_b, &target_w)
+        } else {
+            attractor_black(&in_z_b, &in_z_w, &bw_succ, &wb_succ, &target_b, &target_w)
+        };
 
         let mut any_removed = false;
         for i in 0..b_len {
@@ -407,7 +687,10 @@ fn extract_b_set(g: &BuchiGraph) -> FxHashSet<State> {
     out
 }
 
-fn extract_tempo_strategy(g: &BuchiGraph) -> Result<FxHashMap<State, State>, SearchError> {
+fn extract_tempo_strategy(
+    g: &BuchiGraph,
+    layout: &PieceLayout,
+) -> Result<FxHashMap<State, State>, SearchError> {
     let b_len = g.b_list.len();
     let w_len = g.w_list.len();
 
@@ -438,7 +721,10 @@ fn extract_tempo_strategy(g: &BuchiGraph) -> Result<FxHashMap<State, State>, Sea
 
         let chosen_bi = if g.is_accept_w[wi] {
             // Prefer pass if it stays in the winning region.
-            if let Some(&pass_bi) = g.b_index.get(&g.w_list[wi]) {
+            let wstate = &g.w_list[wi];
+            if let Some(pass_bi) =
+                bucket_lookup(&g.b_index, &g.b_list, wstate.zobrist(layout), wstate)
+            {
                 if pass_bi < b_len && g.in_z_b[pass_bi] {
                     pass_bi
                 } else {
@@ -460,3 +746,219 @@ fn extract_tempo_strategy(g: &BuchiGraph) -> Result<FxHashMap<State, State>, Sea
 
     Ok(out)
 }
+
+/// Build a replayable forced lasso witnessing that `start`'s tempo trap keeps revisiting an
+/// accepting ("free pass") placement infinitely often.
+///
+/// Walks the strategy-restricted subgraph from `start`: Black always takes its lowest-index
+/// in-region successor (any deterministic tie-break demonstrates the claim equally well; the
+/// choice doesn't need verifying, since `g.in_z_w` already proves every in-region successor wins
+/// for White) and White always replies via `strategy` (see [`extract_tempo_strategy`]). With both
+/// sides deterministic this is a single walk through a functional graph of black-to-move states,
+/// so recording each node's position along the walk finds the first repeated state directly,
+/// rather than needing a general DFS with an explicit stack. That repeat closes the (only) cycle
+/// reachable from `start`. Returns the finite prefix up to the cycle's entry point followed by the
+/// cycle itself, as an alternating sequence of black/white states ending back on the entry point.
+///
+/// Errors if `start` is outside the computed winning region. Otherwise it errors only if the walk
+/// finds an inconsistency, chiefly a cycle that doesn't pass through an accepting white node, which cannot happen for
+/// a correctly computed nonempty winning region — this is a self-check on
+/// [`compute_winning_region`]/[`extract_tempo_strategy`], not an expected outcome.
+fn lasso_witness(
+    g: &BuchiGraph,
+    strategy: &FxHashMap<State, State>,
+    layout: &PieceLayout,
+    start: &State,
+) -> Result<Vec<State>, SearchError> {
+    let start_bi = bucket_lookup(&g.b_index, &g.b_list, start.zobrist(layout), start)
+        .filter(|&bi| g.in_z_b[bi])
+        .ok_or_else(|| SearchError::InvalidScenario {
+            reason: "lasso witness requested from a state outside the computed winning region"
+                .to_string(),
+        })?;
+
+    // path[k] = (black index, chosen white index) taken on step k of the walk.
+    let mut path: Vec<(usize, usize)> = Vec::new();
+    let mut seen: FxHashMap<usize, usize> = FxHashMap::default();
+
+    let mut bi = start_bi;
+    let cycle_start = loop {
+        if let Some(&k) = seen.get(&bi) {
+            break k;
+        }
+        seen.insert(bi, path.len());
+
+        let wi = g.bw_succ[bi]
+            .iter()
+            .copied()
+            .filter(|&wi| g.in_z_w[wi])
+            .min()
+            .ok_or_else(|| SearchError::InvalidScenario {
+                reason: "lasso witness found a black node with no in-region successor".to_string(),
+            })?;
+
+        let next_state =
+            strategy
+                .get(&g.w_list[wi])
+                .ok_or_else(|| SearchError::InvalidScenario {
+                    reason: "lasso witness found a white node missing from the extracted strategy"
+                        .to_string(),
+                })?;
+        let next_bi = bucket_lookup(
+            &g.b_index,
+            &g.b_list,
+            next_state.zobrist(layout),
+            next_state,
+        )
+        .ok_or_else(|| SearchError::InvalidScenario {
+            reason: "lasso witness strategy stepped to a state outside the black node index"
+                .to_string(),
+        })?;
+
+        path.push((bi, wi));
+        bi = next_bi;
+    };
+
+    let has_accept = path[cycle_start..].iter().any(|&(_, wi)| g.is_accept_w[wi]);
+    if !has_accept {
+        return Err(SearchError::InvalidScenario {
+            reason:
+                "lasso witness's only reachable cycle does not pass through an accepting white node"
+                    .to_string(),
+        });
+    }
+
+    let mut out: Vec<State> = Vec::with_capacity(path.len() * 2 + 1);
+    for &(bi, wi) in &path {
+        out.push(g.b_list[bi].clone());
+        out.push(g.w_list[wi].clone());
+    }
+    out.push(g.b_list[bi].clone());
+    Ok(out)
+}
+
+/// The long-run fate of a black-to-move state under the tempo-trap winning region (see
+/// [`compute_winning_region`]) and White's extracted memoryless strategy within it (see
+/// [`extract_tempo_strategy`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Removed by the black attractor: Black can force an exit from the winning subgame no matter
+    /// what White plays, so White cannot hold this position in `btm_trap` forever.
+    WhiteEscapes,
+    /// In the winning subgame, and the accepting ("free pass") placement recurs on the eventual
+    /// strategy-consistent cycle reachable from this state: a real game from here repeats a
+    /// position forever rather than settling into a strict, non-repeating confinement.
+    DrawByRepetition,
+    /// In the winning subgame, but the eventual strategy-consistent cycle reachable from this
+    /// state never revisits an accepting placement. Büchi correctness rules this out for a
+    /// correctly computed nonempty winning region (every winning cycle must pass through the
+    /// accepting set infinitely often), so this variant should be unreachable in practice; it's
+    /// kept as a label rather than an error so one malformed node can't fail classification for
+    /// the rest of the trap.
+    TrappedForever,
+}
+
+/// Classify every state in `btm_trap` by its long-run [`Outcome`] under the tempo-trap winning
+/// region and White's memoryless strategy within it.
+pub fn classify_tempo_trap<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    btm_trap: &FxHashSet<State>,
+) -> Result<FxHashMap<State, Outcome>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let g = compute_winning_region(scn, btm_trap)?;
+    let strategy = extract_tempo_strategy(&g, &scn.rules.layout)?;
+    let hits_accept = cycle_hits_accept_labels(&g, &strategy, &scn.rules.layout)?;
+
+    let mut out: FxHashMap<State, Outcome> = FxHashMap::default();
+    for (bi, s) in g.b_list.iter().enumerate() {
+        let outcome = if !g.in_z_b[bi] {
+            Outcome::WhiteEscapes
+        } else if hits_accept[bi] {
+            Outcome::DrawByRepetition
+        } else {
+            Outcome::TrappedForever
+        };
+        out.insert(s.clone(), outcome);
+    }
+    Ok(out)
+}
+
+/// For every black index inside the winning subgame, whether the eventual cycle of the
+/// strategy-restricted walk from it (the same deterministic walk [`lasso_witness`] takes from a
+/// single starting state) passes through an accepting white node.
+///
+/// Labels every in-region node in one linear pass: the walk from any node follows a functional
+/// graph (Black takes its lowest-index in-region successor, White follows `strategy`), so it
+/// either reaches an already-labeled node or closes a fresh cycle. Either way, every node on the
+/// just-walked path gets the label the walk resolved to — nodes in the cycle share it trivially,
+/// and nodes in the prefix leading into the cycle inherit it too, since "revisited infinitely
+/// often" is a property of the eventual cycle, not the transient prefix that reaches it.
+fn cycle_hits_accept_labels(
+    g: &BuchiGraph,
+    strategy: &FxHashMap<State, State>,
+    layout: &PieceLayout,
+) -> Result<Vec<bool>, SearchError> {
+    let b_len = g.b_list.len();
+    let mut label: Vec<Option<bool>> = vec![None; b_len];
+    let mut pos_in_path: Vec<Option<usize>> = vec![None; b_len];
+
+    for start in 0..b_len {
+        if !g.in_z_b[start] || label[start].is_some() {
+            continue;
+        }
+
+        let mut path: Vec<(usize, usize)> = Vec::new();
+        let mut bi = start;
+        let resolved = loop {
+            if let Some(hits) = label[bi] {
+                break hits;
+            }
+            if let Some(entry) = pos_in_path[bi] {
+                break path[entry..].iter().any(|&(_, wi)| g.is_accept_w[wi]);
+            }
+
+            pos_in_path[bi] = Some(path.len());
+
+            let wi = g.bw_succ[bi]
+                .iter()
+                .copied()
+                .filter(|&wi| g.in_z_w[wi])
+                .min()
+                .ok_or_else(|| SearchError::InvalidScenario {
+                    reason:
+                        "tempo trap classification found a black node with no in-region successor"
+                            .to_string(),
+                })?;
+            let next_state =
+                strategy
+                    .get(&g.w_list[wi])
+                    .ok_or_else(|| SearchError::InvalidScenario {
+                        reason:
+                            "tempo trap classification found a white node missing from the extracted strategy"
+                                .to_string(),
+                    })?;
+            let next_bi = bucket_lookup(
+                &g.b_index,
+                &g.b_list,
+                next_state.zobrist(layout),
+                next_state,
+            )
+            .ok_or_else(|| SearchError::InvalidScenario {
+                reason: "tempo trap classification strategy stepped to a state outside the black node index"
+                    .to_string(),
+            })?;
+
+            path.push((bi, wi));
+            bi = next_bi;
+        };
+
+        for &(pbi, _) in &path {
+            label[pbi] = Some(resolved);
+        }
+    }
+
+    Ok(label.into_iter().map(|l| l.unwrap_or(false)).collect())
+}
@@ -0,0 +1,172 @@
+//! Unified win/draw classification over a bounded `InAbsBox` universe.
+//!
+//! [`crate::search::forced_mate::forced_mate_bounded`] (reachability: White mates) and
+//! [`crate::search::trap::maximal_inescapable_trap`] (safety: Black is confined) answer two
+//! halves of the same question over two different candidate-generation shapes, each returning a
+//! raw state set. `classify_universe` instead runs the mate search and a trap fixed point scoped
+//! to the *same* `InAbsBox` universe, and tags every black-to-move placement with a single
+//! coherent [`Outcome`] plus, for every drawn-by-fortress state, a checkable witness.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::scenario::{CandidateGeneration, DomainLike, LawsLike, Scenario, SearchError, State};
+use crate::search::forced_mate::forced_mate_bounded;
+use crate::search::movegen::{legal_black_moves, legal_white_moves};
+use crate::search::resources::ResourceTracker;
+use crate::search::universe::try_for_each_state_in_abs_box;
+
+/// Why a black-to-move state is not in the forced-mate region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawKind {
+    /// Black has a move after which no white reply stays in the fortress set or the forced-mate
+    /// region, so Black can force play out of the universe or the domain rather than surviving
+    /// forever.
+    Escapes,
+    /// Black survives forever: play stays in this set under optimal confinement by White (a
+    /// greatest fixed point over the trap objective, scoped to the non-winning states).
+    Fortress,
+}
+
+/// The game-theoretic outcome of a black-to-move state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// White can force mate. `dtm` is the distance to mate in plies, present iff the caller's
+    /// solver computed it.
+    Win { dtm: Option<u32> },
+    /// White cannot force mate; see [`DrawKind`] for why.
+    Draw { kind: DrawKind },
+}
+
+/// A single coherent verdict over every black-to-move state in an `InAbsBox` universe.
+#[derive(Debug, Clone)]
+pub struct UniverseVerdict {
+    pub outcomes: FxHashMap<State, Outcome>,
+    /// For every white-to-move state reachable from a `Fortress` state by one black move, the
+    /// white reply that keeps play inside the fortress forever. Checkable by replaying: from any
+    /// `Fortress` state, any black move either lands on a key of this map, whose mapped reply is
+    /// itself `Fortress` (or, at depth 0, a fortress state with no legal black move), or gives White
+    /// a reply into the forced-mate region.
+    pub fortress_strategy: FxHashMap<State, State>,
+}
+
+/// Classify every black-to-move state in `scn`'s `InAbsBox` universe as a forced mate (with
+/// distance) or a draw (escape vs. fortress), with a certificate for the fortress region.
+pub fn classify_universe<D, L, P>(scn: &Scenario<D, L, P>) -> Result<UniverseVerdict, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    scn.validate()?;
+
+    let (bound, allow_captures) = match scn.candidates {
+        CandidateGeneration::InAbsBox {
+            bound,
+            allow_captures,
+        } => (bound, allow_captures),
+        _ => {
+            return Err(SearchError::InvalidScenario {
+                reason: "classify_universe currently requires candidates=InAbsBox".to_string(),
+            })
+        }
+    };
+
+    let mate = forced_mate_bounded(scn, true, false)?;
+    let dtm = mate.dtm.unwrap_or_default();
+
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    // Re-enumerate the same black-to-move placements forced_mate_bounded solved over.
+    let mut universe: Vec<State> = Vec::new();
+    try_for_each_state_in_abs_box(&scn.rules, bound, allow_captures, |s| {
+        if !scn.rules.is_legal_position(&s.pos) {
+            return Ok(());
+        }
+        if !scn.laws.allow_state(&s) {
+            return Ok(());
+        }
+        if !scn.domain.inside(&s) {
+            return Ok(());
+        }
+        tracker.bump_states("classify_universe", 1)?;
+        universe.push(s);
+        Ok(())
+    })?;
+
+    // The draw region is everything outside the mate solver's winning set. Run the same
+    // greatest-fixed-point pruning as `trap::maximal_inescapable_trap`, but scoped to it: a state
+    // survives iff every black move has a white reply that stays inside the current set or lands
+    // in the forced-mate region (a black move into a lost line is not an escape).
+    let mut candidates: FxHashSet<State> = universe
+        .iter()
+        .filter(|s| !mate.winning_btm.contains(*s))
+        .cloned()
+        .collect();
+
+    loop {
+        tracker.bump_steps("classify_fortress_iter", 1)?;
+
+        let mut to_remove: Vec<State> = Vec::new();
+        for p in candidates.iter() {
+            let black_moves = legal_black_moves(scn, &scn.laws, p, &mut tracker)?;
+            let fails = black_moves.iter().any(|after_black| {
+                legal_white_moves(scn, &scn.laws, after_black, &mut tracker)
+                    .map(|replies| {
+                        !replies
+                            .iter()
+                            .any(|q| candidates.contains(q) || mate.winning_btm.contains(q))
+                    })
+                    .unwrap_or(true)
+            });
+            if fails {
+                to_remove.push(p.clone());
+            }
+        }
+
+        if to_remove.is_empty() {
+            break;
+        }
+        for p in to_remove {
+            candidates.remove(&p);
+        }
+    }
+
+    // Extract a concrete defensive reply for every white-to-move state reachable from a fortress
+    // state, so the fortress claim is checkable rather than just asserted.
+    let mut fortress_strategy: FxHashMap<State, State> = FxHashMap::default();
+    for p in candidates.iter() {
+        for after_black in legal_black_moves(scn, &scn.laws, p, &mut tracker)? {
+            if fortress_strategy.contains_key(&after_black) {
+                continue;
+            }
+            let reply = legal_white_moves(scn, &scn.laws, &after_black, &mut tracker)?
+                .into_iter()
+                .find(|q| candidates.contains(q));
+            if let Some(reply) = reply {
+                fortress_strategy.insert(after_black, reply);
+            }
+        }
+    }
+
+    let mut outcomes: FxHashMap<State, Outcome> = FxHashMap::default();
+    for s in universe {
+        let outcome = if let Some(&d) = dtm.get(&s) {
+            Outcome::Win { dtm: Some(d) }
+        } else if mate.winning_btm.contains(&s) {
+            Outcome::Win { dtm: None }
+        } else if candidates.contains(&s) {
+            Outcome::Draw {
+                kind: DrawKind::Fortress,
+            }
+        } else {
+            Outcome::Draw {
+                kind: DrawKind::Escapes,
+            }
+        };
+        outcomes.insert(s, outcome);
+    }
+
+    Ok(UniverseVerdict {
+        outcomes,
+        fortress_strategy,
+    })
+}
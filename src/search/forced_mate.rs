@@ -13,9 +13,10 @@ use std::collections::VecDeque;
 
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::chess::bitboard::BoxAttackTables;
 use crate::core::coord::Coord;
 use crate::scenario::{CandidateGeneration, DomainLike, LawsLike, Scenario, SearchError, State};
-use crate::search::movegen::{legal_black_moves, legal_white_moves};
+use crate::search::movegen::{for_each_legal_black_move, for_each_legal_white_move};
 use crate::search::resources::ResourceTracker;
 use crate::search::universe::try_for_each_state_in_abs_box;
 
@@ -31,9 +32,16 @@ pub struct ForcedMateResult {
 ///
 /// This routine currently requires `CandidateGeneration::InAbsBox` so that "leaving the universe"
 /// (e.g. walking beyond the absolute bound) is observable.
+///
+/// When `symmetry_reduction` is set and `scn.rules.layout.is_direction_free()` holds, states are
+/// folded under the 8 D4 board symmetries before being admitted to the universe, collapsing
+/// symmetric black-to-move placements into one node (up to 8× fewer states) without changing the
+/// result, since the mate/trap predicates are symmetry-invariant. Direction-dependent layouts
+/// (e.g. pawns) silently ignore this flag.
 pub fn forced_mate_bounded<D, L, P>(
     scn: &Scenario<D, L, P>,
     compute_dtm: bool,
+    symmetry_reduction: bool,
 ) -> Result<ForcedMateResult, SearchError>
 where
     D: DomainLike,
@@ -53,11 +61,16 @@ where
         }
     };
 
+    let fold_symmetry = symmetry_reduction && scn.rules.layout.is_direction_free();
+
     let mut tracker = ResourceTracker::new(scn.limits);
 
     // Build universe placements.
     let mut universe: FxHashSet<State> = FxHashSet::default();
-    try_for_each_state_in_abs_box(&scn.rules.layout, bound, allow_captures, |s| {
+    try_for_each_state_in_abs_box(&scn.rules, bound, allow_captures, |mut s| {
+        if fold_symmetry {
+            s.pos.canonicalize_d4(&scn.rules.layout);
+        }
         if !scn.rules.is_legal_position(&s.pos) {
             return Ok(());
         }
@@ -93,23 +106,45 @@ where
         tracker.bump_steps("mate_build_edges", 1)?;
 
         let mut b_out: Vec<usize> = Vec::with_capacity(8);
-        for wpos in legal_black_moves(scn, &scn.laws, p, &mut tracker)? {
-            if let Some(&j) = idx.get(&wpos) {
+        let mut has_escape = false;
+        for_each_legal_black_move(scn, &scn.laws, p, &mut tracker, |_delta, wpos| {
+            // `wpos` comes straight out of move generation, not through the `fold_symmetry`
+            // folding every `universe` member went through on admission above — without
+            // re-folding it here, the lookup below would miss on its own distinct (but
+            // symmetry-equivalent) form and every move would look like an escape.
+            let mut folded;
+            let key = if fold_symmetry {
+                folded = wpos.clone();
+                folded.pos.canonicalize_d4(&scn.rules.layout);
+                &folded
+            } else {
+                wpos
+            };
+            if let Some(&j) = idx.get(key) {
                 b_out.push(j);
             } else {
-                black_has_escape[i] = true;
+                has_escape = true;
             }
-        }
+        })?;
         b_out.sort_unstable();
         b_out.dedup();
         bw_succ[i] = b_out;
+        black_has_escape[i] = has_escape;
 
         let mut w_out: Vec<usize> = Vec::new();
-        for bpos in legal_white_moves(scn, &scn.laws, p, &mut tracker)? {
-            if let Some(&j) = idx.get(&bpos) {
+        for_each_legal_white_move(scn, &scn.laws, p, &mut tracker, |bpos| {
+            let mut folded;
+            let key = if fold_symmetry {
+                folded = bpos.clone();
+                folded.pos.canonicalize_d4(&scn.rules.layout);
+                &folded
+            } else {
+                bpos
+            };
+            if let Some(&j) = idx.get(key) {
                 w_out.push(j);
             }
-        }
+        })?;
         w_out.sort_unstable();
         w_out.dedup();
         wb_succ[i] = w_out;
@@ -147,12 +182,19 @@ where
         remaining_nonwin_w_succ[bi] = bw_succ[bi].len() + if black_has_escape[bi] { 1 } else { 0 };
     }
 
+    // Attack checks dominate this scan once the universe is large; the black king and every piece
+    // lie inside the absolute `bound` box, so every king-relative piece coordinate is within
+    // `2 * bound` and tables of that size cover every placement.
+    let mut attack_tables = BoxAttackTables::new(2 * bound);
     let mut q: VecDeque<Node> = VecDeque::new();
     for bi in 0..n {
         if black_has_escape[bi] || !bw_succ[bi].is_empty() {
             continue;
         }
-        if scn.rules.is_attacked(Coord::ORIGIN, &placements[bi].pos) {
+        if scn
+            .rules
+            .is_attacked_boxed(&mut attack_tables, Coord::ORIGIN, &placements[bi].pos)
+        {
             is_mate[bi] = true;
             win_b[bi] = true;
             q.push_back(Node::Black(bi));
@@ -204,7 +246,8 @@ where
             &mut tracker,
             &placements,
             &bw_succ,
-            &wb_succ,
+            &pred_w_of_b,
+            &pred_b_of_w,
             &win_b,
             &win_w,
             &is_mate,
@@ -222,12 +265,22 @@ enum Node {
     White(usize),
 }
 
+/// Compute exact distance-to-mate via a single backward BFS pass in nondecreasing DTM order
+/// (classic retrograde analysis, as used to build endgame tablebases).
+///
+/// Seed the queue with every mate node at `dtm_b=0`. Popping a black node relaxes its white
+/// predecessors (white minimizes, so the first time a white node is reached is final); popping a
+/// white node decrements a remaining-successor counter on its black predecessors (black maximizes,
+/// so the node only finalizes once every reply has been accounted for, and — because successors are
+/// popped in nondecreasing order — the last one processed is exactly the max). Each node and each
+/// edge is visited once, giving O(V+E) instead of the O(passes·n) cost of repeated relaxation.
 fn compute_dtm_layers<D, L, P>(
     scn: &Scenario<D, L, P>,
     tracker: &mut ResourceTracker,
     placements: &[State],
     bw_succ: &[Vec<usize>],
-    wb_succ: &[Vec<usize>],
+    pred_w_of_b: &[Vec<usize>],
+    pred_b_of_w: &[Vec<usize>],
     win_b: &[bool],
     win_w: &[bool],
     is_mate: &[bool],
@@ -242,84 +295,57 @@ where
     let mut dtm_b: Vec<u32> = vec![inf; n];
     let mut dtm_w: Vec<u32> = vec![inf; n];
 
+    // Remaining non-winning-or-unfinalized white successors for each black node, mirroring the
+    // attractor counter used above: it only hits zero once every white reply is finalized.
+    let mut remaining_w_succ: Vec<usize> = vec![0; n];
+    for bi in 0..n {
+        remaining_w_succ[bi] = bw_succ[bi].len();
+    }
+
+    let mut q: VecDeque<DtmNode> = VecDeque::new();
     for bi in 0..n {
         if win_b[bi] && is_mate[bi] {
             dtm_b[bi] = 0;
+            q.push_back(DtmNode::Black(bi));
         }
     }
 
-    loop {
-        tracker.bump_steps("mate_dtm_iter", 1)?;
-
-        let mut changed = false;
+    while let Some(node) = q.pop_front() {
+        tracker.bump_steps("mate_dtm_bfs", 1)?;
 
-        // White nodes: 1 + min successor dtm_b.
-        for wi in 0..n {
-            if !win_w[wi] {
-                continue;
-            }
-            let mut best = inf;
-            for &bi in wb_succ[wi].iter() {
-                if !win_b[bi] {
-                    continue;
+        match node {
+            DtmNode::Black(bi) => {
+                let d = dtm_b[bi];
+                for &wi in pred_w_of_b[bi].iter() {
+                    // Only winning white predecessors participate in the DTM graph.
+                    if !win_w[wi] || dtm_w[wi] != inf {
+                        continue;
+                    }
+                    dtm_w[wi] = d.saturating_add(1);
+                    q.push_back(DtmNode::White(wi));
                 }
-                best = best.min(dtm_b[bi]);
-            }
-            let cand = if best == inf {
-                inf
-            } else {
-                best.saturating_add(1)
-            };
-            if cand < dtm_w[wi] {
-                dtm_w[wi] = cand;
-                changed = true;
             }
-        }
-
-        // Black nodes: 1 + max successor dtm_w.
-        for bi in 0..n {
-            if !win_b[bi] || is_mate[bi] {
-                continue;
-            }
-
-            // Winning non-mate black nodes must have at least one in-universe move.
-            if bw_succ[bi].is_empty() {
-                return Err(SearchError::InvalidScenario {
-                    reason: "DTM requested but found a winning non-mate black node with no moves"
-                        .to_string(),
-                });
-            }
-
-            let mut max_v = 0u32;
-            for &wi in bw_succ[bi].iter() {
-                if !win_w[wi] {
-                    // Should not happen inside winning region.
-                    return Err(SearchError::InvalidScenario {
-                        reason: "DTM requested but winning black node has non-winning successor"
-                            .to_string(),
-                    });
-                }
-                let v = dtm_w[wi];
-                if v == inf {
-                    max_v = inf;
-                    break;
+            DtmNode::White(wi) => {
+                let d = dtm_w[wi];
+                for &bi in pred_b_of_w[wi].iter() {
+                    if !win_b[bi] || is_mate[bi] || dtm_b[bi] != inf {
+                        continue;
+                    }
+                    if remaining_w_succ[bi] == 0 {
+                        return Err(SearchError::InvalidScenario {
+                            reason: "DTM requested but found a winning non-mate black node with \
+                                     no moves"
+                                .to_string(),
+                        });
+                    }
+                    remaining_w_succ[bi] -= 1;
+                    if remaining_w_succ[bi] == 0 {
+                        dtm_b[bi] = d.saturating_add(1);
+                        q.push_back(DtmNode::Black(bi));
+                    }
                 }
-                max_v = max_v.max(v);
-            }
-            let cand = if max_v == inf {
-                inf
-            } else {
-                max_v.saturating_add(1)
-            };
-            if cand < dtm_b[bi] {
-                dtm_b[bi] = cand;
-                changed = true;
             }
         }
-
-        if !changed {
-            break;
-        }
     }
 
     // Extract winning black nodes.
@@ -351,3 +377,9 @@ where
 
     Ok(out)
 }
+
+#[derive(Debug, Clone, Copy)]
+enum DtmNode {
+    Black(usize),
+    White(usize),
+}
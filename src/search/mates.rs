@@ -3,24 +3,49 @@
 //! These helpers enumerate positions within an L∞ bound, but always apply **infinite-board**
 //! legality: the slice boundary is never treated as a wall for black king movement.
 
+use crate::chess::bitboard::BoxAttackTables;
 use crate::chess::bounds::enumerate_positions_in_bound;
 use crate::chess::rules::Rules;
+use crate::core::coord::Coord;
 
 /// Count checkmates (black king in check + no legal black moves) among positions
 /// where all non-captured pieces lie within the given L∞ bound.
 ///
 /// This uses **true infinite-board** mate logic: it does *not* treat the slice edge
 /// as a wall. If black has a legal move, it's not mate.
+///
+/// Every placement this enumerates is confined to `bound`, so the in-check test reuses one
+/// [`BoxAttackTables`] across the whole scan instead of each position rescanning every piece via
+/// [`Rules::is_attacked`] — the same trick [`crate::search::forced_mate::forced_mate_bounded`]
+/// uses for its mate-terminal scan.
 pub fn count_checkmates_in_bound(rules: &Rules, bound: i32) -> usize {
     let positions = enumerate_positions_in_bound(&rules.layout, bound, false);
-    positions.iter().filter(|p| rules.is_checkmate(p)).count()
+    let mut tables = BoxAttackTables::new(bound);
+    positions
+        .iter()
+        .filter(|p| is_checkmate_boxed(rules, &mut tables, p))
+        .count()
 }
 
-/// Enumerate all checkmates within the bound.
+/// Enumerate all checkmates within the bound. See [`count_checkmates_in_bound`].
 pub fn checkmates_in_bound(rules: &Rules, bound: i32) -> Vec<crate::core::position::Position> {
     let positions = enumerate_positions_in_bound(&rules.layout, bound, false);
+    let mut tables = BoxAttackTables::new(bound);
     positions
         .into_iter()
-        .filter(|p| rules.is_checkmate(p))
+        .filter(|p| is_checkmate_boxed(rules, &mut tables, p))
         .collect()
 }
+
+/// Bitboard-accelerated equivalent of [`Rules::is_checkmate`], for positions known to lie inside
+/// `tables`' box.
+fn is_checkmate_boxed(
+    rules: &Rules,
+    tables: &mut BoxAttackTables,
+    pos: &crate::core::position::Position,
+) -> bool {
+    if !rules.is_attacked_boxed(tables, Coord::ORIGIN, pos) {
+        return false;
+    }
+    rules.black_moves(pos).is_empty()
+}
@@ -7,8 +7,13 @@
 //! - All heavy routines use [`resources::ResourceTracker`] and return `Result<_, crate::scenario::SearchError>`.
 
 pub mod buchi;
+pub mod classify;
+pub mod forced_mate;
 pub mod mates;
 pub mod movegen;
+pub mod negamax;
 pub mod resources;
 pub mod strategy;
+pub mod tablebase;
 pub mod trap;
+pub mod universe;
@@ -1,3 +1,5 @@
+use crate::core::coord::Coord;
+use crate::core::zobrist;
 use crate::scenario::{DomainLike, LawsLike, Scenario, SearchError, State};
 use crate::search::resources::ResourceTracker;
 
@@ -73,6 +75,338 @@ where
     Ok(out)
 }
 
+/// Like [`legal_black_moves`], but calls `f` once per legal successor instead of collecting them
+/// into a `Vec`: walks a single [`crate::core::position::Position`] in place via
+/// [`crate::chess::rules::Rules::for_each_black_move`] (itself built on `Rules::apply`/
+/// `Rules::undo`). Each candidate is still cloned and canonicalized into a `State` before Laws
+/// filtering runs, but no successor `Vec` is built. Useful for callers that build a whole move graph (e.g.
+/// [`crate::search::forced_mate::forced_mate_bounded`]'s edge construction) and only need a
+/// transient reference to each successor, not an owned `Vec`.
+pub fn for_each_legal_black_move<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    s: &State,
+    tracker: &mut ResourceTracker,
+    mut f: impl FnMut(Coord, &State),
+) -> Result<(), SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut pos = s.pos.clone();
+    let mut count = 0usize;
+
+    scn.rules.for_each_black_move(&mut pos, |delta, next| {
+        let mut canon = next.clone();
+        canon.canonicalize(&scn.rules.layout);
+        let to = State {
+            abs_king: if scn.track_abs_king {
+                s.abs_king + delta
+            } else {
+                s.abs_king
+            },
+            pos: canon,
+        };
+
+        if laws.allow_black_move(s, &to, delta) && laws.allow_state(&to) {
+            count += 1;
+            f(delta, &to);
+        }
+        true
+    });
+
+    tracker.bump_edges("movegen_black", count)?;
+    Ok(())
+}
+
+/// Like [`legal_white_moves`], but calls `f` once per legal successor instead of collecting them
+/// into a `Vec`; see [`for_each_legal_black_move`] for why that matters for graph-building
+/// callers. Built on [`crate::chess::rules::Rules::for_each_white_move`].
+pub fn for_each_legal_white_move<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    s: &State,
+    tracker: &mut ResourceTracker,
+    mut f: impl FnMut(&State),
+) -> Result<(), SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut count = 0usize;
+
+    if scn.white_can_pass && laws.allow_pass(s) {
+        count += 1;
+        f(s);
+    }
+
+    let mut pos = s.pos.clone();
+    scn.rules.for_each_white_move(&mut pos, false, |_mv, next| {
+        let mut canon = next.clone();
+        canon.canonicalize(&scn.rules.layout);
+        let to = State {
+            abs_king: s.abs_king,
+            pos: canon,
+        };
+
+        if laws.allow_white_move(s, &to) && laws.allow_state(&to) {
+            count += 1;
+            f(&to);
+        }
+        true
+    });
+
+    tracker.bump_edges("movegen_white", count)?;
+    Ok(())
+}
+
+/// Predecessors of `target` (an "after black" state) under a single legal black king step: the
+/// black-to-move `State`s `p` such that [`legal_black_moves`] from `p` contains `target`.
+///
+/// The reverse search here is cheap because [`crate::chess::rules::Rules::black_predecessors`]
+/// inverts [`crate::chess::rules::Rules::apply`]/[`crate::chess::rules::Rules::undo`] directly
+/// instead of searching; this just applies the same law/domain filtering `legal_black_moves`
+/// applies to the forward edge, plus the `abs_king` bookkeeping `legal_black_moves` does for
+/// `track_abs_king` scenarios (run in reverse: `p.abs_king = target.abs_king - delta`).
+pub fn black_move_predecessors<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    target: &State,
+) -> Vec<State>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut out = Vec::new();
+
+    for (delta, pos) in scn.rules.black_predecessors(&target.pos) {
+        let abs_king = if scn.track_abs_king {
+            target.abs_king - delta
+        } else {
+            target.abs_king
+        };
+        let p = State { abs_king, pos };
+
+        if !laws.allow_black_move(&p, target, delta) {
+            continue;
+        }
+        if !laws.allow_state(&p) {
+            continue;
+        }
+
+        out.push(p);
+    }
+
+    out
+}
+
+/// Predecessors of `target` (a black-to-move state reached by a white reply) under a single legal
+/// white move: the "after black" `State`s `w` such that [`legal_white_moves`] from `w` contains
+/// `target`.
+///
+/// White moves never change `abs_king`, so `w.abs_king == target.abs_king` always. The trivial
+/// `allow_pass` predecessor (`w == target`) is not enumerated here, since it can never grow a
+/// backward frontier (see [`crate::search::trap::initial_candidate_set`]'s `BackwardFromMates`
+/// candidate generation).
+pub fn white_move_predecessors<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    target: &State,
+) -> Vec<State>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut out = Vec::new();
+
+    for (_, _, _, pos) in scn.rules.white_predecessors(&target.pos) {
+        let w = State {
+            abs_king: target.abs_king,
+            pos,
+        };
+
+        if !laws.allow_white_move(&w, target) {
+            continue;
+        }
+        if !laws.allow_state(&w) {
+            continue;
+        }
+
+        out.push(w);
+    }
+
+    out
+}
+
+/// Like [`legal_black_moves`], but incrementally updates a [`crate::core::zobrist`] hash instead
+/// of recomputing it from scratch for every successor.
+///
+/// `hash` must be `s.zobrist(&scn.rules.layout)`. When `scn.track_abs_king` is false, the
+/// king-relative squares that stay "absolute" (`abs_king == ORIGIN`) all shift on every black
+/// move, so there is no sub-linear incremental update; this falls back to a full recompute in
+/// that case and only updates incrementally (XOR out the old king/captured-piece keys, XOR in the
+/// new king key) when `track_abs_king` is true.
+pub fn legal_black_moves_hashed<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    s: &State,
+    hash: u64,
+    tracker: &mut ResourceTracker,
+) -> Result<Vec<(State, u64)>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut out: Vec<(State, u64)> = Vec::with_capacity(8);
+
+    for (delta, pos2) in scn.rules.black_moves_with_delta(&s.pos) {
+        let new_abs_king = if scn.track_abs_king {
+            s.abs_king + delta
+        } else {
+            s.abs_king
+        };
+
+        let to = State {
+            abs_king: new_abs_king,
+            pos: pos2,
+        };
+
+        if !laws.allow_black_move(s, &to, delta) {
+            continue;
+        }
+        if !laws.allow_state(&to) {
+            continue;
+        }
+
+        let to_hash = if scn.track_abs_king {
+            let mut h = zobrist::move_black_king(hash, s.abs_king, new_abs_king);
+            if let Some(captured) = captured_kind_at(&s.pos, &scn.rules.layout, delta) {
+                // The captured piece sat exactly where the king lands.
+                h = zobrist::toggle_piece(h, captured, new_abs_king);
+            }
+            h
+        } else {
+            to.zobrist(&scn.rules.layout)
+        };
+
+        out.push((to, to_hash));
+    }
+
+    tracker.bump_edges("movegen_black", out.len())?;
+    Ok(out)
+}
+
+/// Like [`legal_white_moves`], but incrementally updates a [`crate::core::zobrist`] hash instead
+/// of recomputing it from scratch for every successor. `hash` must be
+/// `s.zobrist(&scn.rules.layout)`.
+pub fn legal_white_moves_hashed<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    s: &State,
+    hash: u64,
+    tracker: &mut ResourceTracker,
+) -> Result<Vec<(State, u64)>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut out: Vec<(State, u64)> = Vec::new();
+
+    if scn.white_can_pass && laws.allow_pass(s) {
+        out.push((s.clone(), hash));
+    }
+
+    for (mv, pos2) in scn.rules.white_moves_with_move(&s.pos, false) {
+        let to = State {
+            abs_king: s.abs_king,
+            pos: pos2,
+        };
+
+        if !laws.allow_white_move(s, &to) {
+            continue;
+        }
+        if !laws.allow_state(&to) {
+            continue;
+        }
+
+        let to_hash = match mv {
+            Some((kind, from, moved_to)) => {
+                zobrist::move_piece(hash, kind, from + s.abs_king, moved_to + s.abs_king)
+            }
+            None => hash,
+        };
+
+        out.push((to, to_hash));
+    }
+
+    tracker.bump_edges("movegen_white", out.len())?;
+    Ok(out)
+}
+
+/// The kind of the piece (if any) sitting on relative square `delta` in `pos`, i.e. the piece the
+/// black king would capture by stepping to `delta`.
+fn captured_kind_at(
+    pos: &crate::core::position::Position,
+    layout: &crate::chess::layout::PieceLayout,
+    delta: Coord,
+) -> Option<crate::chess::piece::PieceKind> {
+    for i in 0..pos.count() {
+        let sq = pos.square(i);
+        if !sq.is_none() && sq.coord() == delta {
+            return Some(layout.kind(i));
+        }
+    }
+    None
+}
+
+/// The classified status of a black-to-move [`State`], in the spirit of shakmaty's `Outcome`:
+/// a principled result instead of re-deriving mate/stalemate/escape from raw successor counts at
+/// every call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// In check with no legal reply: White has a forced mate.
+    WhiteMates,
+    /// Not in check with no legal reply: the game is drawn.
+    Stalemate,
+    /// At least one legal reply leaves `scn.domain`.
+    DomainExit,
+    /// At least one legal reply stays inside `scn.domain`; the game continues.
+    Ongoing,
+}
+
+/// Classify a black-to-move `s`: in-check with no legal move is [`Outcome::WhiteMates`], not in
+/// check with no legal move is [`Outcome::Stalemate`], a legal move leaving `scn.domain` is
+/// [`Outcome::DomainExit`], and otherwise [`Outcome::Ongoing`].
+pub fn classify_black_to_move<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    laws: &L,
+    s: &State,
+    tracker: &mut ResourceTracker,
+) -> Result<Outcome, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let moves = legal_black_moves(scn, laws, s, tracker)?;
+
+    if moves.is_empty() {
+        let in_check = scn
+            .rules
+            .is_attacked(crate::core::coord::Coord::ORIGIN, &s.pos);
+        return Ok(if in_check {
+            Outcome::WhiteMates
+        } else {
+            Outcome::Stalemate
+        });
+    }
+
+    if moves.iter().any(|to| !scn.domain.inside(to)) {
+        return Ok(Outcome::DomainExit);
+    }
+
+    Ok(Outcome::Ongoing)
+}
+
 pub fn is_checkmate_with_laws<D, L, P>(
     scn: &Scenario<D, L, P>,
     laws: &L,
@@ -83,13 +417,7 @@ where
     D: DomainLike,
     L: LawsLike,
 {
-    if !scn
-        .rules
-        .is_attacked(crate::core::coord::Coord::ORIGIN, &s.pos)
-    {
-        return Ok(false);
-    }
-    Ok(legal_black_moves(scn, laws, s, tracker)?.is_empty())
+    Ok(classify_black_to_move(scn, laws, s, tracker)? == Outcome::WhiteMates)
 }
 
 pub fn is_stalemate_with_laws<D, L, P>(
@@ -102,11 +430,5 @@ where
     D: DomainLike,
     L: LawsLike,
 {
-    if scn
-        .rules
-        .is_attacked(crate::core::coord::Coord::ORIGIN, &s.pos)
-    {
-        return Ok(false);
-    }
-    Ok(legal_black_moves(scn, laws, s, tracker)?.is_empty())
+    Ok(classify_black_to_move(scn, laws, s, tracker)? == Outcome::Stalemate)
 }
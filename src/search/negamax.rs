@@ -0,0 +1,387 @@
+//! Forward, depth-limited forced-mate search.
+//!
+//! [`crate::search::forced_mate::forced_mate_bounded`] requires materializing the whole
+//! `InAbsBox` universe, which is infeasible past modest bounds. This module instead walks the game
+//! tree forward from a [`StartState`], proving (or failing to prove, within budget) a forced mate
+//! via iterative-deepening negamax with alpha-beta pruning — the side-agnostic formulation where
+//! White maximizes toward "mate found" and Black minimizes.
+//!
+//! Unlike [`legal_black_moves`]/[`legal_white_moves`], which clone and canonicalize every
+//! candidate successor up front, this walks a single [`Position`] in place via
+//! [`Rules::for_each_black_move`]/[`Rules::for_each_white_move`] (themselves built on
+//! [`Rules::apply`]/[`Rules::undo`]), recursing directly from inside the callback. That also
+//! means an alpha-beta cutoff stops candidate generation immediately instead of only skipping the
+//! already-generated remainder, and canonicalization only happens where it's actually needed: the
+//! transposition-table key and the final proven line.
+//!
+//! [`legal_black_moves`]: crate::search::movegen::legal_black_moves
+//! [`legal_white_moves`]: crate::search::movegen::legal_white_moves
+
+use rustc_hash::FxHashMap;
+
+use crate::core::coord::Coord;
+use crate::core::position::Position;
+use crate::scenario::{DomainLike, LawsLike, Scenario, SearchError, Side, StartState, State};
+use crate::search::resources::ResourceTracker;
+
+/// A proven forced-mate line: the alternating sequence of states from `start` to checkmate.
+#[derive(Debug, Clone)]
+pub struct MateLine {
+    pub line: Vec<State>,
+    pub plies: u32,
+}
+
+/// Score for a node at a given ply: higher is better for the side currently maximizing.
+///
+/// Mirrors a mate-distance evaluation: a proven mate scores `MATE - ply` so shorter mates
+/// outrank longer ones, and non-mates score the minimum.
+const MATE: i32 = 1_000_000;
+const NON_MATE: i32 = -MATE;
+
+/// What a transposition-table `score` actually bounds, since alpha-beta pruning can cut a node's
+/// search short before its true value is known.
+///
+/// A node that exhausts every move without an early cutoff stores [`Bound::Exact`]. One that
+/// cuts off on `best >= beta` only proves the true value is *at least* `best` (the pruned moves
+/// might have scored higher), so it stores [`Bound::Lower`]. One that never raises `alpha` past
+/// its entry value only proves the true value is *at most* `best`, so it stores [`Bound::Upper`].
+/// Reusing a cached score at a different (possibly wider) alpha-beta window without checking this
+/// tag would replay a bound as if it were exact — the classic fail-soft-TT-without-flag bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// Search for a forced mate from `start` within `max_depth` plies, using iterative deepening.
+///
+/// Returns `Ok(Some(line))` the first time a depth proves mate (so the returned line has the
+/// minimal ply count up to `max_depth`), `Ok(None)` if no depth up to `max_depth` proves one, and
+/// an error if a resource limit is hit first.
+pub fn forced_mate_search<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    start: &StartState,
+    max_depth: u32,
+) -> Result<Option<MateLine>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    scn.validate()?;
+
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    for depth in 1..=max_depth {
+        tracker.bump_steps("negamax_iter_deepen", 1)?;
+
+        // Transposition table keyed by canonical state: (depth searched, score, bound).
+        // A cached entry is only reusable at >= the depth it was computed at, and only for a
+        // lookup window the bound is actually compatible with (see `Bound`).
+        let mut tt: FxHashMap<(Side, State), (u32, i32, Bound)> = FxHashMap::default();
+
+        let mut pos = start.state.pos.clone();
+        let mut line: Vec<State> = vec![start.state.clone()];
+        let score = negamax(
+            scn,
+            &mut tracker,
+            &mut tt,
+            start.to_move,
+            start.state.abs_king,
+            &mut pos,
+            depth,
+            NON_MATE,
+            MATE + 1,
+            &mut line,
+        )?;
+
+        if score > 0 {
+            let plies = (MATE - score) as u32;
+            line.truncate((plies as usize) + 1);
+            return Ok(Some(MateLine { line, plies }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Depth-unbounded variant of [`forced_mate_search`]: iterative-deepens until either a mate is
+/// proven or the scenario's [`ResourceLimits::max_runtime_steps`](crate::scenario::ResourceLimits)
+/// budget (tracked by `forced_mate_search`'s `tracker` across the whole deepening loop) is
+/// exhausted, converting that case to `Ok(None)` instead of propagating
+/// `SearchError::LimitExceeded` — for a caller asking "is there a forced mate at all, within
+/// budget" rather than one who already knows a specific ply cap to search to.
+pub fn forced_mate_search_within_budget<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    start: &StartState,
+) -> Result<Option<MateLine>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    match forced_mate_search(scn, start, u32::MAX) {
+        Ok(found) => Ok(found),
+        Err(SearchError::LimitExceeded { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Negamax over a single mutated [`Position`] plus the side-tracked `abs_king`, rather than over
+/// owned [`State`] clones. `line` is the best line found *for the caller's ancestor frame*; this
+/// function only overwrites it with a deeper line when it finds a strictly better one.
+#[allow(clippy::too_many_arguments)]
+fn negamax<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    tracker: &mut ResourceTracker,
+    tt: &mut FxHashMap<(Side, State), (u32, i32, Bound)>,
+    to_move: Side,
+    abs_king: Coord,
+    pos: &mut Position,
+    depth_left: u32,
+    mut alpha: i32,
+    beta: i32,
+    line: &mut Vec<State>,
+) -> Result<i32, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    tracker.bump_steps("negamax_node", 1)?;
+
+    // Canonicalize lazily: only to form the transposition-table key, not for every candidate
+    // generated below.
+    let tt_key = {
+        let mut canon = pos.clone();
+        canon.canonicalize(&scn.rules.layout);
+        (to_move, State::new(abs_king, canon))
+    };
+    if let Some(&(seen_depth, score, bound)) = tt.get(&tt_key) {
+        if seen_depth >= depth_left {
+            match bound {
+                Bound::Exact => return Ok(score),
+                // `score` only proves the true value is >= itself; that's only useful here if
+                // it already meets or beats the current `beta`.
+                Bound::Lower if score >= beta => return Ok(score),
+                // `score` only proves the true value is <= itself; that's only useful here if
+                // it's already at or below the current `alpha`.
+                Bound::Upper if score <= alpha => return Ok(score),
+                _ => {}
+            }
+        }
+    }
+
+    // Snapshot of `alpha` at entry, before the move loops below mutate it, so the final bound
+    // tag reflects whether this node failed low relative to the window it was *called* with.
+    let alpha_orig = alpha;
+
+    let next_to_move = match to_move {
+        Side::Black => Side::White,
+        Side::White => Side::Black,
+    };
+    let from_state = State::new(abs_king, pos.clone());
+
+    let mut has_move = false;
+    let mut best = NON_MATE;
+    let mut best_line: Option<Vec<State>> = None;
+    let mut err: Option<SearchError> = None;
+
+    match to_move {
+        Side::Black => {
+            scn.rules.for_each_black_move(pos, |delta, next_pos| {
+                has_move = true;
+                if err.is_some() {
+                    return false;
+                }
+
+                let new_abs_king = if scn.track_abs_king {
+                    abs_king + delta
+                } else {
+                    abs_king
+                };
+                let to_state = State::new(new_abs_king, next_pos.clone());
+
+                if !scn.laws.allow_black_move(&from_state, &to_state, delta)
+                    || !scn.laws.allow_state(&to_state)
+                {
+                    return true;
+                }
+                if depth_left == 0 {
+                    return true;
+                }
+
+                let mut child_line = line.clone();
+                child_line.push(to_state);
+
+                match negamax(
+                    scn,
+                    tracker,
+                    tt,
+                    next_to_move,
+                    new_abs_king,
+                    next_pos,
+                    depth_left - 1,
+                    -beta,
+                    -alpha,
+                    &mut child_line,
+                ) {
+                    Ok(child_score) => {
+                        let score = score_for_child(to_move, child_score);
+                        if score > best {
+                            best = score;
+                            best_line = Some(child_line);
+                        }
+                        alpha = alpha.max(score);
+                        alpha < beta
+                    }
+                    Err(e) => {
+                        err = Some(e);
+                        false
+                    }
+                }
+            });
+        }
+        Side::White => {
+            if scn.white_can_pass && scn.laws.allow_pass(&from_state) {
+                has_move = true;
+                if depth_left > 0 {
+                    let to_state = from_state.clone();
+                    let mut child_line = line.clone();
+                    child_line.push(to_state);
+
+                    match negamax(
+                        scn,
+                        tracker,
+                        tt,
+                        next_to_move,
+                        abs_king,
+                        pos,
+                        depth_left - 1,
+                        -beta,
+                        -alpha,
+                        &mut child_line,
+                    ) {
+                        Ok(child_score) => {
+                            let score = score_for_child(to_move, child_score);
+                            if score > best {
+                                best = score;
+                                best_line = Some(child_line);
+                            }
+                            alpha = alpha.max(score);
+                        }
+                        Err(e) => err = Some(e),
+                    }
+                }
+            }
+
+            if err.is_none() && alpha < beta {
+                scn.rules.for_each_white_move(pos, false, |mv, next_pos| {
+                    has_move = true;
+                    if err.is_some() {
+                        return false;
+                    }
+
+                    let to_state = State::new(abs_king, next_pos.clone());
+                    if !scn.laws.allow_white_move(&from_state, &to_state)
+                        || !scn.laws.allow_state(&to_state)
+                    {
+                        return true;
+                    }
+                    if depth_left == 0 {
+                        return true;
+                    }
+
+                    let mut child_line = line.clone();
+                    child_line.push(to_state);
+
+                    match negamax(
+                        scn,
+                        tracker,
+                        tt,
+                        next_to_move,
+                        abs_king,
+                        next_pos,
+                        depth_left - 1,
+                        -beta,
+                        -alpha,
+                        &mut child_line,
+                    ) {
+                        Ok(child_score) => {
+                            let score = score_for_child(to_move, child_score);
+                            if score > best {
+                                best = score;
+                                best_line = Some(child_line);
+                            }
+                            alpha = alpha.max(score);
+                            alpha < beta
+                        }
+                        Err(e) => {
+                            err = Some(e);
+                            false
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    if let Some(e) = err {
+        return Err(e);
+    }
+
+    if !has_move {
+        // A black-to-move node with no in-universe reply is an immediate win for White iff Black
+        // is in check here; otherwise it's an escape (non-mate) rather than a win. White having no
+        // legal move (shouldn't normally arise with `white_can_pass`) is treated the same as a
+        // non-mate leaf rather than asserting.
+        let score = if to_move == Side::Black && scn.rules.is_attacked(Coord::ORIGIN, pos) {
+            MATE
+        } else {
+            NON_MATE
+        };
+        tt.insert(tt_key, (depth_left, score, Bound::Exact));
+        return Ok(score);
+    }
+
+    if depth_left == 0 {
+        return Ok(NON_MATE);
+    }
+
+    if let Some(bl) = best_line {
+        *line = bl;
+    }
+
+    // `best >= beta` means a cutoff stopped the loop early (pruned siblings might have scored
+    // higher): only a lower bound. `best <= alpha_orig` means no move ever raised `alpha`: only
+    // an upper bound. Otherwise every move was examined and `best` is the true value.
+    let bound = if best >= beta {
+        Bound::Lower
+    } else if best <= alpha_orig {
+        Bound::Upper
+    } else {
+        Bound::Exact
+    };
+    tt.insert(tt_key, (depth_left, best, bound));
+    Ok(best)
+}
+
+/// Negamax child-score fold: White nodes propagate the child score directly (maximize), Black
+/// nodes negate it (minimize White's score == maximize the negated value).
+#[inline]
+fn score_for_child(to_move: Side, child_score: i32) -> i32 {
+    match to_move {
+        Side::White => decay(child_score),
+        Side::Black => decay(-child_score),
+    }
+}
+
+/// Shorten a mate score by one ply as it propagates up, so shorter mates are preferred.
+#[inline]
+fn decay(score: i32) -> i32 {
+    if score > 0 {
+        score - 1
+    } else if score < 0 {
+        score + 1
+    } else {
+        score
+    }
+}
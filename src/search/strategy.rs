@@ -6,9 +6,14 @@
 //! The helpers here extract a memoryless strategy *after* correctness-critical computation.
 //! Preferences are used only as tie-breakers and do not affect trap set membership.
 
-use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
 
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::core::coord::Coord;
+use crate::core::square::Square;
 use crate::scenario::{DomainLike, LawsLike, PreferencesLike, Scenario, SearchError, State};
+use crate::search::buchi::is_tempo_node;
 use crate::search::movegen::{legal_black_moves, legal_white_moves};
 use crate::search::resources::ResourceTracker;
 
@@ -47,7 +52,7 @@ where
                 continue;
             }
 
-            let ranking = scn.preferences.rank_white_moves(&w, &stay);
+            let ranking = scn.preferences.rank_white_moves(&w, &stay, scn.tie_break);
             let choice = ranking
                 .into_iter()
                 .find_map(|idx| stay.get(idx).cloned())
@@ -59,3 +64,578 @@ where
 
     Ok(out)
 }
+
+/// Extract White's "stay in trap" strategy (see [`extract_white_stay_strategy`]) and compile it
+/// into a [`WhiteStrategy`], which keeps the raw map alongside a compact decision-tree lookup form
+/// and a [`WhiteStrategy::verify`] check.
+///
+/// `trap` is the set a prior [`crate::search::trap::maximal_inescapable_trap`] call certified as
+/// inescapable; the returned strategy is the concrete witness that every reachable white-to-move
+/// node has a reply staying inside it.
+pub fn extract_white_strategy<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    trap: &FxHashSet<State>,
+) -> Result<WhiteStrategy, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+    P: PreferencesLike,
+{
+    let map = extract_white_stay_strategy(scn, trap)?;
+    let tree = DecisionNode::compile(scn, &map);
+    Ok(WhiteStrategy { map, tree })
+}
+
+/// A memoryless White strategy over a solved trap (see [`extract_white_strategy`]), in both its
+/// direct `FxHashMap` form and a compiled decision-tree form.
+pub struct WhiteStrategy {
+    map: FxHashMap<State, State>,
+    tree: DecisionNode,
+}
+
+impl WhiteStrategy {
+    /// The direct `white-to-move state -> reply` map, as produced by
+    /// [`extract_white_stay_strategy`].
+    pub fn as_map(&self) -> &FxHashMap<State, State> {
+        &self.map
+    }
+
+    /// The compiled decision-tree form of this strategy (see [`DecisionNode`]).
+    pub fn as_tree(&self) -> &DecisionNode {
+        &self.tree
+    }
+
+    /// Look up White's reply to `w` via the compiled tree rather than the raw map. Agrees with
+    /// `self.as_map().get(w)` for every `w` the strategy was built from.
+    pub fn lookup(&self, w: &State) -> Option<&State> {
+        self.tree.lookup(w)
+    }
+
+    /// Re-check that every recorded reply lands back in `trap`, so the strategy doubles as an
+    /// independently checkable proof of the trap rather than something callers must trust blindly.
+    pub fn verify(&self, trap: &FxHashSet<State>) -> StrategyVerifyReport {
+        let bad_replies: Vec<State> = self
+            .map
+            .iter()
+            .filter(|(_, reply)| !trap.contains(*reply))
+            .map(|(w, _)| w.clone())
+            .collect();
+        StrategyVerifyReport {
+            ok: bad_replies.is_empty(),
+            bad_replies,
+        }
+    }
+}
+
+/// Report produced by [`WhiteStrategy::verify`].
+#[derive(Debug, Clone)]
+pub struct StrategyVerifyReport {
+    pub ok: bool,
+    /// White-to-move states whose recorded reply does *not* land back in the trap, keyed by the
+    /// white-to-move state itself (not the offending reply).
+    pub bad_replies: Vec<State>,
+}
+
+/// A single feature a [`DecisionNode::Test`] branches on: either the absolute black king
+/// coordinate, or the square a given piece slot occupies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    AbsKing,
+    Slot(usize),
+}
+
+impl Feature {
+    fn eval(self, s: &State) -> FeatureValue {
+        match self {
+            Feature::AbsKing => FeatureValue::Coord(s.abs_king),
+            Feature::Slot(idx) => FeatureValue::Square(s.pos.square(idx)),
+        }
+    }
+}
+
+/// The value a [`Feature`] evaluates to on a given state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureValue {
+    Coord(Coord),
+    Square(Square),
+}
+
+/// A compiled decision tree over a `white-to-move state -> reply` map: instead of hashing a full
+/// [`State`] against a flat table, walk down testing one [`Feature`] at a time (the absolute king
+/// coordinate, then each piece slot's square, in that order) until a single matching reply remains
+/// — the same "test a feature, branch, recurse" shape a compiler uses to turn pattern matches into
+/// decision trees.
+///
+/// A leaf is reached as soon as only one recorded state reaches it (states are split further even
+/// when they share a reply); the features not yet tested on that path are not checked. A lookup for a state the
+/// strategy was never built from may therefore return a stale reply rather than `None`, the same
+/// trade-off an exhaustively-tested pattern match relies on the input actually matching some arm.
+/// Callers that care should check `as_map().contains_key(w)` instead, or go through
+/// [`WhiteStrategy::lookup`] only on states already known to be trap members.
+pub enum DecisionNode {
+    /// No reachable white-to-move state ever had the features tested on this path.
+    Empty,
+    /// Every state reaching this node shares the same recorded reply.
+    Leaf(State),
+    /// Test `feature`, then recurse into the branch matching its value.
+    Test {
+        feature: Feature,
+        branches: FxHashMap<FeatureValue, DecisionNode>,
+    },
+}
+
+impl DecisionNode {
+    fn compile<D, L, P>(scn: &Scenario<D, L, P>, map: &FxHashMap<State, State>) -> DecisionNode
+    where
+        D: DomainLike,
+        L: LawsLike,
+    {
+        let mut features: Vec<Feature> = Vec::new();
+        if scn.track_abs_king {
+            features.push(Feature::AbsKing);
+        }
+        for idx in 0..scn.rules.layout.piece_count() {
+            features.push(Feature::Slot(idx));
+        }
+
+        let entries: Vec<(&State, &State)> = map.iter().collect();
+        Self::build(&entries, &features)
+    }
+
+    fn build(entries: &[(&State, &State)], features: &[Feature]) -> DecisionNode {
+        if entries.is_empty() {
+            return DecisionNode::Empty;
+        }
+        if entries.len() == 1 {
+            return DecisionNode::Leaf(entries[0].1.clone());
+        }
+
+        let Some((feature, rest)) = features.split_first() else {
+            // Exhausted every feature without narrowing to one reply; fall back to the first —
+            // this only happens if two distinct `State` keys agree on every tested feature, which
+            // shouldn't occur for a `State` built from a single scenario's layout.
+            return DecisionNode::Leaf(entries[0].1.clone());
+        };
+
+        let mut groups: FxHashMap<FeatureValue, Vec<(&State, &State)>> = FxHashMap::default();
+        for &(w, reply) in entries {
+            groups.entry(feature.eval(w)).or_default().push((w, reply));
+        }
+
+        let branches = groups
+            .into_iter()
+            .map(|(value, group)| (value, DecisionNode::build(&group, rest)))
+            .collect();
+
+        DecisionNode::Test {
+            feature: *feature,
+            branches,
+        }
+    }
+
+    /// Walk the tree for `w`, returning its recorded reply if one was found.
+    pub fn lookup(&self, w: &State) -> Option<&State> {
+        match self {
+            DecisionNode::Empty => None,
+            DecisionNode::Leaf(reply) => Some(reply),
+            DecisionNode::Test { feature, branches } => branches
+                .get(&feature.eval(w))
+                .and_then(|node| node.lookup(w)),
+        }
+    }
+}
+
+/// Extract the principal variation from `start` through a distance-to-mate table (as produced by
+/// `forced_mate::forced_mate_bounded`'s `dtm`), alternating a negamax-style choice at each ply:
+/// from a black-to-move node, Black (the defender) picks the legal move that *maximizes* the best
+/// DTM White can still force; from the resulting white-to-move node, White picks the legal reply
+/// that *minimizes* it. `scn.preferences` only breaks ties among moves with identical DTM —
+/// correctness never depends on which tied move is chosen.
+///
+/// Returns the full line (alternating black-to-move and white-to-move states, `start` first) and
+/// its length in plies (`dtm[start]`). Errors if `start` is not a winning (present) entry in `dtm`.
+pub fn extract_dtm_line<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    dtm: &FxHashMap<State, u32>,
+    start: &State,
+) -> Result<(Vec<State>, u32), SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+    P: PreferencesLike,
+{
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    let &start_dtm = dtm.get(start).ok_or_else(|| SearchError::InvalidScenario {
+        reason: "extract_dtm_line requires a start state present in the DTM table".to_string(),
+    })?;
+
+    let mut line: Vec<State> = vec![start.clone()];
+    let mut cur = start.clone();
+
+    while dtm[&cur] != 0 {
+        tracker.bump_steps("dtm_line_extract", 1)?;
+
+        // For each black move, White's best (DTM-minimizing) reply and the DTM it achieves.
+        let mut per_move: Vec<(State, u32, State)> = Vec::new();
+        for w in legal_black_moves(scn, &scn.laws, &cur, &mut tracker)? {
+            let replies = legal_white_moves(scn, &scn.laws, &w, &mut tracker)?;
+            let mut winning: Vec<State> = replies
+                .into_iter()
+                .filter(|r| dtm.contains_key(r))
+                .collect();
+            if winning.is_empty() {
+                continue;
+            }
+
+            let best = winning.iter().map(|r| dtm[r]).min().unwrap();
+            let mut tied: Vec<State> = winning.into_iter().filter(|r| dtm[r] == best).collect();
+            let ranking = scn.preferences.rank_white_moves(&w, &tied, scn.tie_break);
+            let chosen = ranking
+                .into_iter()
+                .find_map(|idx| tied.get(idx).cloned())
+                .unwrap_or_else(|| tied.swap_remove(0));
+
+            per_move.push((w, best, chosen));
+        }
+
+        if per_move.is_empty() {
+            return Err(SearchError::InvalidScenario {
+                reason: "extract_dtm_line: no legal black move has a winning white reply"
+                    .to_string(),
+            });
+        }
+
+        // Black (the defender) picks the move that maximizes White's best-forced DTM.
+        let worst_for_white = per_move.iter().map(|(_, v, _)| *v).max().unwrap();
+        let mut tied_black: Vec<State> = per_move
+            .iter()
+            .filter(|(_, v, _)| *v == worst_for_white)
+            .map(|(w, _, _)| w.clone())
+            .collect();
+        let ranking = scn
+            .preferences
+            .rank_black_moves(&cur, &tied_black, scn.tie_break);
+        let chosen_w = ranking
+            .into_iter()
+            .find_map(|idx| tied_black.get(idx).cloned())
+            .unwrap_or_else(|| tied_black.swap_remove(0));
+
+        let (_, _, chosen_reply) = per_move
+            .into_iter()
+            .find(|(w, _, _)| *w == chosen_w)
+            .expect("chosen_w was one of per_move's black moves");
+
+        line.push(chosen_w);
+        line.push(chosen_reply.clone());
+        cur = chosen_reply;
+    }
+
+    Ok((line, start_dtm))
+}
+
+/// Extract White's complete forced-mate strategy reachable from `start`: a map from every
+/// white-to-move state that can arise (for *any* black defense, not just the principal variation)
+/// to White's DTM-minimizing response.
+///
+/// Where [`extract_dtm_line`] follows one line assuming Black always plays the objectively worst
+/// defense, this covers every black reply at every reachable node, so it can answer "what should
+/// White do here?" no matter how Black actually defends. Ties are broken the same way as
+/// [`extract_dtm_line`].
+pub fn extract_dtm_tree<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    dtm: &FxHashMap<State, u32>,
+    start: &State,
+) -> Result<FxHashMap<State, State>, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+    P: PreferencesLike,
+{
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    if !dtm.contains_key(start) {
+        return Err(SearchError::InvalidScenario {
+            reason: "extract_dtm_tree requires a start state present in the DTM table".to_string(),
+        });
+    }
+
+    let mut strategy: FxHashMap<State, State> = FxHashMap::default();
+    let mut seen: FxHashSet<State> = FxHashSet::default();
+    let mut queue: VecDeque<State> = VecDeque::new();
+    queue.push_back(start.clone());
+    seen.insert(start.clone());
+
+    while let Some(b) = queue.pop_front() {
+        tracker.bump_steps("dtm_tree_extract", 1)?;
+        if dtm[&b] == 0 {
+            continue; // checkmate: no further White move from here
+        }
+
+        for w in legal_black_moves(scn, &scn.laws, &b, &mut tracker)? {
+            if strategy.contains_key(&w) {
+                continue;
+            }
+
+            let replies = legal_white_moves(scn, &scn.laws, &w, &mut tracker)?;
+            let mut winning: Vec<State> = replies
+                .into_iter()
+                .filter(|r| dtm.contains_key(r))
+                .collect();
+            if winning.is_empty() {
+                // Inconsistent with a winning `dtm[start]`; skip rather than fail the whole tree.
+                continue;
+            }
+
+            let best = winning.iter().map(|r| dtm[r]).min().unwrap();
+            let mut tied: Vec<State> = winning.into_iter().filter(|r| dtm[r] == best).collect();
+            let ranking = scn.preferences.rank_white_moves(&w, &tied, scn.tie_break);
+            let chosen = ranking
+                .into_iter()
+                .find_map(|idx| tied.get(idx).cloned())
+                .unwrap_or_else(|| tied.swap_remove(0));
+
+            strategy.insert(w, chosen.clone());
+
+            if seen.insert(chosen.clone()) {
+                queue.push_back(chosen);
+            }
+        }
+    }
+
+    Ok(strategy)
+}
+
+/// What a [`verify_strategy_progress`] strategy is supposed to achieve, and the data needed to
+/// check it.
+pub enum ProgressObjective<'a> {
+    /// A mate strategy (from [`extract_dtm_line`]/[`extract_dtm_tree`]), rooted at `start`: every
+    /// step reachable under the strategy must strictly decrease `dtm` by exactly one ply pair,
+    /// terminating at `dtm == 0`.
+    ForcedMate {
+        dtm: &'a FxHashMap<State, u32>,
+        start: &'a State,
+    },
+    /// A tempo-trap "stay" strategy (from [`extract_white_stay_strategy`]): every strongly
+    /// connected component of the graph the strategy induces over `btm_trap` that contains a cycle
+    /// (more than one node, or a self-loop) must contain at least one tempo node (see
+    /// [`is_tempo_node`]).
+    TempoTrap { btm_trap: &'a FxHashSet<State> },
+}
+
+/// A single way [`verify_strategy_progress`] found the strategy to not make genuine progress.
+#[derive(Debug, Clone)]
+pub enum ProgressViolation {
+    /// White's reply from `white_node` did not strictly decrease `dtm` by exactly 2 (one black
+    /// ply + one white ply), or landed outside the `dtm` table entirely.
+    DtmNotDecreasing {
+        white_node: State,
+        black_reply: State,
+    },
+    /// A cycle in the tempo-trap strategy graph that never passes through a tempo node: an
+    /// infinite play consistent with the strategy could shuffle forever without White ever
+    /// taking the "free pass", so the Büchi objective would not actually be satisfied.
+    TempoFreeCycle { states: Vec<State> },
+}
+
+/// Report produced by [`verify_strategy_progress`]: whether the strategy makes genuine progress
+/// and, if not, every state that witnesses a violation.
+#[derive(Debug, Clone)]
+pub struct StrategyProgressReport {
+    pub ok: bool,
+    pub violations: Vec<ProgressViolation>,
+}
+
+/// Verify that an extracted memoryless strategy actually makes progress rather than shuffling
+/// forever, per `objective` (see [`ProgressObjective`]).
+pub fn verify_strategy_progress<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    strategy: &FxHashMap<State, State>,
+    objective: ProgressObjective<'_>,
+) -> Result<StrategyProgressReport, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    match objective {
+        ProgressObjective::ForcedMate { dtm, start } => {
+            verify_mate_progress(scn, strategy, dtm, start)
+        }
+        ProgressObjective::TempoTrap { btm_trap } => verify_tempo_progress(scn, strategy, btm_trap),
+    }
+}
+
+fn verify_mate_progress<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    strategy: &FxHashMap<State, State>,
+    dtm: &FxHashMap<State, u32>,
+    start: &State,
+) -> Result<StrategyProgressReport, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    if !dtm.contains_key(start) {
+        return Err(SearchError::InvalidScenario {
+            reason: "verify_strategy_progress requires `start` to be present in the DTM table"
+                .to_string(),
+        });
+    }
+
+    let mut violations: Vec<ProgressViolation> = Vec::new();
+    let mut seen: FxHashSet<State> = FxHashSet::default();
+    let mut queue: VecDeque<State> = VecDeque::new();
+    queue.push_back(start.clone());
+    seen.insert(start.clone());
+
+    while let Some(b) = queue.pop_front() {
+        tracker.bump_steps("verify_mate_progress", 1)?;
+
+        let Some(&d) = dtm.get(&b) else {
+            continue; // outside the table: not this strategy's responsibility to cover
+        };
+        if d == 0 {
+            continue; // checkmate: no further White move to check
+        }
+
+        for w in legal_black_moves(scn, &scn.laws, &b, &mut tracker)? {
+            let Some(reply) = strategy.get(&w) else {
+                continue; // Black move not covered by this strategy's lines
+            };
+
+            let decreases = matches!(dtm.get(reply), Some(&rd) if rd + 2 == d);
+            if !decreases {
+                violations.push(ProgressViolation::DtmNotDecreasing {
+                    white_node: w,
+                    black_reply: reply.clone(),
+                });
+            } else if seen.insert(reply.clone()) {
+                queue.push_back(reply.clone());
+            }
+        }
+    }
+
+    Ok(StrategyProgressReport {
+        ok: violations.is_empty(),
+        violations,
+    })
+}
+
+fn verify_tempo_progress<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    strategy: &FxHashMap<State, State>,
+    btm_trap: &FxHashSet<State>,
+) -> Result<StrategyProgressReport, SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    let mut tracker = ResourceTracker::new(scn.limits);
+
+    // Nodes: every white-to-move state the strategy commits a reply for.
+    let w_list: Vec<State> = strategy.keys().cloned().collect();
+    let w_len = w_list.len();
+    let mut w_index: FxHashMap<State, usize> = FxHashMap::default();
+    for (i, w) in w_list.iter().enumerate() {
+        w_index.insert(w.clone(), i);
+    }
+
+    // Edges: w -> w2 for every legal black reply to `strategy[w]` that lands on another node the
+    // strategy covers. A reply the strategy doesn't cover is a dead end for this graph, not a
+    // cycle, so it's simply not an edge.
+    let mut succ: Vec<Vec<usize>> = vec![Vec::new(); w_len];
+    for (wi, w) in w_list.iter().enumerate() {
+        tracker.bump_steps("verify_tempo_progress", 1)?;
+
+        let reply = &strategy[w];
+        for w2 in legal_black_moves(scn, &scn.laws, reply, &mut tracker)? {
+            if let Some(&wi2) = w_index.get(&w2) {
+                succ[wi].push(wi2);
+            }
+        }
+    }
+
+    let is_tempo: Vec<bool> = w_list
+        .iter()
+        .map(|w| is_tempo_node(scn, btm_trap, w))
+        .collect();
+
+    let mut violations: Vec<ProgressViolation> = Vec::new();
+    for comp in strongly_connected_components(&succ) {
+        let is_cycle = comp.len() > 1 || succ[comp[0]].contains(&comp[0]);
+        if !is_cycle {
+            continue; // a transient node can't be part of an infinite play
+        }
+        if !comp.iter().any(|&i| is_tempo[i]) {
+            violations.push(ProgressViolation::TempoFreeCycle {
+                states: comp.into_iter().map(|i| w_list[i].clone()).collect(),
+            });
+        }
+    }
+
+    Ok(StrategyProgressReport {
+        ok: violations.is_empty(),
+        violations,
+    })
+}
+
+/// Strongly connected components of the graph given by `succ` (adjacency list by node index), via
+/// an iterative two-pass (Kosaraju) traversal so it doesn't blow the call stack on large graphs.
+fn strongly_connected_components(succ: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = succ.len();
+    let mut visited = vec![false; n];
+    let mut finish_order: Vec<usize> = Vec::with_capacity(n);
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            if *next < succ[node].len() {
+                let child = succ[node][*next];
+                *next += 1;
+                if !visited[child] {
+                    visited[child] = true;
+                    stack.push((child, 0));
+                }
+            } else {
+                finish_order.push(node);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut pred: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (u, outs) in succ.iter().enumerate() {
+        for &v in outs {
+            pred[v].push(u);
+        }
+    }
+
+    let mut assigned = vec![false; n];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for &node in finish_order.iter().rev() {
+        if assigned[node] {
+            continue;
+        }
+        assigned[node] = true;
+        let mut comp = vec![node];
+        let mut stack = vec![node];
+        while let Some(u) = stack.pop() {
+            for &v in &pred[u] {
+                if !assigned[v] {
+                    assigned[v] = true;
+                    comp.push(v);
+                    stack.push(v);
+                }
+            }
+        }
+        components.push(comp);
+    }
+
+    components
+}
@@ -0,0 +1,358 @@
+//! On-disk WDL+DTM tablebase, in the spirit of Syzygy endgame tablebases.
+//!
+//! [`crate::search::forced_mate::forced_mate_bounded`] returns everything in RAM
+//! (`winning_btm: FxHashSet<State>` and `dtm: FxHashMap<State, u32>`), which does not survive large
+//! `bound` values. This module separates generation from probing: [`write_tablebase`] persists an
+//! already-solved universe to a compact file, and [`Tablebase::open`] memory-maps it for cheap
+//! repeated [`Tablebase::probe`] queries without reconstructing the graph.
+//!
+//! ## On-disk layout
+//!
+//! ```text
+//! header:      magic "ICTB", version: u32, bound: i32, allow_captures: u8, piece_count: u32,
+//!              state_count: u64
+//! keys:        state_count canonical keys, each piece_count * 8 bytes (raw `Square` values in the
+//!              same order `Position::canonicalize` produces), sorted lexicographically
+//! wdl bits:    ceil(state_count / 8) bytes, bit i set iff state i is a White win
+//! dtm stream:  delta+varint-encoded distance-to-mate, one entry per state in key order (0 for
+//!              draws; DTM values are locally clustered by layer, so delta+varint compresses well)
+//! checkpoints: ceil(state_count / DTM_CHECKPOINT_STRIDE) fixed-size (u64 byte offset into the dtm
+//!              stream, i64 cumulative value) pairs, one per `DTM_CHECKPOINT_STRIDE`-th state,
+//!              letting a probe resume varint decoding from the nearest checkpoint instead of
+//!              from the start of the stream
+//! ```
+//!
+//! A probe binary-searches the key array to find a state's index, decodes its WDL bit directly,
+//! and for a win decodes its DTM entry by seeking to the nearest preceding checkpoint and
+//! replaying at most `DTM_CHECKPOINT_STRIDE` varints forward — O(log n) overall instead of
+//! rescanning the whole delta-encoded stream from the front.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::chess::layout::PieceLayout;
+use crate::core::coord::Coord;
+use crate::core::position::Position;
+use crate::scenario::{SearchError, State};
+use crate::search::forced_mate::ForcedMateResult;
+
+const MAGIC: &[u8; 4] = b"ICTB";
+const FORMAT_VERSION: u32 = 2;
+
+/// Every `DTM_CHECKPOINT_STRIDE`-th state in key order gets a checkpoint, bounding how many
+/// varints a probe ever has to replay to recover a DTM value.
+const DTM_CHECKPOINT_STRIDE: usize = 64;
+
+/// Win/Draw classification for a black-to-move state (Black can never win here).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wdl {
+    Win,
+    Draw,
+}
+
+fn io_err(stage: &'static str, path: &Path, error: impl ToString) -> SearchError {
+    SearchError::Io {
+        stage,
+        path: path.display().to_string(),
+        error: error.to_string(),
+    }
+}
+
+/// Canonical sort key for a state: the raw packed squares, in layout order.
+///
+/// This assumes `pos` has already been through `Position::canonicalize`, so identical-piece runs
+/// are in a fixed order and the key is well-defined.
+fn canonical_key(pos: &Position) -> Vec<i64> {
+    pos.squares().iter().map(|s| s.raw()).collect()
+}
+
+fn write_varint_u64(out: &mut Vec<u8>, mut v: u64) {
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_varint_u64(buf: &[u8], pos: &mut usize) -> u64 {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = buf[*pos];
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    result
+}
+
+#[inline]
+fn zigzag_encode(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+#[inline]
+fn zigzag_decode(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+/// Persist an already-solved forced-mate tablebase (`result`, computed with `compute_dtm=true`)
+/// to `path`.
+///
+/// `universe` must be exactly the placements `forced_mate_bounded` enumerated (the same order is
+/// not required; this routine sorts into canonical order itself).
+pub fn write_tablebase<P: AsRef<Path>>(
+    path: P,
+    layout: &PieceLayout,
+    bound: i32,
+    allow_captures: bool,
+    universe: &[State],
+    result: &ForcedMateResult,
+) -> Result<(), SearchError> {
+    let path = path.as_ref();
+    let dtm = result
+        .dtm
+        .as_ref()
+        .ok_or_else(|| SearchError::InvalidScenario {
+            reason: "write_tablebase requires a ForcedMateResult computed with compute_dtm=true"
+                .to_string(),
+        })?;
+
+    let piece_count = layout.piece_count();
+
+    let mut entries: Vec<(Vec<i64>, Wdl, u32)> = universe
+        .iter()
+        .map(|s| {
+            let key = canonical_key(&s.pos);
+            match dtm.get(s) {
+                Some(&d) => (key, Wdl::Win, d),
+                None => (key, Wdl::Draw, 0),
+            }
+        })
+        .collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    let file = File::create(path).map_err(|e| io_err("tablebase_write", path, e))?;
+    let mut w = BufWriter::new(file);
+
+    w.write_all(MAGIC)
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+    w.write_all(&FORMAT_VERSION.to_le_bytes())
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+    w.write_all(&bound.to_le_bytes())
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+    w.write_all(&[allow_captures as u8])
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+    w.write_all(&(piece_count as u32).to_le_bytes())
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+    w.write_all(&(entries.len() as u64).to_le_bytes())
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+
+    for (key, _, _) in entries.iter() {
+        for &v in key.iter() {
+            w.write_all(&v.to_le_bytes())
+                .map_err(|e| io_err("tablebase_write", path, e))?;
+        }
+    }
+
+    let mut wdl_bits = vec![0u8; entries.len().div_ceil(8)];
+    for (i, (_, wdl, _)) in entries.iter().enumerate() {
+        if *wdl == Wdl::Win {
+            wdl_bits[i / 8] |= 1 << (i % 8);
+        }
+    }
+    w.write_all(&wdl_bits)
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+
+    let mut dtm_bytes: Vec<u8> = Vec::new();
+    let mut checkpoints: Vec<(u64, i64)> = Vec::new();
+    let mut prev: i64 = 0;
+    for (i, (_, _, d)) in entries.iter().enumerate() {
+        if i % DTM_CHECKPOINT_STRIDE == 0 {
+            checkpoints.push((dtm_bytes.len() as u64, prev));
+        }
+        let d = *d as i64;
+        write_varint_u64(&mut dtm_bytes, zigzag_encode(d - prev));
+        prev = d;
+    }
+    w.write_all(&dtm_bytes)
+        .map_err(|e| io_err("tablebase_write", path, e))?;
+
+    for (offset, value) in checkpoints.iter() {
+        w.write_all(&offset.to_le_bytes())
+            .map_err(|e| io_err("tablebase_write", path, e))?;
+        w.write_all(&value.to_le_bytes())
+            .map_err(|e| io_err("tablebase_write", path, e))?;
+    }
+
+    w.flush().map_err(|e| io_err("tablebase_write", path, e))?;
+    Ok(())
+}
+
+/// A memory-mapped, queryable tablebase produced by [`write_tablebase`].
+pub struct Tablebase {
+    mmap: Mmap,
+    bound: i32,
+    allow_captures: bool,
+    piece_count: usize,
+    state_count: usize,
+    keys_offset: usize,
+    wdl_offset: usize,
+    dtm_offset: usize,
+    checkpoints_offset: usize,
+}
+
+const CHECKPOINT_BYTES: usize = 8 + 8;
+
+const HEADER_LEN: usize = 4 + 4 + 4 + 1 + 4 + 8;
+
+impl Tablebase {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SearchError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| io_err("tablebase_open", path, e))?;
+        // SAFETY: the file is treated as read-only, immutable tablebase data for the lifetime of
+        // this mapping; concurrent external writers are not supported.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| io_err("tablebase_open", path, e))?;
+
+        if mmap.len() < HEADER_LEN || &mmap[0..4] != MAGIC {
+            return Err(SearchError::InvalidScenario {
+                reason: "tablebase file missing ICTB magic".to_string(),
+            });
+        }
+        let version = u32::from_le_bytes(mmap[4..8].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(SearchError::InvalidScenario {
+                reason: format!("unsupported tablebase version {version}"),
+            });
+        }
+        let bound = i32::from_le_bytes(mmap[8..12].try_into().unwrap());
+        let allow_captures = mmap[12] != 0;
+        let piece_count = u32::from_le_bytes(mmap[13..17].try_into().unwrap()) as usize;
+        let state_count = u64::from_le_bytes(mmap[17..25].try_into().unwrap()) as usize;
+
+        let keys_offset = HEADER_LEN;
+        let key_bytes = piece_count * 8;
+        let wdl_offset = keys_offset + state_count * key_bytes;
+        let dtm_offset = wdl_offset + state_count.div_ceil(8);
+        // The checkpoint table is the file's last section, and its size (unlike the delta+varint
+        // dtm stream before it) is fixed by `state_count`, so it's addressed from the end of the
+        // file rather than by adding up the preceding variable-length dtm stream's length.
+        let num_checkpoints = state_count.div_ceil(DTM_CHECKPOINT_STRIDE);
+        let checkpoints_offset = mmap.len() - num_checkpoints * CHECKPOINT_BYTES;
+
+        Ok(Self {
+            mmap,
+            bound,
+            allow_captures,
+            piece_count,
+            state_count,
+            keys_offset,
+            wdl_offset,
+            dtm_offset,
+            checkpoints_offset,
+        })
+    }
+
+    pub fn bound(&self) -> i32 {
+        self.bound
+    }
+
+    pub fn allow_captures(&self) -> bool {
+        self.allow_captures
+    }
+
+    pub fn len(&self) -> usize {
+        self.state_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state_count == 0
+    }
+
+    fn key_at(&self, index: usize) -> &[u8] {
+        let key_bytes = self.piece_count * 8;
+        let start = self.keys_offset + index * key_bytes;
+        &self.mmap[start..start + key_bytes]
+    }
+
+    fn key_value_at(&self, index: usize, slot: usize) -> i64 {
+        let key_bytes = self.piece_count * 8;
+        let start = self.keys_offset + index * key_bytes + slot * 8;
+        i64::from_le_bytes(self.mmap[start..start + 8].try_into().unwrap())
+    }
+
+    /// Binary-search for `key` among the stored canonical keys, returning its index.
+    fn find_index(&self, key: &[i64]) -> Option<usize> {
+        let (mut lo, mut hi) = (0usize, self.state_count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let mid_key = self.key_at(mid);
+            let ord = (0..self.piece_count)
+                .map(|i| self.key_value_at(mid, i).cmp(&key[i]))
+                .find(|o| *o != std::cmp::Ordering::Equal)
+                .unwrap_or(std::cmp::Ordering::Equal);
+            let _ = mid_key; // keep the slice borrow alive for documentation purposes
+            match ord {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    /// Read the checkpoint covering `index`: the byte offset into the dtm stream (relative to
+    /// `dtm_offset`) and cumulative value at the start of the `DTM_CHECKPOINT_STRIDE`-aligned
+    /// entry at or before `index`.
+    fn checkpoint_before(&self, index: usize) -> (usize, usize, i64) {
+        let checkpoint_idx = index / DTM_CHECKPOINT_STRIDE;
+        let start = self.checkpoints_offset + checkpoint_idx * CHECKPOINT_BYTES;
+        let offset = u64::from_le_bytes(self.mmap[start..start + 8].try_into().unwrap()) as usize;
+        let value = i64::from_le_bytes(self.mmap[start + 8..start + 16].try_into().unwrap());
+        (checkpoint_idx * DTM_CHECKPOINT_STRIDE, offset, value)
+    }
+
+    fn dtm_at(&self, index: usize) -> u32 {
+        let bytes = &self.mmap[self.dtm_offset..];
+        let (checkpoint_index, checkpoint_offset, checkpoint_value) = self.checkpoint_before(index);
+        let mut pos = checkpoint_offset;
+        let mut value = checkpoint_value;
+        for _ in checkpoint_index..=index {
+            let delta = zigzag_decode(read_varint_u64(bytes, &mut pos));
+            value += delta;
+        }
+        value as u32
+    }
+
+    /// Probe a state's Win/Draw classification and, for wins, its distance-to-mate.
+    pub fn probe(&self, state: &State) -> Option<(Wdl, u32)> {
+        let key = canonical_key(&state.pos);
+        if key.len() != self.piece_count {
+            return None;
+        }
+        let index = self.find_index(&key)?;
+
+        let is_win = self.mmap[self.wdl_offset + index / 8] & (1 << (index % 8)) != 0;
+        if is_win {
+            Some((Wdl::Win, self.dtm_at(index)))
+        } else {
+            Some((Wdl::Draw, 0))
+        }
+    }
+}
+
+/// A convenience helper used by `probe` callers that only track the black king's absolute anchor
+/// implicitly (translation-reduced scenarios). Exposed for symmetry with `State::new`.
+pub fn probe_at(tb: &Tablebase, abs_king: Coord, pos: Position) -> Option<(Wdl, u32)> {
+    tb.probe(&State::new(abs_king, pos))
+}
@@ -10,6 +10,7 @@
 
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::thread;
 
 use rustc_hash::{FxHashMap, FxHashSet};
 
@@ -18,15 +19,24 @@ use crate::core::coord::Coord;
 use crate::scenario::{
     CacheMode, CandidateGeneration, DomainLike, LawsLike, Scenario, SearchError, Side, State,
 };
-use crate::search::movegen::{is_stalemate_with_laws, legal_black_moves, legal_white_moves};
+use crate::search::mates::checkmates_in_bound;
+use crate::search::movegen::{
+    black_move_predecessors, is_stalemate_with_laws, legal_black_moves, legal_white_moves,
+    white_move_predecessors,
+};
 use crate::search::resources::ResourceTracker;
 use crate::search::universe::try_for_each_state_in_abs_box;
 
 /// Cache for move generation during trap pruning.
+///
+/// `CacheMode::ZobristKeyed` uses `black_z`/`white_z` (keyed by [`State::zobrist`]) instead of
+/// `black`/`white`, so lookups don't have to hash or compare the full piece placement.
 #[derive(Default)]
 struct MoveCache {
     black: FxHashMap<State, Arc<[State]>>,
     white: FxHashMap<State, Arc<[State]>>,
+    black_z: FxHashMap<u64, Arc<[State]>>,
+    white_z: FxHashMap<u64, Arc<[State]>>,
 }
 
 impl MoveCache {
@@ -40,6 +50,23 @@ impl MoveCache {
         D: DomainLike,
         L: LawsLike,
     {
+        if matches!(scn.cache_mode, CacheMode::ZobristKeyed) {
+            let key = s.zobrist(&scn.rules.layout);
+            if let Some(v) = self.black_z.get(&key) {
+                return Ok(v.clone());
+            }
+
+            let moves = legal_black_moves(scn, &scn.laws, s, tracker)?;
+            let arc: Arc<[State]> = moves.into();
+
+            self.evict_to_fit(scn, tracker, 1, arc.len())?;
+            tracker.try_reserve_map("cache_black", "black_move_cache", &mut self.black_z, 1)?;
+            tracker.bump_cache_entries("cache_black", 1)?;
+            tracker.bump_cached_moves("cache_black", arc.len())?;
+            self.black_z.insert(key, arc.clone());
+            return Ok(arc);
+        }
+
         let do_cache = matches!(
             scn.cache_mode,
             CacheMode::BlackOnly | CacheMode::BothBounded
@@ -74,6 +101,23 @@ impl MoveCache {
         D: DomainLike,
         L: LawsLike,
     {
+        if matches!(scn.cache_mode, CacheMode::ZobristKeyed) {
+            let key = s.zobrist(&scn.rules.layout);
+            if let Some(v) = self.white_z.get(&key) {
+                return Ok(v.clone());
+            }
+
+            let moves = legal_white_moves(scn, &scn.laws, s, tracker)?;
+            let arc: Arc<[State]> = moves.into();
+
+            self.evict_to_fit(scn, tracker, 1, arc.len())?;
+            tracker.try_reserve_map("cache_white", "white_move_cache", &mut self.white_z, 1)?;
+            tracker.bump_cache_entries("cache_white", 1)?;
+            tracker.bump_cached_moves("cache_white", arc.len())?;
+            self.white_z.insert(key, arc.clone());
+            return Ok(arc);
+        }
+
         let do_cache = matches!(scn.cache_mode, CacheMode::BothBounded);
         if do_cache {
             if let Some(v) = self.white.get(s) {
@@ -173,6 +217,18 @@ impl MoveCache {
             tracker.dec_cached_moves(v_len);
             return true;
         }
+        if let Some((k, v_len)) = self.black_z.iter().next().map(|(&k, v)| (k, v.len())) {
+            self.black_z.remove(&k);
+            tracker.dec_cache_entries(1);
+            tracker.dec_cached_moves(v_len);
+            return true;
+        }
+        if let Some((k, v_len)) = self.white_z.iter().next().map(|(&k, v)| (k, v.len())) {
+            self.white_z.remove(&k);
+            tracker.dec_cache_entries(1);
+            tracker.dec_cached_moves(v_len);
+            return true;
+        }
         false
     }
 }
@@ -180,46 +236,247 @@ impl MoveCache {
 /// Compute the maximal inescapable trap inside the scenario's domain.
 ///
 /// The returned set is a set of **black-to-move** states inside the domain.
+///
+/// This is a counter-based backward attractor (the classic dataflow-worklist approach) over the
+/// bipartite game graph rather than a repeated full rescan of `trap`: `p` (black-to-move) moves to
+/// `w` (white-to-move, an "after black" state), and `w`'s white replies land on further black
+/// states `q`. Tracking `cnt[w]` = the number of `w`'s replies currently in `trap` lets a single
+/// removal decrement exactly the `w`s it affects and re-examine only their black predecessors,
+/// instead of rescanning every surviving candidate on every round.
+///
+/// Opt in to [`maximal_inescapable_trap_parallel`]'s round-based fixed point instead via
+/// [`ResourceLimits::parallel_trap`](crate::scenario::ResourceLimits), which is faster on large
+/// universes (worth the thread setup and per-round shard bookkeeping once the candidate count is
+/// large enough) despite iterating to the same result — see that function's doc comment for why
+/// the two agree. The sequential algorithm above stays the default so small demos don't pay for
+/// threads they don't need.
 pub fn maximal_inescapable_trap<D, L, P>(
     scn: &Scenario<D, L, P>,
 ) -> Result<FxHashSet<State>, SearchError>
 where
-    D: DomainLike,
-    L: LawsLike,
+    D: DomainLike + Sync,
+    L: LawsLike + Sync,
+    P: Sync,
 {
     scn.validate()?;
     let mut tracker = ResourceTracker::new(scn.limits);
 
     let mut trap = initial_candidate_set(scn, &mut tracker)?;
 
+    if scn.limits.parallel_trap {
+        let available_threads = thread::available_parallelism().map_or(1, |n| n.get());
+        return parallel_fixed_point(scn, trap, available_threads, &mut tracker);
+    }
+
     let mut cache = MoveCache::default();
 
-    loop {
-        tracker.bump_steps("trap_prune_iter", 1)?;
+    // `cnt[w]`: number of white replies of `w` currently in `trap`.
+    let mut cnt: FxHashMap<State, usize> = FxHashMap::default();
+    // `preds_white[q]`: the `w`s with a white reply landing on `q` (only tracked for `q ∈ trap`,
+    // since only trap members are ever removed and need to trigger a decrement).
+    let mut preds_white: FxHashMap<State, Vec<State>> = FxHashMap::default();
+    // `blacks_through[w]`: the black `p`s with a black move into `w`.
+    let mut blacks_through: FxHashMap<State, Vec<State>> = FxHashMap::default();
+
+    for p in trap.iter() {
+        tracker.bump_steps("trap_prune_build_index", 1)?;
+
+        let black_moves = cache.black_moves(scn, &mut tracker, p)?;
+        for w in black_moves.iter() {
+            if !blacks_through.contains_key(w) {
+                tracker.try_reserve_map(
+                    "trap_prune_index",
+                    "blacks_through",
+                    &mut blacks_through,
+                    1,
+                )?;
+            }
+            blacks_through.entry(w.clone()).or_default().push(p.clone());
 
-        let mut to_remove: Vec<State> = Vec::new();
+            if cnt.contains_key(w) {
+                continue;
+            }
 
-        for p in trap.iter() {
-            tracker.bump_steps("trap_prune_scan", 1)?;
+            let white_moves = cache.white_moves(scn, &mut tracker, w)?;
+            let mut in_trap = 0usize;
+            for q in white_moves.iter() {
+                if trap.contains(q) {
+                    in_trap += 1;
+                    if !preds_white.contains_key(q) {
+                        tracker.try_reserve_map(
+                            "trap_prune_index",
+                            "preds_white",
+                            &mut preds_white,
+                            1,
+                        )?;
+                    }
+                    preds_white.entry(q.clone()).or_default().push(w.clone());
+                }
+            }
 
-            // If black has a move to a position from which every white reply exits the current set,
-            // then `p` cannot be in an inescapable trap.
-            let black_moves = cache.black_moves(scn, &mut tracker, p)?;
+            tracker.try_reserve_map("trap_prune_index", "cnt", &mut cnt, 1)?;
+            cnt.insert(w.clone(), in_trap);
+        }
+    }
 
-            let mut fails = false;
-            for after_black in black_moves.iter() {
-                let white_moves = cache.white_moves(scn, &mut tracker, after_black)?;
-                let has_reply_in_trap = white_moves.iter().any(|q| trap.contains(q));
-                if !has_reply_in_trap {
-                    fails = true;
-                    break;
+    // Seed the removal worklist with every black `p` whose only hope, some `w`, already has no
+    // reply left inside `trap`.
+    let mut worklist: VecDeque<State> = VecDeque::new();
+    let mut queued: FxHashSet<State> = FxHashSet::default();
+    for (w, &c) in cnt.iter() {
+        if c != 0 {
+            continue;
+        }
+        if let Some(ps) = blacks_through.get(w) {
+            for p in ps {
+                if queued.insert(p.clone()) {
+                    worklist.push_back(p.clone());
                 }
             }
+        }
+    }
 
-            if fails {
-                to_remove.push(p.clone());
+    while let Some(p) = worklist.pop_front() {
+        tracker.bump_steps("trap_prune_worklist", 1)?;
+        queued.remove(&p);
+
+        if !trap.remove(&p) {
+            // Already removed via another path into the worklist.
+            continue;
+        }
+
+        let Some(ws) = preds_white.get(&p) else {
+            continue;
+        };
+        for w in ws {
+            let Some(c) = cnt.get_mut(w) else {
+                continue;
+            };
+            if *c == 0 {
+                continue;
+            }
+            *c -= 1;
+            if *c != 0 {
+                continue;
             }
+
+            if let Some(ps) = blacks_through.get(w) {
+                for p2 in ps {
+                    if trap.contains(p2) && queued.insert(p2.clone()) {
+                        worklist.push_back(p2.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(trap)
+}
+
+/// Parallel variant of [`maximal_inescapable_trap`].
+///
+/// The fixed-point iteration is a monotone worklist over `trap`: each round's membership test for
+/// a candidate `p` only reads `trap` as it stood at the start of the round, so the per-candidate
+/// evaluations within a round are independent and can run on separate threads. `num_threads` is
+/// clamped to at least 1; candidates are split into that many contiguous shards per round, and the
+/// round's removals are merged back in before the next round starts (this sequential round
+/// boundary is what keeps the "greatest fixed point" semantics identical to the single-threaded
+/// version — only the scan *within* a round is parallelized, not the fixed point itself).
+///
+/// Unlike [`maximal_inescapable_trap`], this does not share a [`MoveCache`] across threads (it
+/// isn't safe to mutate from multiple threads without its own locking, which would defeat the
+/// point); each shard regenerates moves directly. Budget accounting is summed across shards once
+/// per round rather than checked after every single move generation, so a round may briefly
+/// overshoot `ResourceLimits` before the excess is reported.
+pub fn maximal_inescapable_trap_parallel<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    num_threads: usize,
+) -> Result<FxHashSet<State>, SearchError>
+where
+    D: DomainLike + Sync,
+    L: LawsLike + Sync,
+    P: Sync,
+{
+    scn.validate()?;
+    let mut tracker = ResourceTracker::new(scn.limits);
+    let trap = initial_candidate_set(scn, &mut tracker)?;
+    parallel_fixed_point(scn, trap, num_threads.max(1), &mut tracker)
+}
+
+/// Shared round-based fixed-point loop behind both [`maximal_inescapable_trap_parallel`] and
+/// [`maximal_inescapable_trap`]'s opt-in `parallel_trap` path, over an already-built `trap` candidate
+/// set so the latter doesn't pay to build it twice.
+fn parallel_fixed_point<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    mut trap: FxHashSet<State>,
+    num_threads: usize,
+    tracker: &mut ResourceTracker,
+) -> Result<FxHashSet<State>, SearchError>
+where
+    D: DomainLike + Sync,
+    L: LawsLike + Sync,
+    P: Sync,
+{
+    loop {
+        tracker.bump_steps("trap_prune_iter_parallel", 1)?;
+
+        let snapshot: Vec<State> = trap.iter().cloned().collect();
+        let shards = shard_contiguous(&snapshot, num_threads);
+
+        let mut round_steps: u64 = 0;
+        let mut to_remove: Vec<State> = Vec::new();
+
+        let trap_ref = &trap;
+        let results: Vec<Result<(Vec<State>, u64), SearchError>> = thread::scope(|scope| {
+            let handles: Vec<_> = shards
+                .into_iter()
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut local_tracker = ResourceTracker::new(scn.limits);
+                        let mut removed = Vec::new();
+                        for p in &chunk {
+                            local_tracker.bump_steps("trap_prune_scan_parallel", 1)?;
+
+                            let black_moves =
+                                legal_black_moves(scn, &scn.laws, p, &mut local_tracker)?;
+
+                            let mut fails = false;
+                            for after_black in black_moves.iter() {
+                                let white_moves = legal_white_moves(
+                                    scn,
+                                    &scn.laws,
+                                    after_black,
+                                    &mut local_tracker,
+                                )?;
+                                let has_reply_in_trap =
+                                    white_moves.iter().any(|q| trap_ref.contains(q));
+                                if !has_reply_in_trap {
+                                    fails = true;
+                                    break;
+                                }
+                            }
+
+                            if fails {
+                                removed.push(p.clone());
+                            }
+                        }
+                        Ok((removed, local_tracker.counts().runtime_steps))
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("trap shard thread panicked"))
+                .collect()
+        });
+
+        for result in results {
+            let (removed, steps) = result?;
+            round_steps += steps;
+            to_remove.extend(removed);
         }
+        tracker.bump_steps("trap_prune_scan_parallel", round_steps)?;
 
         if to_remove.is_empty() {
             break;
@@ -233,6 +490,16 @@ where
     Ok(trap)
 }
 
+/// Split `items` into at most `num_shards` contiguous, roughly equal-sized chunks.
+fn shard_contiguous<T: Clone>(items: &[T], num_shards: usize) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let num_shards = num_shards.min(items.len()).max(1);
+    let chunk_len = items.len().div_ceil(num_shards);
+    items.chunks(chunk_len).map(|c| c.to_vec()).collect()
+}
+
 /// Compute the maximal *tempo* trap inside an already-computed inescapable trap.
 ///
 /// A tempo trap is a Büchi objective: White must be able to stay inside the inescapable trap
@@ -301,7 +568,7 @@ where
                 });
             }
 
-            try_for_each_state_in_abs_box(&scn.rules.layout, *bound, *allow_captures, |s| {
+            try_for_each_state_in_abs_box(&scn.rules, *bound, *allow_captures, |s| {
                 if !scn.rules.is_legal_position(&s.pos) {
                     return Ok(());
                 }
@@ -398,6 +665,34 @@ where
                 }
             }
         }
+
+        CandidateGeneration::BackwardFromMates { bound, max_queue } => {
+            if *max_queue == 0 {
+                return Err(SearchError::InvalidScenario {
+                    reason: "BackwardFromMates requires max_queue > 0".to_string(),
+                });
+            }
+
+            let mut q: VecDeque<State> = VecDeque::new();
+
+            for pos in checkmates_in_bound(&scn.rules, *bound) {
+                let s = State {
+                    abs_king: Coord::ORIGIN,
+                    pos,
+                };
+                try_add_backward(scn, tracker, *max_queue, &mut trap, &mut q, s)?;
+            }
+
+            while let Some(p) = q.pop_front() {
+                tracker.bump_steps("candidates_backward_scan", 1)?;
+
+                for w in white_move_predecessors(scn, &scn.laws, &p) {
+                    for p_prev in black_move_predecessors(scn, &scn.laws, &w) {
+                        try_add_backward(scn, tracker, *max_queue, &mut trap, &mut q, p_prev)?;
+                    }
+                }
+            }
+        }
     }
 
     Ok(trap)
@@ -441,3 +736,45 @@ where
 
     Ok(())
 }
+
+/// Like [`try_add_reachable_b`], but for [`CandidateGeneration::BackwardFromMates`]'s backward
+/// breadth-first search: `p` is a black-to-move state discovered as a predecessor (either a
+/// checkmate seed or an un-move of an already-accepted candidate) rather than a forward successor.
+fn try_add_backward<D, L, P>(
+    scn: &Scenario<D, L, P>,
+    tracker: &mut ResourceTracker,
+    max_queue: usize,
+    trap: &mut FxHashSet<State>,
+    q: &mut VecDeque<State>,
+    p: State,
+) -> Result<(), SearchError>
+where
+    D: DomainLike,
+    L: LawsLike,
+{
+    if !scn.laws.allow_state(&p) {
+        return Ok(());
+    }
+    if !scn.domain.inside(&p) {
+        return Ok(());
+    }
+    if scn.remove_stalemates && is_stalemate_with_laws(scn, &scn.laws, &p, tracker)? {
+        return Ok(());
+    }
+
+    if trap.insert(p.clone()) {
+        tracker.bump_states("candidates_backward", 1)?;
+        if q.len() >= max_queue {
+            return Err(SearchError::LimitExceeded {
+                stage: "candidates_backward",
+                metric: "queue",
+                limit: max_queue as u64,
+                observed: (q.len() + 1) as u64,
+                counts: tracker.counts(),
+            });
+        }
+        q.push_back(p);
+    }
+
+    Ok(())
+}
@@ -3,8 +3,7 @@
 //! The core representation stores white pieces in king-relative coordinates, but some experiments
 //! need an *absolute* bounding box for both the king anchor and all pieces.
 
-use crate::chess::layout::PieceLayout;
-use crate::chess::piece::PieceKind;
+use crate::chess::rules::Rules;
 use crate::core::coord::Coord;
 use crate::core::position::{Position, MAX_PIECES};
 use crate::core::square::Square;
@@ -18,26 +17,45 @@ use crate::scenario::State;
 ///
 /// If `allow_captures` is true, each piece may also be absent (`Square::NONE`).
 pub fn for_each_state_in_abs_box(
-    layout: &PieceLayout,
+    rules: &Rules,
     bound: i32,
     allow_captures: bool,
     mut f: impl FnMut(State),
 ) {
     // A wrapper that cannot fail.
-    try_for_each_state_in_abs_box(layout, bound, allow_captures, |s| {
+    try_for_each_state_in_abs_box(rules, bound, allow_captures, |s| {
         f(s);
         Ok(())
     })
     .unwrap_or_else(|never: std::convert::Infallible| match never {});
 }
 
+/// Like [`try_for_each_state_in_abs_box`], but folds each yielded state under the 8 D4 board
+/// symmetries (see [`crate::core::position::Position::canonicalize_d4`]) before invoking `f`,
+/// collapsing symmetric black-to-move placements into a single representative.
+///
+/// Only sound when `layout.is_direction_free()`; callers must gate on that themselves (e.g. via
+/// `ScenarioConfig::effective_symmetry_reduction`) so pawn layouts opt out.
+pub fn try_for_each_state_in_abs_box_symmetric<E>(
+    rules: &Rules,
+    bound: i32,
+    allow_captures: bool,
+    mut f: impl FnMut(State) -> Result<(), E>,
+) -> Result<(), E> {
+    try_for_each_state_in_abs_box(rules, bound, allow_captures, |mut s| {
+        s.pos.canonicalize_d4(&rules.layout);
+        f(s)
+    })
+}
+
 /// Like [`for_each_state_in_abs_box`], but allows early exit via a fallible callback.
 pub fn try_for_each_state_in_abs_box<E>(
-    layout: &PieceLayout,
+    rules: &Rules,
     bound: i32,
     allow_captures: bool,
     mut f: impl FnMut(State) -> Result<(), E>,
 ) -> Result<(), E> {
+    let layout = &rules.layout;
     assert!(bound >= 0);
     assert!(layout.piece_count() <= MAX_PIECES);
 
@@ -58,7 +76,6 @@ pub fn try_for_each_state_in_abs_box<E>(
     fn choose_k<E>(
         abs_squares: &[Square],
         used: &mut [bool],
-        allowed: impl Fn(usize) -> bool + Copy,
         start: usize,
         k: usize,
         chosen: &mut Vec<usize>,
@@ -68,11 +85,11 @@ pub fn try_for_each_state_in_abs_box<E>(
             return f(chosen, used);
         }
         for i in start..abs_squares.len() {
-            if used[i] || !allowed(i) {
+            if used[i] {
                 continue;
             }
             chosen.push(i);
-            choose_k(abs_squares, used, allowed, i + 1, k, chosen, f)?;
+            choose_k(abs_squares, used, i + 1, k, chosen, f)?;
             chosen.pop();
         }
         Ok(())
@@ -82,12 +99,13 @@ pub fn try_for_each_state_in_abs_box<E>(
         group_idx: usize,
         abs_squares: &[Square],
         used: &mut [bool],
-        layout: &PieceLayout,
+        rules: &Rules,
         abs_king: Coord,
         allow_captures: bool,
         cur_abs: &mut [Square; MAX_PIECES],
         f: &mut dyn FnMut(State) -> Result<(), E>,
     ) -> Result<(), E> {
+        let layout = &rules.layout;
         if group_idx == layout.identical_runs().len() {
             let mut cur_rel = [Square::NONE; MAX_PIECES];
             for i in 0..layout.piece_count() {
@@ -101,24 +119,18 @@ pub fn try_for_each_state_in_abs_box<E>(
             let mut pos = Position::new(layout.piece_count(), cur_rel);
             // Cur is constructed to already be canonical, but keep this call as an invariant check.
             pos.canonicalize(layout);
+            // Overlaps and the king square are already excluded by `used`; this also rejects
+            // placements where the white king ends up adjacent to the black king.
+            if rules.validate_position(&pos).is_err() {
+                return Ok(());
+            }
             f(State::new(abs_king, pos))?;
             return Ok(());
         }
 
         let run = &layout.identical_runs()[group_idx];
-        let kind = layout.kind(run.start);
         let len = run.end - run.start;
 
-        // Special legality for the white king: cannot be adjacent to the black king.
-        let allowed_square = |idx: usize| -> bool {
-            if kind == PieceKind::King {
-                let rel = abs_squares[idx].coord() - abs_king;
-                rel.chebyshev_norm() > 1
-            } else {
-                true
-            }
-        };
-
         let min_k = if allow_captures { 0 } else { len };
         let max_k = len;
         let mut chosen: Vec<usize> = Vec::new();
@@ -141,7 +153,7 @@ pub fn try_for_each_state_in_abs_box<E>(
                     group_idx + 1,
                     abs_squares,
                     used,
-                    layout,
+                    rules,
                     abs_king,
                     allow_captures,
                     cur_abs,
@@ -154,15 +166,7 @@ pub fn try_for_each_state_in_abs_box<E>(
                 Ok(())
             };
 
-            choose_k(
-                abs_squares,
-                used,
-                allowed_square,
-                0,
-                k,
-                &mut chosen,
-                &mut callback,
-            )?;
+            choose_k(abs_squares, used, 0, k, &mut chosen, &mut callback)?;
         }
         Ok(())
     }
@@ -181,7 +185,7 @@ pub fn try_for_each_state_in_abs_box<E>(
                 0,
                 &abs_squares,
                 &mut used,
-                layout,
+                rules,
                 abs_king,
                 allow_captures,
                 &mut cur_abs,
@@ -8,12 +8,12 @@
 //!
 //! See `src/bin/export_solution.rs` and `src/bin/play_solution.rs` for the user-facing tools.
 
-use std::collections::BTreeMap;
 use std::fs;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use memmap2::Mmap;
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 
@@ -33,7 +33,21 @@ use crate::search::trap::maximal_inescapable_trap;
 const FORMAT_VERSION: u32 = 1;
 const MANIFEST_FILENAME: &str = "manifest.json";
 const DATA_FILENAME: &str = "data.bin";
-const DATA_MAGIC: &[u8; 8] = b"ICHSOL01";
+/// 4-byte magic at the front of `data.bin`'s fixed header (see [`write_data`]).
+const DATA_MAGIC: &[u8; 4] = b"ICSB";
+/// FNV-1a offset basis, used for `data.bin`'s trailing content hash (see [`Fnv1aWriter`]).
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+/// FNV-1a prime, used for `data.bin`'s trailing content hash (see [`Fnv1aWriter`]).
+const FNV_PRIME: u64 = 0x100000001b3;
+/// Bit 0 of the data-file header's reserved/flags word: when set, the payload is LEB128-varint
+/// encoded (see [`write_int_u32`]/[`write_int_i32`]/[`write_int_i64`]) and `trap_set_ids`/
+/// `tempo_set_ids` are sorted and delta-encoded, instead of the fixed-width encoding every earlier
+/// bundle version used.
+const FLAG_VARINT: u32 = 1 << 0;
+/// Byte length of `data.bin`'s fixed header (see [`write_data`]).
+const DATA_HEADER_LEN: u64 = 16;
+/// Byte length of `data.bin`'s trailing content-hash copy (see [`write_data`]).
+const DATA_TRAILER_LEN: u64 = 8;
 
 #[derive(Debug, Clone, Copy)]
 pub struct ExportOptions {
@@ -43,6 +57,10 @@ pub struct ExportOptions {
     pub compute_tempo: bool,
     /// Override the recommended relative view bound stored in the manifest.
     pub view_bound: Option<i32>,
+    /// Write `data.bin`'s payload as LEB128 varints (with sorted-delta id sequences) instead of
+    /// the fixed-width encoding. Shrinks typical bundles substantially; [`load_bundle`] dispatches
+    /// on the header flag either way, but [`MappedBundle::open`] rejects compressed bundles.
+    pub compress: bool,
 }
 
 impl Default for ExportOptions {
@@ -51,10 +69,107 @@ impl Default for ExportOptions {
             force: false,
             compute_tempo: true,
             view_bound: None,
+            compress: true,
         }
     }
 }
 
+/// The labeled set of relative move deltas a bundle's `transitions` are indexed against.
+///
+/// Every bundle this crate currently produces uses [`Self::king_steps`], since
+/// `Rules::black_moves_with_delta` only ever generates king steps, but
+/// [`SolutionData::transitions`]/[`LoadedSolution::transitions`] are sized to `alphabet.len()`
+/// rather than hardcoded to 8, so a future exporter for a different tracked piece only needs to
+/// build a different `MoveAlphabet` — the bundle format and the interactive CLI already follow
+/// whatever alphabet the manifest declares.
+#[derive(Debug, Clone)]
+pub struct MoveAlphabet {
+    deltas: Vec<Coord>,
+    labels: Vec<char>,
+}
+
+impl MoveAlphabet {
+    /// The 8 king-step directions with their existing q/w/e/a/d/z/x/c key labels.
+    pub fn king_steps() -> Self {
+        Self {
+            deltas: vec![
+                Coord::new(-1, 1),
+                Coord::new(0, 1),
+                Coord::new(1, 1),
+                Coord::new(-1, 0),
+                Coord::new(1, 0),
+                Coord::new(-1, -1),
+                Coord::new(0, -1),
+                Coord::new(1, -1),
+            ],
+            labels: vec!['q', 'w', 'e', 'a', 'd', 'z', 'x', 'c'],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// The transition-array slot for `delta`, or `None` if it isn't one of this alphabet's moves.
+    pub fn index_of(&self, delta: Coord) -> Option<usize> {
+        self.deltas.iter().position(|&d| d == delta)
+    }
+
+    /// The transition-array slot bound to key label `ch`, or `None` if `ch` isn't in this alphabet.
+    pub fn index_of_label(&self, ch: char) -> Option<usize> {
+        self.labels.iter().position(|&l| l == ch)
+    }
+
+    pub fn delta_at(&self, idx: usize) -> Option<Coord> {
+        self.deltas.get(idx).copied()
+    }
+
+    pub fn label_at(&self, idx: usize) -> Option<char> {
+        self.labels.get(idx).copied()
+    }
+
+    /// `(key, delta)` pairs in alphabet order, for building help text and key/delta lookup maps.
+    pub fn entries(&self) -> impl Iterator<Item = (char, Coord)> + '_ {
+        self.labels.iter().copied().zip(self.deltas.iter().copied())
+    }
+
+    fn to_manifest(&self) -> MoveAlphabetManifest {
+        MoveAlphabetManifest {
+            deltas: self.deltas.iter().map(|d| (d.x, d.y)).collect(),
+            labels: self.labels.clone(),
+        }
+    }
+
+    fn from_manifest(m: &MoveAlphabetManifest) -> Result<Self, SearchError> {
+        if m.deltas.len() != m.labels.len() {
+            return Err(SearchError::InvalidScenario {
+                reason: format!(
+                    "solution manifest move_alphabet has {} deltas but {} labels",
+                    m.deltas.len(),
+                    m.labels.len()
+                ),
+            });
+        }
+        Ok(Self {
+            deltas: m.deltas.iter().map(|&(x, y)| Coord::new(x, y)).collect(),
+            labels: m.labels.clone(),
+        })
+    }
+}
+
+/// JSON-serializable form of [`MoveAlphabet`], stored as [`SolutionManifest::move_alphabet`].
+/// `Coord` has no `Serialize`/`Deserialize` derive of its own, so deltas round-trip as plain
+/// `(x, y)` tuples here instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveAlphabetManifest {
+    pub deltas: Vec<(i32, i32)>,
+    pub labels: Vec<char>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolutionManifest {
     pub format_version: u32,
@@ -66,6 +181,7 @@ pub struct SolutionManifest {
     pub view: ViewManifest,
     pub counts: CountsManifest,
     pub files: FilesManifest,
+    pub move_alphabet: MoveAlphabetManifest,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +226,9 @@ pub struct CountsManifest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilesManifest {
     pub data_bin: String,
+    /// FNV-1a hash of `data.bin`'s payload bytes (everything between the fixed header and the
+    /// trailing hash written into the file itself), recomputed and checked by [`verify_bundle`].
+    pub content_hash: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -130,7 +249,7 @@ pub struct SolutionData {
     pub states: Vec<State>,
     pub trap_set_ids: Vec<u32>,
     pub tempo_set_ids: Vec<u32>,
-    pub transitions: Vec<(u32, [u32; 8])>,
+    pub transitions: Vec<(u32, Vec<u32>)>,
     pub strategy_trap: Vec<(u32, u32)>,
     pub strategy_tempo: Vec<(u32, u32)>,
 }
@@ -146,11 +265,12 @@ pub struct SolutionBundle {
 pub struct LoadedSolution {
     pub manifest: SolutionManifest,
     pub rules: Rules,
+    pub move_alphabet: MoveAlphabet,
     pub states: Vec<State>,
     pub id_of: FxHashMap<State, u32>,
     pub trap_ids: FxHashSet<u32>,
     pub tempo_ids: FxHashSet<u32>,
-    pub transitions: Vec<[u32; 8]>,
+    pub transitions: Vec<Vec<u32>>,
     pub strat_trap: FxHashMap<u32, u32>,
     pub strat_tempo: FxHashMap<u32, u32>,
 }
@@ -161,9 +281,9 @@ pub fn export_bundle<D, L, P>(
     options: ExportOptions,
 ) -> Result<SolutionBundle, SearchError>
 where
-    D: DomainLike,
-    L: LawsLike,
-    P: PreferencesLike,
+    D: DomainLike + Sync,
+    L: LawsLike + Sync,
+    P: PreferencesLike + Sync,
 {
     scn.validate()?;
     if scn.start.to_move != Side::Black {
@@ -215,11 +335,13 @@ where
         trap_set_ids.push(id);
     }
 
+    let alphabet = MoveAlphabet::king_steps();
+
     // Build deterministic transitions for every trap black node.
-    let mut transitions: Vec<(u32, [u32; 8])> = Vec::with_capacity(trap_set_ids.len());
+    let mut transitions: Vec<(u32, Vec<u32>)> = Vec::with_capacity(trap_set_ids.len());
     for b in trap.iter() {
         let b_id = *id_of.get(b).expect("trap states were interned");
-        let mut next = [u32::MAX; 8];
+        let mut next = vec![u32::MAX; alphabet.len()];
 
         for (delta, pos2) in scn.rules.black_moves_with_delta(&b.pos) {
             let to = State {
@@ -238,7 +360,7 @@ where
                 continue;
             }
 
-            let Some(dir) = dir_index(delta) else {
+            let Some(dir) = alphabet.index_of(delta) else {
                 continue;
             };
 
@@ -286,6 +408,25 @@ where
         tempo_strategy: to_u32_len(strategy_tempo.len(), "manifest_counts_tempo_strategy")?,
     };
 
+    let data = SolutionData {
+        states,
+        trap_set_ids,
+        tempo_set_ids,
+        transitions,
+        strategy_trap,
+        strategy_tempo,
+    };
+
+    let piece_count = piece_count_from_manifest(&rules_manifest)? as u32;
+    let content_hash = write_data(
+        out_dir,
+        DATA_FILENAME,
+        FORMAT_VERSION,
+        piece_count,
+        options.compress,
+        &data,
+    )?;
+
     let manifest = SolutionManifest {
         format_version: FORMAT_VERSION,
         created_unix_secs,
@@ -303,20 +444,12 @@ where
         counts,
         files: FilesManifest {
             data_bin: DATA_FILENAME.to_string(),
+            content_hash,
         },
-    };
-
-    let data = SolutionData {
-        states,
-        trap_set_ids,
-        tempo_set_ids,
-        transitions,
-        strategy_trap,
-        strategy_tempo,
+        move_alphabet: alphabet.to_manifest(),
     };
 
     write_manifest(out_dir, &manifest)?;
-    write_data(out_dir, &manifest, &data)?;
 
     Ok(SolutionBundle { manifest, data })
 }
@@ -333,10 +466,13 @@ pub fn load_bundle(bundle_dir: &Path) -> Result<LoadedSolution, SearchError> {
         });
     }
 
+    verify_bundle(bundle_dir)?;
+
     let rules = rules_from_manifest(&manifest.rules)?;
     let piece_count = rules.layout.piece_count();
+    let move_alphabet = MoveAlphabet::from_manifest(&manifest.move_alphabet)?;
 
-    let data = read_data(bundle_dir, piece_count)?;
+    let data = read_data(bundle_dir, piece_count, move_alphabet.len())?;
 
     // Build indices for fast access.
     let mut id_of: FxHashMap<State, u32> = FxHashMap::default();
@@ -351,7 +487,8 @@ pub fn load_bundle(bundle_dir: &Path) -> Result<LoadedSolution, SearchError> {
     let trap_ids: FxHashSet<u32> = data.trap_set_ids.iter().copied().collect();
     let tempo_ids: FxHashSet<u32> = data.tempo_set_ids.iter().copied().collect();
 
-    let mut transitions: Vec<[u32; 8]> = vec![[u32::MAX; 8]; data.states.len()];
+    let mut transitions: Vec<Vec<u32>> =
+        vec![vec![u32::MAX; move_alphabet.len()]; data.states.len()];
     for (state_id, next) in data.transitions.into_iter() {
         let idx = usize::try_from(state_id).map_err(|_| SearchError::InvalidScenario {
             reason: "transition state_id does not fit usize".to_string(),
@@ -370,6 +507,7 @@ pub fn load_bundle(bundle_dir: &Path) -> Result<LoadedSolution, SearchError> {
     Ok(LoadedSolution {
         manifest,
         rules,
+        move_alphabet,
         states: data.states,
         id_of,
         trap_ids,
@@ -438,12 +576,67 @@ fn read_manifest(bundle_dir: &Path) -> Result<SolutionManifest, SearchError> {
     })
 }
 
+/// Write adapter that accumulates a streaming FNV-1a hash of every byte passed through it, so
+/// [`write_data`] gets `data.bin`'s content hash for free while it writes the payload, without a
+/// second pass over the bytes. Also tracks how many payload bytes have been written so far (i.e.
+/// the byte offset of the next write, relative to the end of the fixed header), which
+/// [`write_data`] uses to record each transition record's absolute file offset for
+/// [`MappedBundle`].
+struct Fnv1aWriter<W> {
+    inner: W,
+    hash: u64,
+    pos: u64,
+}
+
+impl<W: Write> Fnv1aWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hash: FNV_OFFSET_BASIS,
+            pos: 0,
+        }
+    }
+}
+
+impl<W: Write> Write for Fnv1aWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        for &byte in &buf[..n] {
+            self.hash ^= byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write `data` to `out_dir/data_filename`, returning the FNV-1a hash of its payload bytes (for
+/// [`FilesManifest::content_hash`]).
+///
+/// Layout: a fixed 16-byte header (4-byte [`DATA_MAGIC`], `u32` `format_version`, a reserved/flags
+/// `u32` with [`FLAG_VARINT`] set when `compress` is true, and a `u32` record count), then the
+/// payload (piece count, states, trap/tempo id sets, transitions, strategies — unchanged in
+/// meaning from before the header existed, just varint-packed when `compress` is set). When
+/// `compress` is false, the payload ends with a dense table of `states.len()` absolute file
+/// offsets (one per state id, `u64::MAX` for states with no transition record), letting
+/// [`MappedBundle`] find a state's transition record without scanning; varint bundles omit this
+/// table since their records aren't fixed-size and can't be addressed by a computed offset.
+/// Finally, an 8-byte trailing copy of the content hash. The trailing copy lets a reader with only
+/// `data.bin` (no manifest) still detect truncation or corruption; [`verify_bundle`] additionally
+/// checks it against the manifest's copy.
 fn write_data(
     out_dir: &Path,
-    manifest: &SolutionManifest,
+    data_filename: &str,
+    format_version: u32,
+    piece_count: u32,
+    compress: bool,
     data: &SolutionData,
-) -> Result<(), SearchError> {
-    let path = out_dir.join(&manifest.files.data_bin);
+) -> Result<u64, SearchError> {
+    let path = out_dir.join(data_filename);
     let f = fs::File::create(&path).map_err(|e| SearchError::Io {
         stage: "solution_export_data_create",
         path: path.display().to_string(),
@@ -451,104 +644,152 @@ fn write_data(
     })?;
     let mut w = BufWriter::new(f);
 
-    let piece_count = piece_count_from_manifest(&manifest.rules)? as u32;
+    let record_count = to_u32_len(data.states.len(), "solution_export_states_len")?;
+    let flags = if compress { FLAG_VARINT } else { 0 };
 
     w.write_all(DATA_MAGIC).map_err(|e| SearchError::Io {
         stage: "solution_export_data_write",
         path: path.display().to_string(),
         error: e.to_string(),
     })?;
-    write_u32(
-        &mut w,
-        manifest.format_version,
-        "solution_export_data_write",
-        &path,
-    )?;
-    write_u32(&mut w, piece_count, "solution_export_data_write", &path)?;
+    write_u32(&mut w, format_version, "solution_export_data_write", &path)?;
+    write_u32(&mut w, flags, "solution_export_data_write", &path)?;
+    write_u32(&mut w, record_count, "solution_export_data_write", &path)?;
 
-    write_u32(
-        &mut w,
-        to_u32_len(data.states.len(), "solution_export_states_len")?,
-        "solution_export_data_write",
-        &path,
-    )?;
+    let mut hw = Fnv1aWriter::new(w);
+    const STAGE: &str = "solution_export_data_write";
+
+    write_int_u32(&mut hw, piece_count, compress, STAGE, &path)?;
+
+    write_int_u32(&mut hw, record_count, compress, STAGE, &path)?;
     for s in data.states.iter() {
-        write_i32(&mut w, s.abs_king.x, "solution_export_data_write", &path)?;
-        write_i32(&mut w, s.abs_king.y, "solution_export_data_write", &path)?;
+        write_int_i32(&mut hw, s.abs_king.x, compress, STAGE, &path)?;
+        write_int_i32(&mut hw, s.abs_king.y, compress, STAGE, &path)?;
         for &sq in s.pos.squares().iter() {
-            write_i64(&mut w, sq.raw(), "solution_export_data_write", &path)?;
+            write_int_i64(&mut hw, sq.raw(), compress, STAGE, &path)?;
         }
     }
 
-    write_u32(
-        &mut w,
-        to_u32_len(data.trap_set_ids.len(), "solution_export_trap_len")?,
-        "solution_export_data_write",
+    write_sorted_ids(
+        &mut hw,
+        &data.trap_set_ids,
+        compress,
+        STAGE,
         &path,
+        "solution_export_trap_len",
     )?;
-    for &id in data.trap_set_ids.iter() {
-        write_u32(&mut w, id, "solution_export_data_write", &path)?;
-    }
-
-    write_u32(
-        &mut w,
-        to_u32_len(data.tempo_set_ids.len(), "solution_export_tempo_len")?,
-        "solution_export_data_write",
+    write_sorted_ids(
+        &mut hw,
+        &data.tempo_set_ids,
+        compress,
+        STAGE,
         &path,
+        "solution_export_tempo_len",
     )?;
-    for &id in data.tempo_set_ids.iter() {
-        write_u32(&mut w, id, "solution_export_data_write", &path)?;
-    }
 
-    write_u32(
-        &mut w,
+    write_int_u32(
+        &mut hw,
         to_u32_len(data.transitions.len(), "solution_export_transitions_len")?,
-        "solution_export_data_write",
+        compress,
+        STAGE,
         &path,
     )?;
+    let mut transition_offsets = vec![u64::MAX; data.states.len()];
     for (state_id, next) in data.transitions.iter() {
-        write_u32(&mut w, *state_id, "solution_export_data_write", &path)?;
+        if !compress {
+            transition_offsets[*state_id as usize] = DATA_HEADER_LEN + hw.pos;
+        }
+        write_int_u32(&mut hw, *state_id, compress, STAGE, &path)?;
         for &dst in next.iter() {
-            write_u32(&mut w, dst, "solution_export_data_write", &path)?;
+            write_int_u32(&mut hw, dst, compress, STAGE, &path)?;
         }
     }
 
-    write_u32(
-        &mut w,
+    write_int_u32(
+        &mut hw,
         to_u32_len(
             data.strategy_trap.len(),
             "solution_export_strategy_trap_len",
         )?,
-        "solution_export_data_write",
+        compress,
+        STAGE,
         &path,
     )?;
     for (w_id, b_id) in data.strategy_trap.iter() {
-        write_u32(&mut w, *w_id, "solution_export_data_write", &path)?;
-        write_u32(&mut w, *b_id, "solution_export_data_write", &path)?;
+        write_int_u32(&mut hw, *w_id, compress, STAGE, &path)?;
+        write_int_u32(&mut hw, *b_id, compress, STAGE, &path)?;
     }
 
-    write_u32(
-        &mut w,
+    write_int_u32(
+        &mut hw,
         to_u32_len(
             data.strategy_tempo.len(),
             "solution_export_strategy_tempo_len",
         )?,
-        "solution_export_data_write",
+        compress,
+        STAGE,
         &path,
     )?;
     for (w_id, b_id) in data.strategy_tempo.iter() {
-        write_u32(&mut w, *w_id, "solution_export_data_write", &path)?;
-        write_u32(&mut w, *b_id, "solution_export_data_write", &path)?;
+        write_int_u32(&mut hw, *w_id, compress, STAGE, &path)?;
+        write_int_u32(&mut hw, *b_id, compress, STAGE, &path)?;
+    }
+
+    if !compress {
+        for &offset in transition_offsets.iter() {
+            write_u64(&mut hw, offset, STAGE, &path)?;
+        }
     }
 
+    let hash = hw.hash;
+    let mut w = hw.inner;
+    write_u64(&mut w, hash, "solution_export_data_write", &path)?;
+
     w.flush().map_err(|e| SearchError::Io {
         stage: "solution_export_data_flush",
         path: path.display().to_string(),
         error: e.to_string(),
-    })
+    })?;
+
+    Ok(hash)
+}
+
+/// Write a length-prefixed id sequence. When `compress` is set, sort ascending and delta-encode
+/// consecutive entries (each delta is non-negative since the input is sorted first) before
+/// varint-packing, since these are exactly the "sequences of state indices" that benefit: the
+/// written order doesn't matter to [`load_bundle`] (ids are only used as [`FxHashSet`] membership),
+/// so sorting here is free.
+fn write_sorted_ids(
+    w: &mut dyn Write,
+    ids: &[u32],
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+    len_stage: &'static str,
+) -> Result<(), SearchError> {
+    write_int_u32(w, to_u32_len(ids.len(), len_stage)?, compress, stage, path)?;
+
+    if compress {
+        let mut sorted = ids.to_vec();
+        sorted.sort_unstable();
+        let mut prev = 0u32;
+        for id in sorted {
+            write_int_u32(w, id - prev, compress, stage, path)?;
+            prev = id;
+        }
+    } else {
+        for &id in ids {
+            write_int_u32(w, id, compress, stage, path)?;
+        }
+    }
+    Ok(())
 }
 
-fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, SearchError> {
+fn read_data(
+    bundle_dir: &Path,
+    piece_count: usize,
+    alphabet_len: usize,
+) -> Result<SolutionData, SearchError> {
     let path = bundle_dir.join(DATA_FILENAME);
     let f = fs::File::open(&path).map_err(|e| SearchError::Io {
         stage: "solution_load_data_open",
@@ -557,7 +798,7 @@ fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, Sear
     })?;
     let mut r = BufReader::new(f);
 
-    let mut magic = [0u8; 8];
+    let mut magic = [0u8; 4];
     r.read_exact(&mut magic).map_err(|e| SearchError::Io {
         stage: "solution_load_data_read",
         path: path.display().to_string(),
@@ -576,7 +817,12 @@ fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, Sear
         });
     }
 
-    let file_piece_count = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
+    let flags = read_u32(&mut r, "solution_load_data_read", &path)?;
+    let compress = flags & FLAG_VARINT != 0;
+    const STAGE: &str = "solution_load_data_read";
+    let record_count = read_u32(&mut r, STAGE, &path)?;
+
+    let file_piece_count = read_int_u32(&mut r, compress, STAGE, &path)? as usize;
     if file_piece_count != piece_count {
         return Err(SearchError::InvalidScenario {
             reason: format!(
@@ -585,15 +831,22 @@ fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, Sear
         });
     }
 
-    let states_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
+    let states_len = read_int_u32(&mut r, compress, STAGE, &path)? as usize;
+    if record_count as usize != states_len {
+        return Err(SearchError::InvalidScenario {
+            reason: format!(
+                "solution data.bin header record_count {record_count} mismatches states length {states_len}"
+            ),
+        });
+    }
     let mut states: Vec<State> = Vec::with_capacity(states_len);
     for _ in 0..states_len {
-        let x = read_i32(&mut r, "solution_load_data_read", &path)?;
-        let y = read_i32(&mut r, "solution_load_data_read", &path)?;
+        let x = read_int_i32(&mut r, compress, STAGE, &path)?;
+        let y = read_int_i32(&mut r, compress, STAGE, &path)?;
 
         let mut squares = [Square::NONE; MAX_PIECES];
         for square in squares.iter_mut().take(piece_count) {
-            let raw = read_i64(&mut r, "solution_load_data_read", &path)?;
+            let raw = read_int_i64(&mut r, compress, STAGE, &path)?;
             *square = Square::from_raw(raw);
         }
 
@@ -604,42 +857,33 @@ fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, Sear
         });
     }
 
-    let trap_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
-    let mut trap_set_ids = Vec::with_capacity(trap_len);
-    for _ in 0..trap_len {
-        trap_set_ids.push(read_u32(&mut r, "solution_load_data_read", &path)?);
-    }
+    let trap_set_ids = read_sorted_ids(&mut r, compress, STAGE, &path)?;
+    let tempo_set_ids = read_sorted_ids(&mut r, compress, STAGE, &path)?;
 
-    let tempo_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
-    let mut tempo_set_ids = Vec::with_capacity(tempo_len);
-    for _ in 0..tempo_len {
-        tempo_set_ids.push(read_u32(&mut r, "solution_load_data_read", &path)?);
-    }
-
-    let transitions_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
-    let mut transitions: Vec<(u32, [u32; 8])> = Vec::with_capacity(transitions_len);
+    let transitions_len = read_int_u32(&mut r, compress, STAGE, &path)? as usize;
+    let mut transitions: Vec<(u32, Vec<u32>)> = Vec::with_capacity(transitions_len);
     for _ in 0..transitions_len {
-        let state_id = read_u32(&mut r, "solution_load_data_read", &path)?;
-        let mut next = [u32::MAX; 8];
+        let state_id = read_int_u32(&mut r, compress, STAGE, &path)?;
+        let mut next = vec![u32::MAX; alphabet_len];
         for d in next.iter_mut() {
-            *d = read_u32(&mut r, "solution_load_data_read", &path)?;
+            *d = read_int_u32(&mut r, compress, STAGE, &path)?;
         }
         transitions.push((state_id, next));
     }
 
-    let strategy_trap_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
+    let strategy_trap_len = read_int_u32(&mut r, compress, STAGE, &path)? as usize;
     let mut strategy_trap: Vec<(u32, u32)> = Vec::with_capacity(strategy_trap_len);
     for _ in 0..strategy_trap_len {
-        let w_id = read_u32(&mut r, "solution_load_data_read", &path)?;
-        let b_id = read_u32(&mut r, "solution_load_data_read", &path)?;
+        let w_id = read_int_u32(&mut r, compress, STAGE, &path)?;
+        let b_id = read_int_u32(&mut r, compress, STAGE, &path)?;
         strategy_trap.push((w_id, b_id));
     }
 
-    let strategy_tempo_len = read_u32(&mut r, "solution_load_data_read", &path)? as usize;
+    let strategy_tempo_len = read_int_u32(&mut r, compress, STAGE, &path)? as usize;
     let mut strategy_tempo: Vec<(u32, u32)> = Vec::with_capacity(strategy_tempo_len);
     for _ in 0..strategy_tempo_len {
-        let w_id = read_u32(&mut r, "solution_load_data_read", &path)?;
-        let b_id = read_u32(&mut r, "solution_load_data_read", &path)?;
+        let w_id = read_int_u32(&mut r, compress, STAGE, &path)?;
+        let b_id = read_int_u32(&mut r, compress, STAGE, &path)?;
         strategy_tempo.push((w_id, b_id));
     }
 
@@ -653,6 +897,30 @@ fn read_data(bundle_dir: &Path, piece_count: usize) -> Result<SolutionData, Sear
     })
 }
 
+/// Read a length-prefixed id sequence written by [`write_sorted_ids`]. When `compress` is set,
+/// entries are deltas from the previous (sorted-ascending) id and are reconstructed by running
+/// sum; member order doesn't matter to callers (ids only end up in an [`FxHashSet`]).
+fn read_sorted_ids(
+    r: &mut dyn Read,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<Vec<u32>, SearchError> {
+    let len = read_int_u32(r, compress, stage, path)? as usize;
+    let mut ids = Vec::with_capacity(len);
+    let mut prev = 0u32;
+    for _ in 0..len {
+        let v = read_int_u32(r, compress, stage, path)?;
+        if compress {
+            prev = prev.wrapping_add(v);
+            ids.push(prev);
+        } else {
+            ids.push(v);
+        }
+    }
+    Ok(ids)
+}
+
 fn rules_manifest_from_rules(rules: &Rules) -> RulesManifest {
     let mut queens = 0u16;
     let mut rooks = 0u16;
@@ -798,20 +1066,6 @@ fn to_u32_len(len: usize, stage: &'static str) -> Result<u32, SearchError> {
     })
 }
 
-fn dir_index(delta: Coord) -> Option<usize> {
-    match (delta.x, delta.y) {
-        (-1, 1) => Some(0),  // q
-        (0, 1) => Some(1),   // w
-        (1, 1) => Some(2),   // e
-        (-1, 0) => Some(3),  // a
-        (1, 0) => Some(4),   // d
-        (-1, -1) => Some(5), // z
-        (0, -1) => Some(6),  // x
-        (1, -1) => Some(7),  // c
-        _ => None,
-    }
-}
-
 fn write_u32(
     w: &mut dyn Write,
     v: u32,
@@ -881,53 +1135,495 @@ fn read_i64(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<i64, S
     Ok(i64::from_le_bytes(buf))
 }
 
-/// Return a human-readable mapping from direction indices to key labels (q,w,e,a,d,z,x,c).
-pub fn direction_labels() -> &'static [char; 8] {
-    &['q', 'w', 'e', 'a', 'd', 'z', 'x', 'c']
+fn write_varint_u64(
+    w: &mut dyn Write,
+    mut v: u64,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            w.write_all(&[byte]).map_err(|e| SearchError::Io {
+                stage,
+                path: path.display().to_string(),
+                error: e.to_string(),
+            })?;
+            return Ok(());
+        }
+        w.write_all(&[byte | 0x80]).map_err(|e| SearchError::Io {
+            stage,
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+    }
 }
 
-/// Return a mapping from direction keys to king-step deltas.
+fn write_varint_u32(
+    w: &mut dyn Write,
+    v: u32,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    write_varint_u64(w, v as u64, stage, path)
+}
+
+/// Zigzag-map `v` to a `u32` (`(n << 1) ^ (n >> 31)`) so small magnitudes of either sign cost one
+/// byte, then varint-pack it.
+fn write_varint_i32(
+    w: &mut dyn Write,
+    v: i32,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    let zigzag = ((v << 1) ^ (v >> 31)) as u32;
+    write_varint_u32(w, zigzag, stage, path)
+}
+
+/// Zigzag-map `v` to a `u64` (`(n << 1) ^ (n >> 63)`), then varint-pack it.
+fn write_varint_i64(
+    w: &mut dyn Write,
+    v: i64,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    let zigzag = ((v << 1) ^ (v >> 63)) as u64;
+    write_varint_u64(w, zigzag, stage, path)
+}
+
+fn read_varint_u64(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<u64, SearchError> {
+    let mut result: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        r.read_exact(&mut byte).map_err(|e| SearchError::Io {
+            stage,
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+        result |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+        if shift >= 64 {
+            return Err(SearchError::InvalidScenario {
+                reason: "solution data.bin varint overflowed 64 bits".to_string(),
+            });
+        }
+    }
+}
+
+fn read_varint_u32(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<u32, SearchError> {
+    let v = read_varint_u64(r, stage, path)?;
+    u32::try_from(v).map_err(|_| SearchError::InvalidScenario {
+        reason: format!("solution data.bin varint value {v} overflows u32"),
+    })
+}
+
+fn read_varint_i32(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<i32, SearchError> {
+    let zigzag = read_varint_u32(r, stage, path)?;
+    Ok(((zigzag >> 1) as i32) ^ -((zigzag & 1) as i32))
+}
+
+fn read_varint_i64(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<i64, SearchError> {
+    let zigzag = read_varint_u64(r, stage, path)?;
+    Ok(((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64))
+}
+
+/// Dispatch to [`write_varint_u32`] or the fixed-width [`write_u32`] depending on `compress`,
+/// matching whatever [`FLAG_VARINT`] value was (or will be) written into the header.
+fn write_int_u32(
+    w: &mut dyn Write,
+    v: u32,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    if compress {
+        write_varint_u32(w, v, stage, path)
+    } else {
+        write_u32(w, v, stage, path)
+    }
+}
+
+/// See [`write_int_u32`].
+fn write_int_i32(
+    w: &mut dyn Write,
+    v: i32,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    if compress {
+        write_varint_i32(w, v, stage, path)
+    } else {
+        write_i32(w, v, stage, path)
+    }
+}
+
+/// See [`write_int_u32`].
+fn write_int_i64(
+    w: &mut dyn Write,
+    v: i64,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    if compress {
+        write_varint_i64(w, v, stage, path)
+    } else {
+        write_i64(w, v, stage, path)
+    }
+}
+
+/// Dispatch to [`read_varint_u32`] or the fixed-width [`read_u32`] depending on `compress`
+/// (derived from the header's [`FLAG_VARINT`] bit).
+fn read_int_u32(
+    r: &mut dyn Read,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<u32, SearchError> {
+    if compress {
+        read_varint_u32(r, stage, path)
+    } else {
+        read_u32(r, stage, path)
+    }
+}
+
+/// See [`read_int_u32`].
+fn read_int_i32(
+    r: &mut dyn Read,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<i32, SearchError> {
+    if compress {
+        read_varint_i32(r, stage, path)
+    } else {
+        read_i32(r, stage, path)
+    }
+}
+
+/// See [`read_int_u32`].
+fn read_int_i64(
+    r: &mut dyn Read,
+    compress: bool,
+    stage: &'static str,
+    path: &Path,
+) -> Result<i64, SearchError> {
+    if compress {
+        read_varint_i64(r, stage, path)
+    } else {
+        read_i64(r, stage, path)
+    }
+}
+
+fn write_u64(
+    w: &mut dyn Write,
+    v: u64,
+    stage: &'static str,
+    path: &Path,
+) -> Result<(), SearchError> {
+    w.write_all(&v.to_le_bytes()).map_err(|e| SearchError::Io {
+        stage,
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })
+}
+
+fn read_u64(r: &mut dyn Read, stage: &'static str, path: &Path) -> Result<u64, SearchError> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf).map_err(|e| SearchError::Io {
+        stage,
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Recompute `data.bin`'s trailing FNV-1a content hash while streaming the file (never buffering
+/// the whole payload in memory), and check it against both the copy trailing the file itself and
+/// the copy recorded in the manifest. Also rejects an unsupported `format_version`. This gives
+/// callers (e.g. `play_solution`, via [`load_bundle`]) a fast corruption/version check before
+/// committing to the full decode in [`read_data`].
+pub fn verify_bundle(bundle_dir: &Path) -> Result<(), SearchError> {
+    let manifest = read_manifest(bundle_dir)?;
+
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(SearchError::InvalidScenario {
+            reason: format!(
+                "unsupported solution format_version {} (expected {FORMAT_VERSION})",
+                manifest.format_version
+            ),
+        });
+    }
+
+    let path = bundle_dir.join(&manifest.files.data_bin);
+    let f = fs::File::open(&path).map_err(|e| SearchError::Io {
+        stage: "solution_verify_data_open",
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+    let file_len = f
+        .metadata()
+        .map_err(|e| SearchError::Io {
+            stage: "solution_verify_data_metadata",
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?
+        .len();
+
+    let mut r = BufReader::new(f);
+
+    let mut magic = [0u8; 4];
+    r.read_exact(&mut magic).map_err(|e| SearchError::Io {
+        stage: "solution_verify_data_read",
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+    if &magic != DATA_MAGIC {
+        return Err(SearchError::InvalidScenario {
+            reason: "solution data.bin has wrong magic bytes".to_string(),
+        });
+    }
+
+    let version = read_u32(&mut r, "solution_verify_data_read", &path)?;
+    if version != FORMAT_VERSION {
+        return Err(SearchError::InvalidScenario {
+            reason: format!("solution data.bin version {version} is not supported"),
+        });
+    }
+    let _flags = read_u32(&mut r, "solution_verify_data_read", &path)?;
+    let _record_count = read_u32(&mut r, "solution_verify_data_read", &path)?;
+
+    if file_len < DATA_HEADER_LEN + DATA_TRAILER_LEN {
+        return Err(SearchError::InvalidScenario {
+            reason: "solution data.bin is shorter than its fixed header + trailing hash"
+                .to_string(),
+        });
+    }
+
+    let mut hash: u64 = FNV_OFFSET_BASIS;
+    let mut remaining = file_len - DATA_HEADER_LEN - DATA_TRAILER_LEN;
+    let mut buf = [0u8; 8192];
+    while remaining > 0 {
+        let chunk = remaining.min(buf.len() as u64) as usize;
+        r.read_exact(&mut buf[..chunk])
+            .map_err(|e| SearchError::Io {
+                stage: "solution_verify_data_read",
+                path: path.display().to_string(),
+                error: e.to_string(),
+            })?;
+        for &byte in &buf[..chunk] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        remaining -= chunk as u64;
+    }
+
+    let trailing_hash = read_u64(&mut r, "solution_verify_data_read", &path)?;
+    if trailing_hash != hash {
+        return Err(SearchError::InvalidScenario {
+            reason: "solution data.bin trailing content hash does not match its payload"
+                .to_string(),
+        });
+    }
+    if manifest.files.content_hash != hash {
+        return Err(SearchError::InvalidScenario {
+            reason: "solution manifest content_hash does not match data.bin".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn read_u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+/// Memory-mapped, lazily-decoded reader for a solved bundle's `data.bin`.
 ///
-/// This is primarily used by the interactive CLI and mirrors the encoding in the solution bundle.
-pub fn direction_deltas() -> BTreeMap<char, Coord> {
-    // Keep this deterministic for help output.
-    let mut m = BTreeMap::new();
-    m.insert('q', Coord::new(-1, 1));
-    m.insert('w', Coord::new(0, 1));
-    m.insert('e', Coord::new(1, 1));
-    m.insert('a', Coord::new(-1, 0));
-    m.insert('d', Coord::new(1, 0));
-    m.insert('z', Coord::new(-1, -1));
-    m.insert('x', Coord::new(0, -1));
-    m.insert('c', Coord::new(1, -1));
-    m
-}
-
-pub fn dir_index_from_key(ch: char) -> Option<usize> {
-    match ch {
-        'q' => Some(0),
-        'w' => Some(1),
-        'e' => Some(2),
-        'a' => Some(3),
-        'd' => Some(4),
-        'z' => Some(5),
-        'x' => Some(6),
-        'c' => Some(7),
-        _ => None,
-    }
-}
-
-pub fn delta_from_dir_index(idx: usize) -> Coord {
-    match idx {
-        0 => Coord::new(-1, 1),
-        1 => Coord::new(0, 1),
-        2 => Coord::new(1, 1),
-        3 => Coord::new(-1, 0),
-        4 => Coord::new(1, 0),
-        5 => Coord::new(-1, -1),
-        6 => Coord::new(0, -1),
-        7 => Coord::new(1, -1),
-        _ => Coord::ORIGIN,
+/// Unlike [`load_bundle`], which eagerly parses every state and transition into in-memory
+/// `Vec`s, `MappedBundle` maps the file once and decodes a state or transition record only when
+/// asked, by computed offset — no per-lookup scan, and no full in-memory copy of a potentially
+/// huge solved region. This only works for the fixed-width encoding (`ExportOptions::compress ==
+/// false`): varint records have no fixed size, so they can't be addressed by a computed offset.
+/// Use [`load_bundle`] for compressed bundles, or for bundles small enough that eager parsing
+/// (and its simpler, allocation-backed `transitions[state_id][dir]` access) is no hardship.
+pub struct MappedBundle {
+    mmap: Mmap,
+    piece_count: usize,
+    alphabet_len: usize,
+    states_len: usize,
+    states_start: usize,
+    state_record_size: usize,
+    transition_offsets_start: usize,
+}
+
+impl MappedBundle {
+    /// Open and validate `bundle_dir`, memory-mapping `data.bin`. Validates the header and the
+    /// whole-payload content hash once, up front (the same check [`load_bundle`] performs via
+    /// [`verify_bundle`]); every subsequent [`Self::state_at`]/[`Self::successor`] call is then a
+    /// plain offset computation plus a slice read, not a re-scan.
+    pub fn open(bundle_dir: &Path) -> Result<Self, SearchError> {
+        let manifest = read_manifest(bundle_dir)?;
+        if manifest.format_version != FORMAT_VERSION {
+            return Err(SearchError::InvalidScenario {
+                reason: format!(
+                    "unsupported solution format_version {} (expected {FORMAT_VERSION})",
+                    manifest.format_version
+                ),
+            });
+        }
+
+        verify_bundle(bundle_dir)?;
+
+        let rules = rules_from_manifest(&manifest.rules)?;
+        let piece_count = rules.layout.piece_count();
+        let alphabet = MoveAlphabet::from_manifest(&manifest.move_alphabet)?;
+
+        let path = bundle_dir.join(DATA_FILENAME);
+        let file = fs::File::open(&path).map_err(|e| SearchError::Io {
+            stage: "solution_mapped_open",
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| SearchError::Io {
+            stage: "solution_mapped_mmap",
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+
+        let header_trailer_len = (DATA_HEADER_LEN + DATA_TRAILER_LEN) as usize;
+        if mmap.len() < header_trailer_len {
+            return Err(SearchError::InvalidScenario {
+                reason: "solution data.bin is shorter than its fixed header + trailing hash"
+                    .to_string(),
+            });
+        }
+        if &mmap[0..4] != DATA_MAGIC {
+            return Err(SearchError::InvalidScenario {
+                reason: "solution data.bin has wrong magic bytes".to_string(),
+            });
+        }
+        let flags = read_u32_at(&mmap, 8);
+        if flags & FLAG_VARINT != 0 {
+            return Err(SearchError::InvalidScenario {
+                reason: "MappedBundle only supports the fixed-width data.bin encoding (export \
+                         without ExportOptions::compress); use load_bundle for compressed bundles"
+                    .to_string(),
+            });
+        }
+
+        let mut off = DATA_HEADER_LEN as usize;
+        let file_piece_count = read_u32_at(&mmap, off) as usize;
+        off += 4;
+        if file_piece_count != piece_count {
+            return Err(SearchError::InvalidScenario {
+                reason: format!(
+                    "solution data.bin piece_count {file_piece_count} mismatches manifest {piece_count}"
+                ),
+            });
+        }
+
+        let states_len = read_u32_at(&mmap, off) as usize;
+        off += 4;
+        let state_record_size = 8 + piece_count * 8;
+        let states_start = off;
+        off += state_record_size * states_len;
+
+        let trap_len = read_u32_at(&mmap, off) as usize;
+        off += 4 + trap_len * 4;
+
+        let tempo_len = read_u32_at(&mmap, off) as usize;
+        off += 4 + tempo_len * 4;
+
+        let transitions_len = read_u32_at(&mmap, off) as usize;
+        off += 4;
+        let transition_record_size = 4 + alphabet.len() * 4;
+        off += transition_record_size * transitions_len;
+
+        let strategy_trap_len = read_u32_at(&mmap, off) as usize;
+        off += 4 + strategy_trap_len * 8;
+
+        let strategy_tempo_len = read_u32_at(&mmap, off) as usize;
+        off += 4 + strategy_tempo_len * 8;
+
+        let transition_offsets_start = off;
+        let expected_len = transition_offsets_start + states_len * 8 + DATA_TRAILER_LEN as usize;
+        if expected_len != mmap.len() {
+            return Err(SearchError::InvalidScenario {
+                reason: "solution data.bin size does not match its own header-derived layout"
+                    .to_string(),
+            });
+        }
+
+        Ok(Self {
+            mmap,
+            piece_count,
+            alphabet_len: alphabet.len(),
+            states_len,
+            states_start,
+            state_record_size,
+            transition_offsets_start,
+        })
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.states_len
+    }
+
+    /// Decode the state record for `id`, or `None` if `id` is out of range.
+    pub fn state_at(&self, id: u32) -> Option<State> {
+        let idx = id as usize;
+        if idx >= self.states_len {
+            return None;
+        }
+        let base = self.states_start + idx * self.state_record_size;
+        let x = read_u32_at(&self.mmap, base) as i32;
+        let y = read_u32_at(&self.mmap, base + 4) as i32;
+
+        let mut squares = [Square::NONE; MAX_PIECES];
+        for (i, square) in squares.iter_mut().take(self.piece_count).enumerate() {
+            let raw = read_u64_at(&self.mmap, base + 8 + i * 8) as i64;
+            *square = Square::from_raw(raw);
+        }
+
+        Some(State {
+            abs_king: Coord::new(x, y),
+            pos: Position::new(self.piece_count, squares),
+        })
+    }
+
+    /// The state id reached by moving in direction `dir_idx` (an index into the bundle's
+    /// [`MoveAlphabet`]) from `state_id`, or `None` if `state_id` has no transition record (it
+    /// isn't in the solved trap) or `dir_idx` isn't legal from it.
+    pub fn successor(&self, state_id: u32, dir_idx: usize) -> Option<u32> {
+        if dir_idx >= self.alphabet_len {
+            return None;
+        }
+        let idx = state_id as usize;
+        if idx >= self.states_len {
+            return None;
+        }
+        let offset = read_u64_at(&self.mmap, self.transition_offsets_start + idx * 8);
+        if offset == u64::MAX {
+            return None;
+        }
+        let dst_at = offset as usize + 4 + dir_idx * 4;
+        let dst = read_u32_at(&self.mmap, dst_at);
+        if dst == u32::MAX {
+            None
+        } else {
+            Some(dst)
+        }
     }
 }
 
@@ -0,0 +1,256 @@
+use crate::arena::ArenaBuilder;
+use crate::game::Game;
+use crate::pieces::{Material, PieceKind, Turn};
+use crate::region::Region;
+use crate::solve::buchi::tempo_trap;
+use crate::solve::safety::safety_trap;
+
+use std::time::{Duration, Instant};
+
+/// Cost charged for configurations where the tempo trap doesn't cover every reachable
+/// black-to-move start state. Chosen far above any realistic `w_material * weight + w_region *
+/// size` total so feasibility can be read straight off the scalar cost: `cost < INFEASIBLE_BASE`.
+const INFEASIBLE_BASE: f64 = 1.0e9;
+
+const MIN_RADIUS: i16 = 1;
+const MAX_RADIUS: i16 = 8;
+const MAX_PER_KIND: u8 = 6;
+
+/// Which of [`Region`]'s ball constructors built a [`Config`]'s region.
+///
+/// Grow/shrink neighbors need to rebuild from (shape, radius) rather than mutate a `Region`
+/// directly, since `Region` has no resize operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionShape {
+    LInf,
+    L1,
+    KnightDistance,
+}
+
+impl RegionShape {
+    fn build(self, radius: i16) -> Region {
+        match self {
+            RegionShape::LInf => Region::linf(radius),
+            RegionShape::L1 => Region::l1(radius),
+            RegionShape::KnightDistance => Region::knight_distance(radius.max(0) as u16),
+        }
+    }
+}
+
+/// A candidate `(Region, Material)` configuration for [`anneal`].
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub shape: RegionShape,
+    pub radius: i16,
+    pub material: Material,
+}
+
+impl Config {
+    pub fn new(shape: RegionShape, radius: i16, material: Material) -> Self {
+        Self {
+            shape,
+            radius,
+            material,
+        }
+    }
+
+    fn region(&self) -> Region {
+        self.shape.build(self.radius)
+    }
+}
+
+/// Tunables for [`anneal`].
+#[derive(Clone, Debug)]
+pub struct AnnealConfig {
+    pub w_material: f64,
+    pub w_region: f64,
+    pub initial_temp: f64,
+    /// Multiplicative per-step cooling factor; `temp *= cooling` after every proposal.
+    pub cooling: f64,
+    pub time_limit: Duration,
+    pub seed: u64,
+}
+
+impl Default for AnnealConfig {
+    fn default() -> Self {
+        Self {
+            w_material: 1.0,
+            w_region: 0.01,
+            initial_temp: 10.0,
+            cooling: 0.999,
+            time_limit: Duration::from_secs(30),
+            seed: 0x5EED,
+        }
+    }
+}
+
+/// The best feasible configuration seen during an [`anneal`] run, and its cost.
+#[derive(Clone, Debug)]
+pub struct AnnealResult {
+    pub config: Config,
+    pub cost: f64,
+}
+
+fn piece_weight(kind: PieceKind) -> f64 {
+    match kind {
+        PieceKind::King => 0.0,
+        PieceKind::Queen => 9.0,
+        PieceKind::Rook => 5.0,
+        PieceKind::Bishop => 3.0,
+        PieceKind::Knight => 3.0,
+        PieceKind::Pawn => 1.0,
+    }
+}
+
+fn total_piece_weight(material: &Material) -> f64 {
+    material.queens as f64 * piece_weight(PieceKind::Queen)
+        + material.rooks as f64 * piece_weight(PieceKind::Rook)
+        + material.bishops as f64 * piece_weight(PieceKind::Bishop)
+        + material.knights as f64 * piece_weight(PieceKind::Knight)
+}
+
+/// Objective: rebuild the arena for `cfg`, and either penalize uncovered start states or score
+/// the feasible configuration by weighted material + region size.
+///
+/// A "start state" is any non-sink, black-to-move node — i.e. every legal position Black could
+/// be handed the move in. `cfg` is feasible only if `tempo_trap` covers all of them, since any
+/// uncovered one is a start White cannot force mate from.
+fn cost(cfg: &Config, anneal_cfg: &AnnealConfig) -> f64 {
+    let region = cfg.region();
+    let game = Game::new(region.clone(), cfg.material.clone()).with_allow_pass(true);
+    let arena = ArenaBuilder::new(game).enumerate_all();
+
+    let safety = safety_trap(&arena);
+    let tempo = tempo_trap(&arena, &safety);
+
+    let mut uncovered = 0usize;
+    for (id, node) in arena.nodes.iter().enumerate() {
+        if arena.is_sink(id) || node.turn != Turn::Black {
+            continue;
+        }
+        if !tempo[id] {
+            uncovered += 1;
+        }
+    }
+
+    if uncovered > 0 {
+        return INFEASIBLE_BASE + uncovered as f64;
+    }
+
+    anneal_cfg.w_material * total_piece_weight(&cfg.material)
+        + anneal_cfg.w_region * region.size() as f64
+}
+
+/// A splitmix64 generator local to this module, so the annealing loop doesn't need an external
+/// `rand` dependency; same mixing step as the hash mixer in [`crate::core::position`].
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in `[0, bound)`. `bound` must be nonzero.
+    fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// One random small edit to `cfg`: add/remove one queen, rook, bishop or knight, or grow/shrink
+/// the region radius by one. Bounds (`MIN_RADIUS..=MAX_RADIUS`, `0..=MAX_PER_KIND`) keep the walk
+/// from wandering into an arena too large to enumerate.
+fn neighbor(cfg: &Config, rng: &mut Rng) -> Config {
+    let mut next = cfg.clone();
+
+    if rng.next_below(2) == 0 {
+        let kinds = [
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+        ];
+        let kind = kinds[rng.next_below(kinds.len())];
+        let grow = rng.next_below(2) == 0;
+        let slot = match kind {
+            PieceKind::Queen => &mut next.material.queens,
+            PieceKind::Rook => &mut next.material.rooks,
+            PieceKind::Bishop => &mut next.material.bishops,
+            PieceKind::Knight => &mut next.material.knights,
+            PieceKind::King => unreachable!("king is excluded from `kinds`"),
+            PieceKind::Pawn => unreachable!("pawn is excluded from `kinds`"),
+        };
+        if grow {
+            if *slot < MAX_PER_KIND {
+                *slot += 1;
+            }
+        } else if *slot > 0 {
+            *slot -= 1;
+        }
+    } else if rng.next_below(2) == 0 {
+        next.radius = (next.radius + 1).min(MAX_RADIUS);
+    } else {
+        next.radius = (next.radius - 1).max(MIN_RADIUS);
+    }
+
+    next
+}
+
+/// Metropolis annealing search for the cheapest `(Region, Material)` configuration that still
+/// forces mate of a lone black king.
+///
+/// Proposes a random [`neighbor`] of the current configuration, always accepts improvements, and
+/// accepts worsening moves with probability `exp(-delta/T)`, cooling `T` geometrically
+/// (`anneal_cfg.cooling`) after every proposal until `anneal_cfg.time_limit` elapses. Returns the
+/// best *feasible* configuration seen over the whole run, even if the walk itself ends somewhere
+/// worse (annealing needs to pass through worse and infeasible states to escape local minima).
+pub fn anneal(start: Config, anneal_cfg: &AnnealConfig) -> Option<AnnealResult> {
+    let mut rng = Rng(anneal_cfg.seed);
+
+    let mut current = start;
+    let mut current_cost = cost(&current, anneal_cfg);
+
+    let mut best: Option<AnnealResult> = None;
+    if current_cost < INFEASIBLE_BASE {
+        best = Some(AnnealResult {
+            config: current.clone(),
+            cost: current_cost,
+        });
+    }
+
+    let mut temp = anneal_cfg.initial_temp;
+    let deadline = Instant::now() + anneal_cfg.time_limit;
+
+    while Instant::now() < deadline {
+        let candidate = neighbor(&current, &mut rng);
+        let candidate_cost = cost(&candidate, anneal_cfg);
+        let delta = candidate_cost - current_cost;
+
+        let accept = delta <= 0.0 || rng.next_f64() < (-delta / temp).exp();
+        if accept {
+            current = candidate;
+            current_cost = candidate_cost;
+
+            if current_cost < INFEASIBLE_BASE
+                && best.as_ref().map_or(true, |b| current_cost < b.cost)
+            {
+                best = Some(AnnealResult {
+                    config: current.clone(),
+                    cost: current_cost,
+                });
+            }
+        }
+
+        temp *= anneal_cfg.cooling;
+    }
+
+    best
+}
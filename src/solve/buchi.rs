@@ -1,3 +1,4 @@
+use crate::arena::graph::NodeId;
 use crate::arena::Arena;
 use crate::pieces::Turn;
 use std::collections::VecDeque;
@@ -33,6 +34,12 @@ pub fn tempo_trap(arena: &Arena, safety: &[bool]) -> Vec<bool> {
 }
 
 /// White Buchi winning set for visiting `accept` infinitely often while staying inside `base`.
+///
+/// This is the two-priority special case of [`crate::solve::parity::parity`] (`accept` nodes at
+/// priority 2, the rest of `base` at priority 1, everything outside `base` excluded from the
+/// domain) — kept as its own direct Zielonka-style fixpoint rather than routed through `parity`
+/// since [`tempo_trap`] and [`buchi_strategy`] both need the same two-round shape to additionally
+/// extract a strategy, which `parity` does not.
 pub fn buchi(arena: &Arena, base: &[bool], accept: &[bool]) -> Vec<bool> {
     let n = arena.len();
     assert_eq!(base.len(), n);
@@ -85,7 +92,246 @@ pub fn buchi(arena: &Arena, base: &[bool], accept: &[bool]) -> Vec<bool> {
     }
 }
 
-fn attractor_white(arena: &Arena, w: &[bool], target: &[bool]) -> Vec<bool> {
+/// Like [`tempo_trap`], but also returns a memoryless White strategy: see [`buchi_strategy`].
+pub fn tempo_trap_strategy(arena: &Arena, safety: &[bool]) -> (Vec<bool>, Vec<Option<NodeId>>) {
+    let n = arena.len();
+    assert_eq!(safety.len(), n);
+
+    let mut accept = vec![false; n];
+    if arena.game.allow_pass {
+        for id in 0..n {
+            if !safety[id] {
+                continue;
+            }
+            if arena.nodes[id].turn == Turn::White {
+                let pass_to = id ^ 1;
+                if safety.get(pass_to).copied().unwrap_or(false) {
+                    accept[id] = true;
+                }
+            }
+        }
+    }
+
+    buchi_strategy(arena, safety, &accept)
+}
+
+/// Like [`buchi`], but also returns a memoryless strategy for White: for every White-turn node in
+/// the returned winning set, the concrete successor node id White should move to.
+///
+/// The strategy falls out of the attractor computation that proves membership: each round,
+/// [`attractor_white_with_witness`] records the first already-winning successor that pulled a
+/// White node into the attractor, which gives a choice that strictly decreases "distance to
+/// `accept & W`" and so guarantees progress towards the next visit of `accept`. A White node
+/// already inside this round's `accept & W` target has nothing left to approach until the next
+/// round's target is recomputed, so it's instead given any successor that stays in `W`. The
+/// strategy recorded for a node may be overwritten on a later round if the node survives further
+/// shrinking of `W`; only entries for nodes in the final returned winning set are meaningful.
+pub fn buchi_strategy(
+    arena: &Arena,
+    base: &[bool],
+    accept: &[bool],
+) -> (Vec<bool>, Vec<Option<NodeId>>) {
+    let n = arena.len();
+    assert_eq!(base.len(), n);
+    assert_eq!(accept.len(), n);
+
+    let mut w = base.to_vec();
+    let mut strategy: Vec<Option<NodeId>> = vec![None; n];
+
+    if !accept.iter().any(|&b| b) {
+        return (vec![false; n], strategy);
+    }
+
+    loop {
+        let mut target = vec![false; n];
+        for i in 0..n {
+            target[i] = w[i] && accept[i];
+        }
+        let (a, witness) = attractor_white_with_witness(arena, &w, &target);
+
+        for id in 0..n {
+            if !a[id] || arena.nodes[id].turn != Turn::White {
+                continue;
+            }
+            strategy[id] = witness[id].or_else(|| {
+                // Already inside the target: any successor staying in W keeps the play valid
+                // until the next round's attractor picks up again.
+                arena.nodes[id].succ.iter().copied().find(|&s| w[s])
+            });
+        }
+
+        let mut has_b = false;
+        let mut bset = vec![false; n];
+        for i in 0..n {
+            bset[i] = w[i] && !a[i];
+            if bset[i] {
+                has_b = true;
+            }
+        }
+        if !has_b {
+            return (w, strategy);
+        }
+
+        let c = attractor_black(arena, &w, &bset);
+
+        let mut changed = false;
+        for i in 0..n {
+            if w[i] && c[i] {
+                w[i] = false;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return (w, strategy);
+        }
+    }
+}
+
+/// A concrete White move recovered from a strategy's `from_id -> to_id` choice (see
+/// [`describe_move`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TempoMove {
+    /// White passes, leaving the position unchanged for Black to move next.
+    Pass,
+    /// White moves the piece in white-piece slot `slot_idx` from `from_sq` to `to_sq` (both
+    /// region square indices, per [`crate::state::Packer`]).
+    Move {
+        slot_idx: usize,
+        from_sq: u16,
+        to_sq: u16,
+    },
+}
+
+/// Translate a White node's strategy choice (`from_id -> to_id`, as returned by
+/// [`tempo_trap_strategy`]/[`buchi_strategy`]) into a concrete [`TempoMove`].
+///
+/// A pass is recognized as `to_id == from_id ^ 1` (the paired node sharing the same state; see
+/// [`tempo_trap`]'s doc comment). Otherwise, exactly one piece group's occupied-square multiset
+/// must differ between the two states (one piece moves per ply); the square present only in
+/// `from_id`'s state is where it started, and the square present only in `to_id`'s state is where
+/// it landed. Comparing multisets rather than slot-by-slot is what makes this correct even when
+/// [`crate::state::canonicalize`] reassigns which slot within a group holds which square.
+///
+/// Panics if `from_id`/`to_id` are sinks, or if no group's occupied squares differ.
+pub fn describe_move(arena: &Arena, from_id: NodeId, to_id: NodeId) -> TempoMove {
+    if to_id == (from_id ^ 1) {
+        return TempoMove::Pass;
+    }
+
+    let game = &arena.game;
+    let packer = &game.packer;
+    let n_white = packer.n_white;
+
+    let from_state = arena.nodes[from_id]
+        .state
+        .expect("describe_move called on a sink node");
+    let to_state = arena.nodes[to_id]
+        .state
+        .expect("describe_move called on a sink node");
+
+    let mut from_whites = vec![0u16; n_white];
+    let mut to_whites = vec![0u16; n_white];
+    packer.unpack(from_state, &mut from_whites);
+    packer.unpack(to_state, &mut to_whites);
+
+    for group in &game.layout.groups {
+        let from_slice = &from_whites[group.start..group.start + group.len];
+        let to_slice = &to_whites[group.start..group.start + group.len];
+        if from_slice == to_slice {
+            continue;
+        }
+
+        let from_sq = from_slice
+            .iter()
+            .copied()
+            .find(|sq| !to_slice.contains(sq))
+            .expect("changed group must have a square present only in the source state");
+        let to_sq = to_slice
+            .iter()
+            .copied()
+            .find(|sq| !from_slice.contains(sq))
+            .expect("changed group must have a square present only in the destination state");
+        let slot_idx = group.start
+            + from_slice
+                .iter()
+                .position(|&sq| sq == from_sq)
+                .expect("from_sq was just found in from_slice");
+
+        return TempoMove::Move {
+            slot_idx,
+            from_sq,
+            to_sq,
+        };
+    }
+
+    panic!("describe_move: states differ but no piece group's occupied squares changed")
+}
+
+/// Like [`attractor_white`], but also records, for every White node pulled into the attractor via
+/// a predecessor edge (as opposed to starting in `target` already), the first already-attracted
+/// successor that triggered it — a concrete choice White can make to move strictly closer to
+/// `target`. Nodes that start in `target` (pushed onto the queue before any edge is examined) have
+/// no witness recorded; see [`buchi_strategy`] for how those are handled instead.
+fn attractor_white_with_witness(
+    arena: &Arena,
+    w: &[bool],
+    target: &[bool],
+) -> (Vec<bool>, Vec<Option<NodeId>>) {
+    let n = arena.len();
+    let mut in_attr = vec![false; n];
+    let mut witness: Vec<Option<NodeId>> = vec![None; n];
+    let mut rem: Vec<u32> = vec![0; n];
+
+    for id in 0..n {
+        if !w[id] {
+            continue;
+        }
+        if arena.nodes[id].turn == Turn::Black {
+            let cnt = arena.nodes[id].succ.iter().filter(|&&s| w[s]).count() as u32;
+            rem[id] = cnt;
+        }
+    }
+
+    let mut q: VecDeque<usize> = VecDeque::new();
+    for id in 0..n {
+        if target[id] {
+            in_attr[id] = true;
+            q.push_back(id);
+        }
+    }
+
+    while let Some(v) = q.pop_front() {
+        for &p in &arena.nodes[v].pred {
+            if !w[p] || in_attr[p] {
+                continue;
+            }
+            match arena.nodes[p].turn {
+                Turn::White => {
+                    in_attr[p] = true;
+                    witness[p] = Some(v);
+                    q.push_back(p);
+                }
+                Turn::Black => {
+                    if rem[p] == 0 {
+                        continue;
+                    }
+                    rem[p] -= 1;
+                    if rem[p] == 0 {
+                        in_attr[p] = true;
+                        q.push_back(p);
+                    }
+                }
+            }
+        }
+    }
+
+    (in_attr, witness)
+}
+
+/// `pub(crate)` so [`crate::solve::parity::parity`] can reuse the same worklist attractor instead
+/// of duplicating it for domain-restricted parity-game attractors.
+pub(crate) fn attractor_white(arena: &Arena, w: &[bool], target: &[bool]) -> Vec<bool> {
     // Player0 = White (exists), Player1 = Black (forall)
     let n = arena.len();
     let mut in_attr = vec![false; n];
@@ -136,7 +382,8 @@ fn attractor_white(arena: &Arena, w: &[bool], target: &[bool]) -> Vec<bool> {
     in_attr
 }
 
-fn attractor_black(arena: &Arena, w: &[bool], target: &[bool]) -> Vec<bool> {
+/// `pub(crate)`, see [`attractor_white`].
+pub(crate) fn attractor_black(arena: &Arena, w: &[bool], target: &[bool]) -> Vec<bool> {
     // Player1 = Black (exists), Player0 = White (forall)
     let n = arena.len();
     let mut in_attr = vec![false; n];
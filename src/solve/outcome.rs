@@ -0,0 +1,71 @@
+use crate::arena::Arena;
+use crate::pieces::Turn;
+use crate::rules::attacks::{build_white_occupancy, is_attacked_by_white};
+use crate::rules::movegen::Scratch;
+
+/// Classification of a black-to-move node, in the spirit of shakmaty's `Outcome`: a principled
+/// label instead of re-deriving mate/stalemate/escape from raw successor counts at every call
+/// site. Mirrors `search::movegen::Outcome`, generalizing the live tree's `domain` exit to this
+/// tree's `Succ::Sink` escape-through-the-region-boundary edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// In check with no legal reply: White has a forced mate.
+    WhiteMates,
+    /// Not in check with no legal reply: the game is drawn.
+    Stalemate,
+    /// At least one legal reply escapes the enumerated region (a `Succ::Sink` edge).
+    DomainExit,
+    /// At least one legal reply stays inside the region; the game continues.
+    Ongoing,
+}
+
+/// Classify every black-to-move node in `arena`: `None` for White nodes and sinks, `Some(_)`
+/// otherwise. `reach::checkmate_targets` and other downstream consumers should read this instead
+/// of re-deriving their own mate/stalemate/escape test.
+pub fn classify_all_black(arena: &Arena) -> Vec<Option<Outcome>> {
+    let n = arena.len();
+    let mut out = vec![None; n];
+
+    let game = &arena.game;
+    let cap = game.captured_code();
+    let region = &game.region;
+    let layout = &game.layout;
+    let mut scratch = Scratch::new(layout.total_white());
+
+    for id in 0..n {
+        if arena.nodes[id].turn != Turn::Black || arena.is_sink(id) {
+            continue;
+        }
+
+        let succ = &arena.nodes[id].succ;
+
+        out[id] = Some(if succ.is_empty() {
+            let st = arena.nodes[id].state.expect("non-sink node has a state");
+            let bk_sq = game.packer.unpack(st, scratch.whites_mut());
+            let whites = scratch.whites();
+            let occ = build_white_occupancy(region, whites, cap);
+            let bk_c = region.coord_of(bk_sq);
+            let in_check = is_attacked_by_white(
+                bk_c,
+                region,
+                &game.attack_tables,
+                layout,
+                whites,
+                cap,
+                &occ,
+                &game.movement_registry,
+            );
+            if in_check {
+                Outcome::WhiteMates
+            } else {
+                Outcome::Stalemate
+            }
+        } else if succ.contains(&arena.sink_white) {
+            Outcome::DomainExit
+        } else {
+            Outcome::Ongoing
+        });
+    }
+
+    out
+}
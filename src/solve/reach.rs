@@ -1,7 +1,6 @@
 use crate::arena::Arena;
 use crate::pieces::Turn;
-use crate::rules::attacks::{build_white_occupancy, is_attacked_by_white};
-use crate::rules::movegen::Scratch;
+use crate::solve::outcome::{classify_all_black, Outcome};
 
 use std::collections::VecDeque;
 
@@ -60,38 +59,70 @@ pub fn reachability_white(arena: &Arena, target: &[bool]) -> Vec<bool> {
     win
 }
 
-/// Target set: checkmates (black-to-move, in check, with no legal moves).
-pub fn checkmate_targets(arena: &Arena) -> Vec<bool> {
+/// Optimal ply-distance to `target` under minimax play, generalizing [`reachability_white`]'s
+/// boolean winning set to "how fast".
+///
+/// Same multi-source BFS shape as `reachability_white`, except it propagates a depth instead of a
+/// boolean: every `target` node seeds at depth `0`; a white-to-move predecessor resolves the
+/// moment any successor resolves (White chooses the fastest reply), and a black-to-move
+/// predecessor only resolves once *every* successor has resolved, at which point its depth is one
+/// more than the *last* (hence maximal, since the FIFO queue dequeues in non-decreasing depth
+/// order) successor depth to resolve. `None` means Black can avoid `target` forever (a draw or an
+/// escape).
+pub fn distance_to_target(arena: &Arena, target: &[bool]) -> Vec<Option<u32>> {
     let n = arena.len();
-    let mut target = vec![false; n];
-
-    let game = &arena.game;
-    let cap = game.captured_code();
-    let region = &game.region;
-    let layout = &game.layout;
+    assert_eq!(target.len(), n);
 
-    let mut scratch = Scratch::new(layout.total_white());
+    let mut depth: Vec<Option<u32>> = vec![None; n];
 
+    // For black nodes: number of successors not yet resolved.
+    let mut rem_black: Vec<u32> = vec![0; n];
     for id in 0..n {
-        if arena.nodes[id].turn != Turn::Black {
-            continue;
-        }
-        if arena.is_sink(id) {
-            continue;
+        if arena.nodes[id].turn == Turn::Black {
+            rem_black[id] = arena.nodes[id].succ.len() as u32;
         }
-        if !arena.nodes[id].succ.is_empty() {
-            continue;
+    }
+
+    let mut q: VecDeque<usize> = VecDeque::new();
+    for id in 0..n {
+        if target[id] {
+            depth[id] = Some(0);
+            q.push_back(id);
         }
-        let st = arena.nodes[id].state.expect("non-sink state");
-        let bk_sq = game.packer.unpack(st, scratch.whites_mut());
-        let whites = scratch.whites();
-        let occ = build_white_occupancy(region, whites, cap);
-        let bk_c = region.coord_of(bk_sq);
-        let in_check = is_attacked_by_white(bk_c, region, layout, whites, cap, &occ);
-        if in_check {
-            target[id] = true;
+    }
+
+    while let Some(v) = q.pop_front() {
+        let v_depth = depth[v].expect("queued nodes are always resolved");
+        for &p in &arena.nodes[v].pred {
+            if depth[p].is_some() {
+                continue;
+            }
+            match arena.nodes[p].turn {
+                Turn::White => {
+                    depth[p] = Some(v_depth + 1);
+                    q.push_back(p);
+                }
+                Turn::Black => {
+                    if rem_black[p] == 0 {
+                        continue;
+                    }
+                    rem_black[p] -= 1;
+                    if rem_black[p] == 0 && !arena.nodes[p].succ.is_empty() {
+                        depth[p] = Some(v_depth + 1);
+                        q.push_back(p);
+                    }
+                }
+            }
         }
     }
 
-    target
+    depth
+}
+
+/// Target set: checkmates (black-to-move, in check, with no legal moves).
+pub fn checkmate_targets(arena: &Arena) -> Vec<bool> {
+    classify_all_black(arena)
+        .iter()
+        .map(|label| matches!(label, Some(Outcome::WhiteMates)))
+        .collect()
 }
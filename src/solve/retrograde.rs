@@ -0,0 +1,49 @@
+use crate::arena::{Arena, NodeId};
+use crate::solve::reach::{checkmate_targets, distance_to_target};
+
+/// Sentinel DTM value for a node that is not a proven white win (draw/fortress/sink).
+pub const DRAW: i16 = -1;
+
+/// Retrograde backward induction over the full arena: an exact distance-to-mate (DTM) table,
+/// indexed by `NodeId`.
+///
+/// A thin `i16`/[`DRAW`]-sentinel wrapper over [`distance_to_target`] seeded with
+/// [`checkmate_targets`] — see that function's doc comment for the BFS shape. Kept as its own
+/// function (rather than inlining `distance_to_target(arena, &checkmate_targets(arena))` at every
+/// call site) for the narrower, tablebase-sized `i16` this module's callers expect.
+pub fn distance_to_mate(arena: &Arena) -> Vec<i16> {
+    distance_to_target(arena, &checkmate_targets(arena))
+        .into_iter()
+        .map(|d| d.map_or(DRAW, |d| d as i16))
+        .collect()
+}
+
+/// For a white-to-move node with a proven win, the successor that wins fastest (minimal DTM).
+/// Returns `None` if `id` is not a proven win for White.
+pub fn best_white_move(arena: &Arena, dtm: &[i16], id: NodeId) -> Option<NodeId> {
+    if dtm[id] == DRAW {
+        return None;
+    }
+    arena.nodes[id]
+        .succ
+        .iter()
+        .copied()
+        .filter(|&s| dtm[s] != DRAW)
+        .min_by_key(|&s| dtm[s])
+}
+
+/// For a black-to-move node that is a proven loss, the successor that survives longest (maximal
+/// DTM) — Black's strongest defense, used to generate the hardest (not just any) losing line in
+/// demos. Returns `None` if `id` is not a proven loss for Black (a draw/escape, or already a
+/// checkmate with no successors).
+pub fn best_black_move(arena: &Arena, dtm: &[i16], id: NodeId) -> Option<NodeId> {
+    if dtm[id] == DRAW {
+        return None;
+    }
+    arena.nodes[id]
+        .succ
+        .iter()
+        .copied()
+        .filter(|&s| dtm[s] != DRAW)
+        .max_by_key(|&s| dtm[s])
+}
@@ -4,8 +4,10 @@ use std::collections::VecDeque;
 
 /// Compute the maximal safety trap inside the region.
 ///
-/// Returns a boolean membership vector of length `arena.len()`.
-/// Sinks are always `false`.
+/// Returns a boolean membership vector of length `arena.len()`. Sinks are always `false`; unlike
+/// `solve::outcome::classify_all_black`, this doesn't distinguish *why* a node is outside the set
+/// (mate, stalemate, or escape), since the trap only cares whether White can force staying inside
+/// forever.
 pub fn safety_trap(arena: &Arena) -> Vec<bool> {
     let n = arena.len();
     let mut in_set = vec![true; n];
@@ -0,0 +1,255 @@
+use rustc_hash::FxHashMap;
+
+use crate::game::Game;
+use crate::pieces::Turn;
+use crate::region::Region;
+use crate::rules::attacks::{build_white_occupancy, is_attacked_by_white};
+use crate::rules::movegen::{successors_hashed, Scratch, Succ};
+use crate::state::PackedState;
+use crate::zobrist;
+
+/// Score for a node: higher is better for White. A proven mate scores `MATE - ply`, so shorter
+/// mates outrank longer ones; everything else (stalemate, escape off the region, depth exhausted)
+/// scores [`NON_MATE`].
+const MATE: i32 = 1_000_000;
+const NON_MATE: i32 = -MATE;
+
+/// What a transposition-table `score` actually bounds, since alpha-beta pruning can cut a node's
+/// search short before its true value is known.
+///
+/// A node that exhausts every move without an early cutoff stores [`Bound::Exact`]. One that
+/// cuts off on `best >= beta` only proves the true value is *at least* `best` (the pruned moves
+/// might have scored higher), so it stores [`Bound::Lower`]. One that never raises `alpha` past
+/// its entry value only proves the true value is *at most* `best`, so it stores [`Bound::Upper`].
+/// Reusing a cached score at a different (possibly wider) alpha-beta window without checking this
+/// tag would replay a bound as if it were exact — the classic fail-soft-TT-without-flag bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// The outcome of [`negamax_search`]: an exact game value plus the principal variation that
+/// achieves it.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    /// `> 0` iff White has a forced mate within the searched depth; the higher, the shorter.
+    pub value: i32,
+    /// States from the start position to the leaf, alternating turn, that realize `value`.
+    pub pv: Vec<PackedState>,
+    /// Total nodes visited across every iterative-deepening pass.
+    pub nodes: u64,
+}
+
+/// Search for a forced mate from `(start, to_move)` via iterative-deepening negamax with
+/// alpha-beta pruning, modeled on `search::negamax::forced_mate_search` but walking `Game`
+/// directly instead of a `Scenario`.
+///
+/// Stops as soon as some depth up to `max_depth` proves a mate (so the returned PV is the
+/// shortest one the search finds); otherwise returns the best (typically [`NON_MATE`]) value seen
+/// at `max_depth`.
+pub fn negamax_search(
+    game: &Game,
+    start: PackedState,
+    to_move: Turn,
+    max_depth: u32,
+) -> SearchResult {
+    let mut scratch = Scratch::new(game.layout.total_white());
+    let mut nodes: u64 = 0;
+    let mut best = SearchResult {
+        value: NON_MATE,
+        pv: vec![start],
+        nodes: 0,
+    };
+
+    // Kind-keyed incremental hash of `start` (see `zobrist::piece_key`): `negamax` derives every
+    // descendant's hash from its parent's in O(1) via `successors_hashed`, rather than re-unpacking
+    // `state` at every node the way `zobrist::hash_node` would.
+    let start_hash = game.zobrist.hash_packed_incremental(start);
+
+    for depth in 1..=max_depth {
+        // Transposition table keyed by the zobrist hash of `(state, turn)`: (depth searched,
+        // proven score, bound). A cached entry is only reusable at >= the depth it was computed
+        // at, and only for a lookup window the bound is actually compatible with (see `Bound`).
+        let mut tt: FxHashMap<u64, (u32, i32, Bound)> = FxHashMap::default();
+
+        let mut pv = vec![start];
+        let value = negamax(
+            game,
+            &mut scratch,
+            &mut tt,
+            &mut nodes,
+            to_move,
+            start,
+            start_hash,
+            depth,
+            NON_MATE,
+            MATE + 1,
+            &mut pv,
+        );
+
+        best = SearchResult { value, pv, nodes };
+        if value > 0 {
+            let plies = (MATE - value) as usize;
+            best.pv.truncate(plies + 1);
+            return best;
+        }
+    }
+
+    best
+}
+
+#[allow(clippy::too_many_arguments)]
+fn negamax(
+    game: &Game,
+    scratch: &mut Scratch,
+    tt: &mut FxHashMap<u64, (u32, i32, Bound)>,
+    nodes: &mut u64,
+    to_move: Turn,
+    state: PackedState,
+    hash: u64,
+    depth_left: u32,
+    mut alpha: i32,
+    beta: i32,
+    pv: &mut Vec<PackedState>,
+) -> i32 {
+    *nodes += 1;
+
+    let key = hash ^ zobrist::side_key(to_move);
+    if let Some(&(seen_depth, score, bound)) = tt.get(&key) {
+        if seen_depth >= depth_left {
+            match bound {
+                Bound::Exact => return score,
+                // `score` only proves the true value is >= itself; that's only useful here if
+                // it already meets or beats the current `beta`.
+                Bound::Lower if score >= beta => return score,
+                // `score` only proves the true value is <= itself; that's only useful here if
+                // it's already at or below the current `alpha`.
+                Bound::Upper if score <= alpha => return score,
+                _ => {}
+            }
+        }
+    }
+
+    // Snapshot of `alpha` at entry, before the move loop below mutates it, so the final bound
+    // tag reflects whether this node failed low relative to the window it was *called* with.
+    let alpha_orig = alpha;
+
+    let succs = successors_hashed(game, to_move, state, hash, scratch);
+
+    if succs.is_empty() {
+        // No legal move at all: mate iff the black king is currently in check, otherwise
+        // stalemate. White having zero moves shouldn't normally arise with `allow_pass`, but is
+        // handled the same way as a non-mate leaf.
+        let score = if to_move == Turn::Black && in_check(game, scratch, state) {
+            MATE
+        } else {
+            NON_MATE
+        };
+        tt.insert(key, (depth_left, score, Bound::Exact));
+        return score;
+    }
+
+    if depth_left == 0 {
+        return NON_MATE;
+    }
+
+    let next_to_move = to_move.other();
+    let mut best = NON_MATE;
+
+    for (succ, next_hash) in succs {
+        let child_state = match succ {
+            // A sink absorbs forever (see `arena::graph::ArenaBuilder::enumerate_all`'s
+            // self-looping sink nodes), so stepping into one can never lead to a mate.
+            Succ::Sink => {
+                let score = decay_for(to_move, NON_MATE);
+                if score > best {
+                    best = score;
+                    pv.truncate(1);
+                }
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    break;
+                }
+                continue;
+            }
+            Succ::State(s) => s,
+        };
+
+        let mut child_pv = pv.clone();
+        child_pv.push(child_state);
+
+        let child_score = negamax(
+            game,
+            scratch,
+            tt,
+            nodes,
+            next_to_move,
+            child_state,
+            next_hash,
+            depth_left - 1,
+            -beta,
+            -alpha,
+            &mut child_pv,
+        );
+        let score = decay_for(to_move, child_score);
+
+        if score > best {
+            best = score;
+            *pv = child_pv;
+        }
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let bound = if best >= beta {
+        Bound::Lower
+    } else if best <= alpha_orig {
+        Bound::Upper
+    } else {
+        Bound::Exact
+    };
+    tt.insert(key, (depth_left, best, bound));
+    best
+}
+
+/// Negamax child-score fold: negate at Black nodes (minimize White's score == maximize the
+/// negated value) and shorten a mate score by one ply as it propagates up, so shorter mates are
+/// preferred.
+#[inline]
+fn decay_for(to_move: Turn, child_score: i32) -> i32 {
+    let negated = match to_move {
+        Turn::White => child_score,
+        Turn::Black => -child_score,
+    };
+    if negated > 0 {
+        negated - 1
+    } else if negated < 0 {
+        negated + 1
+    } else {
+        negated
+    }
+}
+
+/// Whether the black king (unpacked from `state`) is currently attacked by White.
+fn in_check(game: &Game, scratch: &mut Scratch, state: PackedState) -> bool {
+    let region: &Region = &game.region;
+    let cap = game.captured_code();
+    let bk_sq = game.packer.unpack(state, scratch.whites_mut());
+    let whites = scratch.whites();
+    let occ = build_white_occupancy(region, whites, cap);
+    let bk_c = region.coord_of(bk_sq);
+    is_attacked_by_white(
+        bk_c,
+        region,
+        &game.attack_tables,
+        &game.layout,
+        whites,
+        cap,
+        &occ,
+        &game.movement_registry,
+    )
+}
@@ -0,0 +1,196 @@
+//! Zobrist-style hashing for [`PackedState`].
+//!
+//! Mirrors `crate::core::zobrist`'s derive-on-demand approach: each field's key is computed from
+//! a fast mix of `(field_index, code)` rather than drawn from a precomputed random table, so there
+//! is nothing to initialize and no RNG dependency.
+//!
+//! Field layout matches [`Packer`]: field `0` is the black king square, fields `1..=n_white` are
+//! white piece slots in `Packer`'s fixed slot order. Slots within a group are already sorted by
+//! [`crate::state::canonicalize`] before packing, so two packed states with the same multiset of
+//! occupied squares per group hash identically.
+
+use crate::pieces::{Layout, PieceKind, Turn};
+use crate::state::{PackedState, Packer};
+
+/// SplitMix64: a fast, well-mixed 64-bit hash. Used here as a stand-in for "look up a precomputed
+/// random table entry" over `(field_index, code)` pairs.
+#[inline]
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// The Zobrist key for `field_index`'s slot holding `code` (a square index, or
+/// `Packer::captured_code()` for "not on the board").
+///
+/// Only sound for hashing a whole state from scratch (see [`hash_packed`]) — *not* for an
+/// incremental per-move update. Keying on `field_index` rather than piece kind means
+/// [`crate::state::canonicalize`] reordering a group after a move changes which field holds which
+/// piece, so XORing out one field's old key and in its new one, the way [`update_piece`] does for
+/// the kind-keyed scheme, cannot be made sound here: a single piece's move can silently relocate a
+/// *different* same-kind piece to another field. Use [`piece_key`]/[`update_piece`] instead when an
+/// O(1) rolling update is needed.
+#[inline]
+pub fn field_key(field_index: usize, code: u16) -> u64 {
+    splitmix64((field_index as u64).wrapping_mul(0x9E3779B97F4A7C15) ^ (code as u64))
+}
+
+/// Hash a packed state from scratch by unpacking it field by field.
+pub fn hash_packed(packer: &Packer, state: PackedState) -> u64 {
+    let mut whites = vec![0u16; packer.n_white];
+    let bk = packer.unpack(state, &mut whites);
+
+    let mut h = field_key(0, bk);
+    for (i, &c) in whites.iter().enumerate() {
+        h ^= field_key(i + 1, c);
+    }
+    h
+}
+
+/// The key for `turn` to move, XORed into a position hash so a [`PackedState`] hashes differently
+/// depending on whose turn it is — a [`PackedState`] alone doesn't encode that.
+#[inline]
+pub fn side_key(turn: Turn) -> u64 {
+    match turn {
+        Turn::Black => splitmix64(0xB1AC_B1AC_B1AC_B1AC),
+        Turn::White => splitmix64(0x4171_4171_4171_4171),
+    }
+}
+
+/// Hash a `(state, turn)` node: [`hash_packed`] XORed with [`side_key`].
+pub fn hash_node(packer: &Packer, state: PackedState, turn: Turn) -> u64 {
+    hash_packed(packer, state) ^ side_key(turn)
+}
+
+/// A per-kind salt distinguishing e.g. a rook on square `s` from a bishop on square `s`. Unlike
+/// [`field_key`]'s field-index salt, this is the same for every slot of a given kind, which is the
+/// property that makes [`piece_key`] safe to update incrementally (see its doc comment).
+#[inline]
+fn kind_salt(kind: PieceKind) -> u64 {
+    let tag: u64 = match kind {
+        PieceKind::King => 1,
+        PieceKind::Queen => 2,
+        PieceKind::Rook => 3,
+        PieceKind::Bishop => 4,
+        PieceKind::Knight => 5,
+        PieceKind::Pawn => 6,
+    };
+    splitmix64(tag)
+}
+
+/// The salt for the black king, kept separate from [`kind_salt`]'s white-piece tags since
+/// [`PieceKind`] only enumerates white material.
+#[inline]
+fn black_king_salt() -> u64 {
+    splitmix64(0)
+}
+
+/// The Zobrist key contribution of a `kind` piece occupying `code` (a square index, or
+/// `Packer::captured_code()` for "not on the board").
+///
+/// Keyed by piece *kind* rather than slot/field index, so — unlike [`field_key`] — it is invariant
+/// under [`crate::state::canonicalize`]'s reordering of identical-piece slots: the hash only
+/// depends on the multiset of (kind, square) occupied, not on which slot within a kind's group
+/// holds which square. That is exactly what makes an O(1) per-move update sound here: moving one
+/// piece of a kind can never change another same-kind piece's contribution, even though
+/// canonicalize may reassign which slot it lives in afterwards. [`ZobristKeys`]'s doc comment
+/// explains why the field-keyed hash above can't offer the same guarantee.
+#[inline]
+pub fn piece_key(kind: PieceKind, code: u16) -> u64 {
+    splitmix64(kind_salt(kind) ^ (code as u64))
+}
+
+/// The Zobrist key contribution of the black king occupying `code`. Always present (the black
+/// king is never captured), so there is no "off the board" code for it.
+#[inline]
+pub fn black_king_key(code: u16) -> u64 {
+    splitmix64(black_king_salt() ^ (code as u64))
+}
+
+/// Hash a packed state from scratch via the kind-keyed scheme (see [`piece_key`]), by unpacking it
+/// field by field and looking up each field's piece kind from `layout`.
+pub fn hash_packed_by_kind(packer: &Packer, layout: &Layout, state: PackedState) -> u64 {
+    let mut whites = vec![0u16; packer.n_white];
+    let bk = packer.unpack(state, &mut whites);
+
+    let mut h = black_king_key(bk);
+    for (i, &c) in whites.iter().enumerate() {
+        h ^= piece_key(layout.slots[i], c);
+    }
+    h
+}
+
+/// Update a kind-keyed hash (see [`hash_packed_by_kind`]) when a `kind` piece moves from `old` to
+/// `new` (use `new == Packer::captured_code()` for a capture, and `old == Packer::captured_code()`
+/// when a promotion brings a reserve slot onto the board). O(1), and — unlike an incremental update
+/// over [`field_key`] — safe to apply directly after the move, before any `canonicalize` call: see
+/// [`piece_key`].
+#[inline]
+pub fn update_piece(hash: u64, kind: PieceKind, old: u16, new: u16) -> u64 {
+    hash ^ piece_key(kind, old) ^ piece_key(kind, new)
+}
+
+/// Update a kind-keyed hash (see [`hash_packed_by_kind`]) when the black king moves from `old` to
+/// `new`. O(1).
+#[inline]
+pub fn update_black_king(hash: u64, old: u16, new: u16) -> u64 {
+    hash ^ black_king_key(old) ^ black_king_key(new)
+}
+
+/// A [`Game`](crate::game::Game)'s hashing half, bundling the [`Packer`] every key above is
+/// derived from so callers get one stable `game.zobrist.hash_node(...)` call site instead of
+/// threading `&game.packer` through separately.
+///
+/// [`Self::hash_packed`]/[`Self::hash_node`] have no per-move rolling update (XOR out `sq_a`'s
+/// key, XOR in `sq_b`'s): see [`field_key`]'s doc comment for why that's unsound in general for the field-index-keyed scheme they're built on —
+/// `state::canonicalize` can reorder *other* slots in the same group when one piece's move changes
+/// their relative order, which a field-index-keyed XOR alone can't account for.
+///
+/// [`Self::hash_packed_incremental`]/[`Self::update_piece`]/[`Self::update_black_king`] are the
+/// kind-keyed alternative (see [`piece_key`]) that *is* safe to update incrementally, for callers
+/// like [`crate::rules::movegen::successors_hashed`] that want an O(1) per-successor hash instead
+/// of a full recompute.
+#[derive(Clone, Debug)]
+pub struct ZobristKeys {
+    packer: Packer,
+    layout: Layout,
+}
+
+impl ZobristKeys {
+    pub fn new(packer: Packer, layout: Layout) -> Self {
+        Self { packer, layout }
+    }
+
+    #[inline]
+    pub fn hash_packed(&self, state: PackedState) -> u64 {
+        hash_packed(&self.packer, state)
+    }
+
+    #[inline]
+    pub fn hash_node(&self, state: PackedState, turn: Turn) -> u64 {
+        hash_node(&self.packer, state, turn)
+    }
+
+    /// The kind-keyed hash of `state` (see [`hash_packed_by_kind`]), computed from scratch.
+    #[inline]
+    pub fn hash_packed_incremental(&self, state: PackedState) -> u64 {
+        hash_packed_by_kind(&self.packer, &self.layout, state)
+    }
+
+    /// O(1) update of a [`Self::hash_packed_incremental`] hash for a `slot_idx` piece moving from
+    /// `old` to `new` (see [`update_piece`]).
+    #[inline]
+    pub fn update_piece(&self, hash: u64, slot_idx: usize, old: u16, new: u16) -> u64 {
+        update_piece(hash, self.layout.slots[slot_idx], old, new)
+    }
+
+    /// O(1) update of a [`Self::hash_packed_incremental`] hash for the black king moving from
+    /// `old` to `new` (see [`update_black_king`]).
+    #[inline]
+    pub fn update_black_king(&self, hash: u64, old: u16, new: u16) -> u64 {
+        update_black_king(hash, old, new)
+    }
+}
@@ -28,7 +28,7 @@ fn abs_box_universe(
 ) -> FxHashSet<State> {
     let mut out: FxHashSet<State> = FxHashSet::default();
     try_for_each_state_in_abs_box::<std::convert::Infallible>(
-        &scn.rules.layout,
+        &scn.rules,
         bound,
         allow_captures,
         |s| {
@@ -73,7 +73,7 @@ fn three_rooks_in_small_abs_box_has_some_forced_mates() {
         remove_stalemates: false,
     };
 
-    let result = forced_mate_bounded(&scn, true).unwrap();
+    let result = forced_mate_bounded(&scn, true, false).unwrap();
     assert!(!result.winning_btm.is_empty());
 
     let dtm = result.dtm.as_ref().expect("DTM requested");
@@ -120,7 +120,7 @@ fn mate_winning_region_is_closed_under_optimal_replies() {
         remove_stalemates: false,
     };
 
-    let result = forced_mate_bounded(&scn, false).unwrap();
+    let result = forced_mate_bounded(&scn, false, false).unwrap();
     let universe = abs_box_universe(&scn, bound, true);
 
     let mut tracker = ResourceTracker::new(ResourceLimits::default());
@@ -171,6 +171,66 @@ fn two_rooks_has_no_forced_mate_region_in_small_abs_box() {
         remove_stalemates: false,
     };
 
-    let result = forced_mate_bounded(&scn, false).unwrap();
+    let result = forced_mate_bounded(&scn, false, false).unwrap();
     assert!(result.winning_btm.is_empty());
 }
+
+#[test]
+fn symmetry_reduction_agrees_with_unfolded_search() {
+    // `symmetry_reduction=true` requires `CandidateGeneration::InAbsBox` (the universe the 8 D4
+    // folds are taken over), matching `forced_mate_bounded`'s own requirement rather than this
+    // file's other tests' `InBox`.
+    let bound = 2;
+    let layout = PieceLayout::from_counts(false, 0, 3, 0, 0);
+    let rules = Rules::new(layout.clone(), 1);
+
+    let scn = Scenario {
+        name: "mate_rrr_abs_box_symmetry",
+        rules,
+        white_can_pass: false,
+        track_abs_king: true,
+        start: StartState {
+            to_move: Side::Black,
+            state: State::new(Coord::ORIGIN, captured_start(&layout)),
+        },
+        candidates: CandidateGeneration::InAbsBox {
+            bound,
+            allow_captures: true,
+        },
+        domain: BuiltinDomain::AbsBox { bound },
+        laws: NoLaws,
+        preferences: NoPreferences,
+        limits: ResourceLimits::default(),
+        cache_mode: CacheMode::None,
+        remove_stalemates: false,
+    };
+
+    let plain = forced_mate_bounded(&scn, false, false).unwrap();
+    let folded = forced_mate_bounded(&scn, false, true).unwrap();
+
+    assert!(!plain.winning_btm.is_empty());
+    assert!(!folded.winning_btm.is_empty());
+
+    // Every unfolded winning placement must fold to a winning placement in the symmetry-reduced
+    // search: the two searches decide the same winning region up to D4. Before fixing the
+    // un-folded `idx.get` lookups in `forced_mate_bounded`, every black successor looked like an
+    // escape under `fold_symmetry`, which would have made `folded.winning_btm` come back empty.
+    let folded_canon: FxHashSet<State> = folded
+        .winning_btm
+        .iter()
+        .map(|s| {
+            let mut c = s.clone();
+            c.pos.canonicalize_d4(&layout);
+            c
+        })
+        .collect();
+
+    for s in plain.winning_btm.iter() {
+        let mut c = s.clone();
+        c.pos.canonicalize_d4(&layout);
+        assert!(
+            folded_canon.contains(&c),
+            "unfolded winning state has no symmetry-reduced counterpart"
+        );
+    }
+}
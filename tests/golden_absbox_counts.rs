@@ -43,7 +43,13 @@ struct ScenarioSpec {
     move_bound: i32,
     #[serde(default = "default_move_bound_mode")]
     move_bound_mode: MoveBoundMode,
-    pieces: PieceCounts,
+    #[serde(default)]
+    pieces: Option<PieceCounts>,
+    /// Alternative to `pieces`: a compact layout notation string, e.g. `"KQQ"` for a white king
+    /// and two queens (see [`PieceLayout::to_text`]/[`PieceLayout::from_text`]). Exactly one of
+    /// `pieces`/`pieces_notation` must be set.
+    #[serde(default)]
+    pieces_notation: Option<String>,
     allow_captures: bool,
     white_can_pass: bool,
     #[serde(default = "default_remove_stalemates")]
@@ -63,14 +69,31 @@ fn captured_start(layout: &PieceLayout) -> Position {
     pos
 }
 
+/// Builds the `PieceLayout` a `ScenarioSpec` describes, from whichever of `pieces`/
+/// `pieces_notation` it set.
+fn resolve_layout(spec: &ScenarioSpec) -> PieceLayout {
+    match (&spec.pieces, &spec.pieces_notation) {
+        (Some(counts), None) => PieceLayout::from_counts(
+            counts.white_king,
+            counts.queens,
+            counts.rooks,
+            counts.bishops,
+            counts.knights,
+        ),
+        (None, Some(text)) => {
+            PieceLayout::from_text(text).expect("invalid pieces_notation in golden scenario JSON")
+        }
+        (Some(_), Some(_)) => {
+            panic!("golden scenario JSON set both `pieces` and `pieces_notation`")
+        }
+        (None, None) => {
+            panic!("golden scenario JSON must set one of `pieces`/`pieces_notation`")
+        }
+    }
+}
+
 fn build_scenario(spec: &ScenarioSpec) -> Scenario<BuiltinDomain, NoLaws, NoPreferences> {
-    let layout = PieceLayout::from_counts(
-        spec.pieces.white_king,
-        spec.pieces.queens,
-        spec.pieces.rooks,
-        spec.pieces.bishops,
-        spec.pieces.knights,
-    );
+    let layout = resolve_layout(spec);
 
     let effective_move_bound = match spec.move_bound_mode {
         MoveBoundMode::Inclusive => spec.move_bound,
@@ -129,3 +152,51 @@ fn golden_abs_box_counts_match() {
         assert_eq!(observed, case.expected, "mismatch for {}", path.display());
     }
 }
+
+#[test]
+fn pieces_notation_resolves_to_the_same_layout_as_piece_counts() {
+    let base = ScenarioSpec {
+        bound: 2,
+        move_bound: 5,
+        move_bound_mode: MoveBoundMode::Inclusive,
+        pieces: Some(PieceCounts {
+            white_king: true,
+            queens: 1,
+            rooks: 0,
+            bishops: 0,
+            knights: 1,
+        }),
+        pieces_notation: None,
+        allow_captures: true,
+        white_can_pass: true,
+        remove_stalemates: true,
+    };
+    let via_notation = ScenarioSpec {
+        pieces: None,
+        pieces_notation: Some("KQN".to_string()),
+        ..base.clone()
+    };
+
+    assert_eq!(
+        resolve_layout(&base).kinds(),
+        resolve_layout(&via_notation).kinds()
+    );
+}
+
+/// Guards `Position::canonicalize` regressions: a position parsed from text, canonicalized, and
+/// re-emitted must parse back to the exact same (already-canonical) position.
+#[test]
+fn position_round_trips_through_text_after_canonicalize() {
+    let layout = PieceLayout::from_text("QQN").unwrap();
+    let abs_king = Coord::new(3, -4);
+
+    let (_, mut pos) = Position::from_text("k3,-4 Q5,-2 Q1,-9 N4,-3", &layout).unwrap();
+    pos.canonicalize(&layout);
+
+    let text = pos.to_text(abs_king, &layout);
+    let (parsed_king, mut reparsed) = Position::from_text(&text, &layout).unwrap();
+    reparsed.canonicalize(&layout);
+
+    assert_eq!(parsed_king, abs_king);
+    assert_eq!(pos, reparsed);
+}
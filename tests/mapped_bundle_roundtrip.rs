@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use infinite_chess::scenarios;
+use infinite_chess::solution::{export_bundle, load_bundle, ExportOptions, MappedBundle};
+
+fn unique_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join("infinite_chess_tests").join(name);
+    let _ = fs::create_dir_all(&base);
+
+    let pid = std::process::id();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    for i in 0..1000u32 {
+        let p = base.join(format!("{pid}-{nanos}-{i}"));
+        if fs::create_dir(&p).is_ok() {
+            return p;
+        }
+    }
+
+    panic!(
+        "failed to create a unique temp dir under {}",
+        base.display()
+    );
+}
+
+#[test]
+fn mapped_bundle_agrees_with_loaded_bundle_for_three_rooks() {
+    let dir = unique_temp_dir("mapped_bundle_roundtrip");
+
+    let scn = scenarios::three_rooks_bound2_mb1();
+    let mut opts = ExportOptions::default();
+    opts.force = true;
+    // `MappedBundle` only supports the fixed-width encoding.
+    opts.compress = false;
+    let _bundle = export_bundle(&scn, &dir, opts).unwrap();
+
+    let loaded = load_bundle(&dir).unwrap();
+    let mapped = MappedBundle::open(&dir).unwrap();
+
+    assert_eq!(mapped.state_count(), loaded.states.len());
+
+    for (id, expected_state) in loaded.states.iter().enumerate() {
+        let id = id as u32;
+        let state = mapped
+            .state_at(id)
+            .expect("state id within range must decode");
+        assert_eq!(&state, expected_state);
+
+        let expected_transitions = &loaded.transitions[id as usize];
+        for (dir_idx, &expected_dst) in expected_transitions.iter().enumerate() {
+            let expected = (expected_dst != u32::MAX).then_some(expected_dst);
+            assert_eq!(mapped.successor(id, dir_idx), expected);
+        }
+    }
+
+    assert_eq!(mapped.state_at(mapped.state_count() as u32), None);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use infinite_chess::chess::layout::PieceLayout;
+use infinite_chess::chess::rules::Rules;
+use infinite_chess::core::coord::Coord;
+use infinite_chess::core::position::{Position, MAX_PIECES};
+use infinite_chess::core::square::Square;
+use infinite_chess::scenario::{
+    CacheMode, CandidateGeneration, NoLaws, NoPreferences, ResourceLimits, Scenario, Side,
+    StartState, State,
+};
+use infinite_chess::scenarios::BuiltinDomain;
+use infinite_chess::search::forced_mate::forced_mate_bounded;
+use infinite_chess::search::tablebase::{write_tablebase, Tablebase, Wdl};
+use infinite_chess::search::universe::try_for_each_state_in_abs_box;
+use rustc_hash::FxHashSet;
+
+fn unique_temp_path(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join("infinite_chess_tests");
+    let _ = fs::create_dir_all(&base);
+
+    let pid = std::process::id();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    for i in 0..1000u32 {
+        let p = base.join(format!("{name}-{pid}-{nanos}-{i}.ictb"));
+        if !p.exists() {
+            return p;
+        }
+    }
+
+    panic!(
+        "failed to create a unique temp path under {}",
+        base.display()
+    );
+}
+
+fn captured_start(layout: &PieceLayout) -> Position {
+    let squares = [Square::NONE; MAX_PIECES];
+    let mut pos = Position::new(layout.piece_count(), squares);
+    pos.canonicalize(layout);
+    pos
+}
+
+#[test]
+fn tablebase_roundtrips_dtm_and_wdl_for_three_rooks() {
+    let bound = 2;
+    let layout = PieceLayout::from_counts(false, 0, 3, 0, 0);
+    let rules = Rules::new(layout.clone(), 1);
+
+    let scn = Scenario {
+        name: "mate_rrr_abs_box_tablebase",
+        rules,
+        white_can_pass: false,
+        track_abs_king: true,
+        start: StartState {
+            to_move: Side::Black,
+            state: State::new(Coord::ORIGIN, captured_start(&layout)),
+        },
+        candidates: CandidateGeneration::InAbsBox {
+            bound,
+            allow_captures: true,
+        },
+        domain: BuiltinDomain::AbsBox { bound },
+        laws: NoLaws,
+        preferences: NoPreferences,
+        limits: ResourceLimits::default(),
+        cache_mode: CacheMode::None,
+        remove_stalemates: false,
+    };
+
+    let result = forced_mate_bounded(&scn, true, false).unwrap();
+    assert!(!result.winning_btm.is_empty());
+
+    let mut universe: FxHashSet<State> = FxHashSet::default();
+    try_for_each_state_in_abs_box::<std::convert::Infallible>(&scn.rules, bound, true, |s| {
+        if scn.rules.is_legal_position(&s.pos) && scn.domain.inside(&s) {
+            universe.insert(s);
+        }
+        Ok(())
+    })
+    .unwrap();
+    let universe: Vec<State> = universe.into_iter().collect();
+
+    // More states than one checkpoint stride, so the round-trip exercises `dtm_at` resuming from
+    // a non-zero checkpoint rather than only ever decoding from the start of the stream.
+    assert!(universe.len() > 64);
+
+    let path = unique_temp_path("tablebase_roundtrip");
+    write_tablebase(&path, &layout, bound, true, &universe, &result).unwrap();
+
+    let tb = Tablebase::open(&path).unwrap();
+    assert_eq!(tb.bound(), bound);
+    assert!(tb.allow_captures());
+    assert_eq!(tb.len(), universe.len());
+
+    let dtm = result.dtm.as_ref().expect("DTM requested");
+    for s in universe.iter() {
+        let (wdl, dtm_value) = tb.probe(s).expect("state from the universe must probe");
+        match dtm.get(s) {
+            Some(&expected_dtm) => {
+                assert_eq!(wdl, Wdl::Win);
+                assert_eq!(dtm_value, expected_dtm);
+            }
+            None => {
+                assert_eq!(wdl, Wdl::Draw);
+                assert_eq!(dtm_value, 0);
+            }
+        }
+    }
+
+    let _ = fs::remove_file(&path);
+}
@@ -129,3 +129,47 @@ fn always_accepting_toy_has_tempo_equal_trap() {
     assert_eq!(tempo.len(), trap.len());
     assert!(tempo.is_subset(&trap));
 }
+
+#[test]
+fn parallel_attractor_agrees_with_sequential() {
+    // `ResourceLimits::parallel_attractor` is never set `true` by any other test; this exercises
+    // `attractor_white_parallel`/`attractor_black_parallel` and checks they find the same Büchi
+    // winning region as the sequential, worklist-based attractors.
+    let bound = 2;
+    let layout = PieceLayout::from_counts(false, 0, 1, 0, 0); // R
+    let rules = Rules::new(layout.clone(), 2);
+
+    let mk = |parallel_attractor: bool| Scenario {
+        name: "tempo_parallel_attractor_abs_box_toy",
+        rules: rules.clone(),
+        white_can_pass: true,
+        track_abs_king: true,
+        start: StartState {
+            to_move: Side::Black,
+            state: State::new(Coord::ORIGIN, captured_start(&layout)),
+        },
+        candidates: CandidateGeneration::InAbsBox {
+            bound,
+            allow_captures: true,
+        },
+        domain: BuiltinDomain::AbsBox { bound },
+        laws: KeepKingInAbsBox { bound },
+        preferences: NoPreferences,
+        limits: ResourceLimits {
+            parallel_attractor,
+            ..ResourceLimits::default()
+        },
+        cache_mode: CacheMode::None,
+        remove_stalemates: false,
+    };
+
+    let scn_sequential = mk(false);
+    let trap = maximal_inescapable_trap(&scn_sequential).unwrap();
+    assert!(!trap.is_empty(), "toy should have a non-empty trap");
+    let tempo_sequential = maximal_tempo_trap(&scn_sequential, &trap).unwrap();
+
+    let scn_parallel = mk(true);
+    let tempo_parallel = maximal_tempo_trap(&scn_parallel, &trap).unwrap();
+
+    assert_eq!(tempo_sequential, tempo_parallel);
+}
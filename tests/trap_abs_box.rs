@@ -7,8 +7,9 @@ use infinite_chess::scenario::{
     CacheMode, CandidateGeneration, NoLaws, NoPreferences, ResourceLimits, Scenario, Side,
     StartState, State,
 };
+use infinite_chess::scenarios;
 use infinite_chess::scenarios::BuiltinDomain;
-use infinite_chess::search::trap::maximal_inescapable_trap;
+use infinite_chess::search::trap::{maximal_inescapable_trap, maximal_inescapable_trap_parallel};
 
 fn captured_start(layout: &PieceLayout) -> Position {
     let squares = [Square::NONE; MAX_PIECES];
@@ -111,3 +112,13 @@ fn abs_box_no_pieces_trap_stays_empty_when_bound_grows() {
     assert!(maximal_inescapable_trap(&mk(1, rules1)).unwrap().is_empty());
     assert!(maximal_inescapable_trap(&mk(2, rules2)).unwrap().is_empty());
 }
+
+#[test]
+fn parallel_trap_agrees_with_sequential_for_three_rooks_bound2_mb1() {
+    let scn = scenarios::three_rooks_bound2_mb1();
+
+    let sequential = maximal_inescapable_trap(&scn).unwrap();
+    let parallel = maximal_inescapable_trap_parallel(&scn, 4).unwrap();
+
+    assert_eq!(sequential, parallel);
+}
@@ -1,4 +1,5 @@
 use infinite_chess::chess::layout::PieceLayout;
+use infinite_chess::chess::rules::Rules;
 use infinite_chess::core::coord::Coord;
 use infinite_chess::core::position::{Position, MAX_PIECES};
 use infinite_chess::core::square::Square;
@@ -9,10 +10,11 @@ use rustc_hash::FxHashSet;
 #[test]
 fn universe_size_sanity_no_pieces() {
     let layout = PieceLayout::from_counts(false, 0, 0, 0, 0);
+    let rules = Rules::new(layout, 1);
     let bound = 2;
 
     let mut count = 0usize;
-    for_each_state_in_abs_box(&layout, bound, true, |_| count += 1);
+    for_each_state_in_abs_box(&rules, bound, true, |_| count += 1);
 
     let side = (2 * bound + 1) as usize;
     assert_eq!(count, side * side);
@@ -21,9 +23,10 @@ fn universe_size_sanity_no_pieces() {
 #[test]
 fn enumerated_states_respect_abs_box_membership() {
     let layout = PieceLayout::from_counts(true, 0, 1, 1, 1); // K R B N
+    let rules = Rules::new(layout, 1);
     let bound = 1;
 
-    for_each_state_in_abs_box(&layout, bound, true, |s| {
+    for_each_state_in_abs_box(&rules, bound, true, |s| {
         assert!(s.abs_king.in_linf_bound(bound));
         for (_, sq) in s.pos.iter_present() {
             assert_ne!(sq.coord(), Coord::ORIGIN);
@@ -36,10 +39,11 @@ fn enumerated_states_respect_abs_box_membership() {
 #[test]
 fn king_on_boundary_has_out_of_universe_moves() {
     let layout = PieceLayout::from_counts(false, 0, 0, 0, 0);
+    let rules = Rules::new(layout, 1);
     let bound = 1;
 
     let mut universe: FxHashSet<State> = FxHashSet::default();
-    for_each_state_in_abs_box(&layout, bound, true, |s| {
+    for_each_state_in_abs_box(&rules, bound, true, |s| {
         universe.insert(s);
     });
 